@@ -0,0 +1,22 @@
+use getset_client::query_capabilities;
+use log::info;
+use params::{Capabilities, GETSET_MAX_VERSION, GETSET_MIN_VERSION};
+use std::io;
+use std::net::SocketAddr;
+
+/// Query the frame versions and ops a node supports, the request is
+/// always sent as v1 so any node can answer it
+pub fn capabilities(address: SocketAddr) -> io::Result<Capabilities> {
+    info!("Capabilities of {}", address);
+
+    let caps = query_capabilities(address)?;
+
+    println!("{}", caps);
+    let local = Capabilities::new(GETSET_MIN_VERSION, GETSET_MAX_VERSION);
+    match local.negotiate(&caps) {
+        Some(v) => println!("Negotiated version: {}", v),
+        None => println!("No common version"),
+    }
+
+    Ok(caps)
+}
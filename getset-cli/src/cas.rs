@@ -0,0 +1,42 @@
+use crate::print_response;
+use getset_client::{invalid_data, transact, Keys};
+use log::info;
+use params::{
+    GetSetFrame, GetSetOp, GetSetPayloadType, ParameterId, ParameterValue, Request, Response,
+    SetResult, GETSET_FLAG_CRC32, GETSET_VERSION_2, NODE_ID_ANONYMOUS,
+};
+use std::io;
+use std::net::SocketAddr;
+
+/// Set each parameter only if its current value is the expected one,
+/// returns the per-parameter results reported by the node
+pub fn compare_and_set(
+    address: SocketAddr,
+    entries: &[(ParameterId, ParameterValue, ParameterValue)],
+    keys: &Keys,
+) -> io::Result<Vec<SetResult>> {
+    let mut req = Request::new(
+        NODE_ID_ANONYMOUS,
+        GETSET_FLAG_CRC32,
+        GetSetOp::CompareAndSet,
+        GetSetPayloadType::ParameterListPacket,
+    );
+    req.set_version(GETSET_VERSION_2);
+    for (id, expected, value) in entries {
+        info!(
+            "Compare and set parameter ID {} Expected {} Value {} at {}",
+            id, expected, value, address
+        );
+        req.push_compare_and_set(*id, *expected, *value)
+            .map_err(invalid_data)?;
+    }
+
+    let bytes = transact(address, &mut req, keys)?;
+
+    let frame = GetSetFrame::new_checked(&bytes[..]).map_err(invalid_data)?;
+    info!("{}", frame);
+    let resp = Response::parse(&frame).map_err(invalid_data)?;
+    print_response(&resp);
+
+    Ok(resp.results().to_vec())
+}
@@ -0,0 +1,10 @@
+use getset_client::{describe_all, Keys};
+use std::io;
+use std::net::SocketAddr;
+
+pub fn describe(address: SocketAddr, keys: &Keys) -> io::Result<()> {
+    for d in describe_all(address, keys)? {
+        println!("{}", d);
+    }
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use param_desc::param_enum::param_enum_name;
+use param_desc::param_flags::{param_flag_names, write_flag_names};
+use param_desc::param_name::param_name;
+use params::{Parameter, ParameterId, ParameterValue, Response};
+
+/// Print a response, enum and flags values are also printed symbolically
+pub fn print_response(resp: &Response) {
+    println!("{}", resp);
+    for p in resp.parameters() {
+        if let Some(symbolic) = symbolic_value(p) {
+            println!("{} = {}", display_name(p.id()), symbolic);
+        }
+    }
+    for r in resp.results() {
+        println!("{} : {}", display_name(r.id()), r.status());
+    }
+    match resp.committed() {
+        Some(true) => println!("Committed"),
+        Some(false) => println!("Rolled back"),
+        None => (),
+    }
+}
+
+/// Parameter name if known, otherwise its ID
+pub fn display_name(id: ParameterId) -> String {
+    param_name(id).map_or(format!("{}", id), String::from)
+}
+
+/// Variant name of an enum, or the bit names of a flags parameter value
+pub fn symbolic_value(p: &Parameter) -> Option<String> {
+    if let Some(name) = param_enum_name(p.id(), p.value()) {
+        return Some(String::from(name));
+    }
+    match (param_flag_names(p.id()), p.value()) {
+        (Some(names), ParameterValue::U32(bits)) => {
+            let mut s = String::new();
+            write_flag_names(&mut s, names, bits).ok()?;
+            Some(s)
+        }
+        _ => None,
+    }
+}
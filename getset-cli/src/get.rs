@@ -1,42 +1,33 @@
+use crate::print_response;
+use getset_client::{invalid_data, transact, Keys};
 use log::info;
 use params::{
-    GetSetFlags, GetSetFrame, GetSetOp, GetSetPayloadType, ParameterId, Request, Response,
-    NODE_ID_ANONYMOUS,
+    Error, GetSetFrame, GetSetOp, GetSetPayloadType, ParameterId, Request, Response,
+    GETSET_FLAG_CRC32, GETSET_VERSION_2, NODE_ID_ANONYMOUS,
 };
 use std::io;
-use std::io::prelude::*;
 use std::net::SocketAddr;
-use std::net::TcpStream;
 
-pub fn get(address: SocketAddr, id: ParameterId) -> io::Result<()> {
+pub fn get(address: SocketAddr, id: ParameterId, keys: &Keys) -> io::Result<()> {
     info!("Get parameter ID {} at {}", id, address);
-    let mut buf: Vec<u8> = vec![0; 1500];
 
-    let mut frame = GetSetFrame::new_unchecked(&mut buf[..]);
     let mut req = Request::new(
         NODE_ID_ANONYMOUS,
-        GetSetFlags::default(),
+        GETSET_FLAG_CRC32,
         GetSetOp::Get,
         GetSetPayloadType::ParameterIdListPacket,
     );
+    req.set_version(GETSET_VERSION_2);
     req.push_id(id).unwrap();
-    req.emit(&mut frame).unwrap();
-    let wire_size = req.wire_size();
 
-    let mut stream = TcpStream::connect(address)?;
+    let bytes = transact(address, &mut req, keys)?;
 
-    info!("Sending {} bytes : {}", wire_size, req);
-    stream.write(&buf[..wire_size])?;
-
-    let bytes_read = stream.read(&mut buf[..])?;
-
-    info!("Recv'd {} bytes", bytes_read);
-
-    if let Ok(frame) = GetSetFrame::new_checked(&buf[..bytes_read]) {
-        info!("{}", frame);
-        if let Ok(resp) = Response::parse(&frame) {
-            println!("{}", resp);
-        }
+    let frame = GetSetFrame::new_checked(&bytes[..]).map_err(invalid_data)?;
+    info!("{}", frame);
+    match Response::parse(&frame) {
+        Ok(resp) => print_response(&resp),
+        Err(Error::Remote(e)) => eprintln!("{}", e),
+        Err(e) => return Err(invalid_data(e)),
     }
 
     Ok(())
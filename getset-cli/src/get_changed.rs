@@ -0,0 +1,77 @@
+use crate::{display_name, symbolic_value};
+use getset_client::{invalid_data, transact, Keys};
+use log::info;
+use params::{
+    GetSetFrame, GetSetOp, GetSetPayloadType, Parameter, ParameterId, Request, Response,
+    GETSET_FLAG_CRC32, GETSET_VERSION_2, NODE_ID_ANONYMOUS,
+};
+use std::io;
+use std::net::SocketAddr;
+
+/// Parameters a node changed at or after some node time
+#[derive(Clone, PartialEq, Debug)]
+pub struct Changed {
+    /// Node time the first page was answered at, pass it as the next
+    /// since to only get newer changes
+    pub node_time_ms: u64,
+    pub parameters: Vec<Parameter>,
+}
+
+/// Fetch the parameters changed at or after `since_ms`, pages are fetched in
+/// ID order, each starting after the last ID of the previous one, until the
+/// node stops setting the more flag
+pub fn get_changed_since(address: SocketAddr, since_ms: u64, keys: &Keys) -> io::Result<Changed> {
+    info!(
+        "Get parameters changed since {} ms at {}",
+        since_ms, address
+    );
+
+    let mut changed = Changed {
+        node_time_ms: 0,
+        parameters: Vec::new(),
+    };
+    let mut version = GETSET_VERSION_2;
+    let mut start_id = ParameterId::default();
+    loop {
+        let mut req = Request::new(
+            NODE_ID_ANONYMOUS,
+            GETSET_FLAG_CRC32,
+            GetSetOp::GetChanged,
+            GetSetPayloadType::SincePacket,
+        );
+        req.set_version(version);
+        req.set_since_ms(since_ms);
+        req.set_start_id(start_id);
+
+        let bytes = transact(address, &mut req, keys)?;
+        version = req.version();
+
+        let frame = GetSetFrame::new_checked(&bytes[..]).map_err(invalid_data)?;
+        info!("{}", frame);
+        let resp = Response::parse(&frame).map_err(invalid_data)?;
+        if changed.parameters.is_empty() {
+            changed.node_time_ms = resp.node_time_ms();
+        }
+        changed.parameters.extend_from_slice(resp.parameters());
+        let next_id = resp
+            .parameters()
+            .last()
+            .and_then(|p| u32::from(p.id()).checked_add(1));
+        match next_id {
+            Some(id) if resp.more() => start_id = ParameterId::new(id),
+            _ => return Ok(changed),
+        }
+    }
+}
+
+pub fn get_changed(address: SocketAddr, since_ms: u64, keys: &Keys) -> io::Result<()> {
+    let changed = get_changed_since(address, since_ms, keys)?;
+    for p in changed.parameters.iter() {
+        println!("{}", p);
+        if let Some(symbolic) = symbolic_value(p) {
+            println!("{} = {}", display_name(p.id()), symbolic);
+        }
+    }
+    println!("Node time {} ms", changed.node_time_ms);
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use getset_client::{invalid_data, transact, Keys};
+use log::info;
+use params::{
+    GetSetFrame, GetSetOp, GetSetPayloadType, Parameter, ParameterFlags, ParameterId,
+    ParameterValue, Request, Response, GETSET_FLAG_CRC32, GETSET_VERSION_2, NODE_ID_ANONYMOUS,
+};
+use std::io;
+use std::net::SocketAddr;
+
+/// Invoke a command parameter, the argument and returned result are
+/// Notification when the command takes or returns none
+pub fn invoke(
+    address: SocketAddr,
+    id: ParameterId,
+    arg: ParameterValue,
+    keys: &Keys,
+) -> io::Result<ParameterValue> {
+    info!("Invoke command ID {} Arg {} at {}", id, arg, address);
+
+    let mut req = Request::new(
+        NODE_ID_ANONYMOUS,
+        GETSET_FLAG_CRC32,
+        GetSetOp::Invoke,
+        GetSetPayloadType::ParameterListPacket,
+    );
+    req.set_version(GETSET_VERSION_2);
+    req.push_parameter(Parameter::new_with_value(
+        id,
+        ParameterFlags::default(),
+        arg,
+    ))
+    .map_err(invalid_data)?;
+
+    let bytes = transact(address, &mut req, keys)?;
+
+    let frame = GetSetFrame::new_checked(&bytes[..]).map_err(invalid_data)?;
+    info!("{}", frame);
+    let resp = Response::parse(&frame).map_err(invalid_data)?;
+    match resp.parameters() {
+        [p] if p.id() == id => Ok(p.value()),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Expected the command's result",
+        )),
+    }
+}
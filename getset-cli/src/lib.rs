@@ -1,9 +1,28 @@
+mod capabilities;
+mod cas;
+mod describe;
+mod display;
 mod get;
+mod get_changed;
+mod invoke;
 mod list_all;
 mod listen;
+mod ping;
+mod scan;
 mod set;
+mod subscribe;
 
+pub use capabilities::*;
+pub use cas::*;
+pub use describe::*;
+pub use display::*;
 pub use get::*;
+pub use get_changed::*;
+pub use getset_client::{transact, transact_stream, Keys};
+pub use invoke::*;
 pub use list_all::*;
 pub use listen::*;
+pub use ping::*;
+pub use scan::*;
 pub use set::*;
+pub use subscribe::*;
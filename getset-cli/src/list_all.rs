@@ -1,39 +1,54 @@
+use crate::print_response;
+use getset_client::{invalid_data, transact, Keys};
 use log::info;
 use params::{
-    GetSetFlags, GetSetFrame, GetSetOp, GetSetPayloadType, Request, Response, NODE_ID_ANONYMOUS,
+    Error, GetSetFrame, GetSetOp, GetSetPayloadType, Request, Response, GETSET_FLAG_CRC32,
+    GETSET_VERSION_2, NODE_ID_ANONYMOUS,
 };
 use std::io;
-use std::io::prelude::*;
 use std::net::SocketAddr;
-use std::net::TcpStream;
 
-pub fn list_all(address: SocketAddr) -> io::Result<()> {
+/// Pages are fetched until the node stops setting the more flag
+pub fn list_all(address: SocketAddr, keys: &Keys) -> io::Result<()> {
     info!("Listing all parameters at {}", address);
-    let mut buf: Vec<u8> = vec![0; 1500];
 
-    let mut frame = GetSetFrame::new_unchecked(&mut buf[..]);
-    let req = Request::new(
-        NODE_ID_ANONYMOUS,
-        GetSetFlags::default(),
-        GetSetOp::ListAll,
-        GetSetPayloadType::None,
-    );
-    req.emit(&mut frame).unwrap();
-    let wire_size = req.wire_size();
-
-    let mut stream = TcpStream::connect(address)?;
-
-    info!("Sending {} bytes : {}", wire_size, req);
-    stream.write(&buf[..wire_size])?;
-
-    let bytes_read = stream.read(&mut buf[..])?;
-
-    info!("Recv'd {} bytes", bytes_read);
-
-    if let Ok(frame) = GetSetFrame::new_checked(&buf[..bytes_read]) {
+    let mut version = GETSET_VERSION_2;
+    let mut offset: u16 = 0;
+    loop {
+        let payload_type = if offset == 0 {
+            GetSetPayloadType::None
+        } else {
+            GetSetPayloadType::PagePacket
+        };
+        let mut req = Request::new(
+            NODE_ID_ANONYMOUS,
+            GETSET_FLAG_CRC32,
+            GetSetOp::ListAll,
+            payload_type,
+        );
+        req.set_version(version);
+        req.set_offset(offset);
+
+        let bytes = transact(address, &mut req, keys)?;
+        version = req.version();
+
+        let frame = GetSetFrame::new_checked(&bytes[..]).map_err(invalid_data)?;
         info!("{}", frame);
-        if let Ok(resp) = Response::parse(&frame) {
-            println!("{}", resp);
+        match Response::parse(&frame) {
+            Ok(resp) => {
+                print_response(&resp);
+                let count = resp.parameters().len() as u16;
+                if !resp.more() || count == 0 {
+                    break;
+                }
+                offset = offset.saturating_add(count);
+            }
+            // Errors reported by the node end the listing
+            Err(Error::Remote(e)) => {
+                eprintln!("{}", e);
+                break;
+            }
+            Err(e) => return Err(invalid_data(e)),
         }
     }
 
@@ -1,11 +1,15 @@
-use log::info;
-use params::{GetSetFrame, Response};
+use crate::print_response;
+use log::{info, warn};
+use params::{Cipher, CipherKey, Error, GetSetFrame, Response, GETSET_FLAG_ENCRYPTED};
 use std::io;
 use std::net::SocketAddr;
 use std::net::UdpSocket;
 
-pub fn start_listening(address: SocketAddr) -> io::Result<()> {
+/// Encrypted broadcasts are decrypted with the key if given, dropped otherwise
+pub fn start_listening(address: SocketAddr, key: Option<&CipherKey>) -> io::Result<()> {
     info!("Listening for broadcast GetSetFrame's on {}", address);
+    // Only opens frames, the salt is never used
+    let cipher = key.map(|key| Cipher::new(*key, 0));
     let socket = UdpSocket::bind(address)?;
     let mut buf: Vec<u8> = vec![0; 1500];
 
@@ -14,10 +18,29 @@ pub fn start_listening(address: SocketAddr) -> io::Result<()> {
         info!("Got {} bytes from {}", amt, src);
 
         if amt >= GetSetFrame::<&[u8]>::header_len() {
-            if let Ok(frame) = GetSetFrame::new_checked(&buf[..amt]) {
+            let frame = GetSetFrame::new_checked(&mut buf[..amt]).and_then(|mut frame| {
+                if frame.flags() & GETSET_FLAG_ENCRYPTED != 0 {
+                    cipher
+                        .as_ref()
+                        .ok_or(Error::EncryptionRequired)?
+                        .open(&mut frame)?;
+                }
+                Ok(GetSetFrame::new_unchecked(frame.into_inner() as &[u8]))
+            });
+            match frame {
+                Err(Error::WireChecksum) => warn!("Dropping frame from {} with a bad CRC32", src),
+                Err(Error::EncryptionRequired) => {
+                    warn!("Dropping encrypted frame from {}, no key given", src)
+                }
+                Err(Error::DecryptFailed) => {
+                    warn!("Dropping frame from {} that fails to decrypt", src)
+                }
+                _ => (),
+            }
+            if let Ok(frame) = frame {
                 info!("{}", frame);
                 if let Ok(resp) = Response::parse(&frame) {
-                    println!("{}", resp);
+                    print_response(&resp);
                 }
             }
         }
@@ -1,7 +1,17 @@
+use getset_client::read_key;
 use log::Level;
-use params::{ParameterValue, ParameterValueTypeId};
+use param_desc::param_command::param_command_by_name;
+use param_desc::param_enum::{param_enum_names, param_enum_value};
+use param_desc::param_flags::{param_flag_names, param_flags_value};
+use params::{
+    AuthKey, CipherKey, ParameterId, ParameterValue, ParameterValueArray, ParameterValueBytes,
+    ParameterValueStr, ParameterValueTypeId,
+};
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process;
 use std::str::FromStr;
+use std::time::Duration;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -10,6 +20,15 @@ struct CLIOptions {
     #[structopt(short = "v", long = "verbose")]
     verbose: bool,
 
+    /// Sign requests with the shared key in this file, required for Set
+    #[structopt(short = "k", long, parse(from_os_str))]
+    key_file: Option<PathBuf>,
+
+    /// Encrypt requests and decrypt replies and broadcasts with the node's
+    /// key in this file
+    #[structopt(short = "e", long, parse(from_os_str))]
+    cipher_key_file: Option<PathBuf>,
+
     #[structopt(subcommand)]
     cmd: Command,
 }
@@ -23,6 +42,38 @@ enum Command {
         address: SocketAddr,
     },
 
+    /// Find the nodes on the subnet, they announce themselves on the
+    /// broadcast port so listen can't run on the same host meanwhile
+    Scan {
+        /// UDP broadcast address:port
+        #[structopt(short = "a", long, default_value = "255.255.255.255:9876")]
+        address: SocketAddr,
+
+        /// Seconds to wait for the nodes to announce themselves
+        #[structopt(short = "t", long, default_value = "2")]
+        timeout: u64,
+    },
+
+    /// Measure the round-trip time to a node
+    Ping {
+        /// TCP address:port, or the broadcast port (192.168.1.39:9876)
+        /// with --udp
+        #[structopt(short = "a", long)]
+        address: Option<SocketAddr>,
+
+        /// Ping over UDP instead of TCP
+        #[structopt(long)]
+        udp: bool,
+
+        /// Number of pings
+        #[structopt(short = "c", long, default_value = "4")]
+        count: usize,
+
+        /// Seconds to wait for each reply
+        #[structopt(short = "t", long, default_value = "1")]
+        timeout: u64,
+    },
+
     /// List all parameters
     ListAll {
         /// TCP address:port
@@ -30,6 +81,20 @@ enum Command {
         address: SocketAddr,
     },
 
+    /// Query the protocol versions and ops a node supports
+    Capabilities {
+        /// TCP address:port
+        #[structopt(short = "a", long, default_value = "192.168.1.39:9877")]
+        address: SocketAddr,
+    },
+
+    /// Describe all parameters as reported by the node
+    Describe {
+        /// TCP address:port
+        #[structopt(short = "a", long, default_value = "192.168.1.39:9877")]
+        address: SocketAddr,
+    },
+
     /// Get parameter(s) by ID
     Get {
         /// TCP address:port
@@ -41,31 +106,124 @@ enum Command {
         id: u32,
     },
 
+    /// Get the parameters changed at or after a node time
+    GetChanged {
+        /// TCP address:port
+        #[structopt(short = "a", long, default_value = "192.168.1.39:9877")]
+        address: SocketAddr,
+
+        /// Node time in milliseconds, as printed by the previous get-changed
+        #[structopt(short = "s", long, default_value = "0")]
+        since: u64,
+    },
+
+    /// Stream changes of parameter(s) by ID as the node pushes them
+    Subscribe {
+        /// TCP address:port
+        #[structopt(short = "a", long, default_value = "192.168.1.39:9877")]
+        address: SocketAddr,
+
+        /// Parameter ID, repeat for each parameter
+        #[structopt(short = "i", long = "id", required = true, number_of_values = 1)]
+        ids: Vec<u32>,
+    },
+
+    /// Invoke a command parameter by name, commands must be signed
+    Invoke {
+        /// TCP address:port
+        #[structopt(short = "a", long, default_value = "192.168.1.39:9877")]
+        address: SocketAddr,
+
+        /// Command name (reboot)
+        name: String,
+
+        /// Command argument, given as for set
+        #[structopt(short = "v", long)]
+        value: Option<String>,
+    },
+
     /// Set parameter(s) by ID, value
     Set {
         /// TCP address:port
         #[structopt(short = "a", long, default_value = "192.168.1.39:9877")]
         address: SocketAddr,
 
-        /// Parameter ID
-        #[structopt(short = "i", long)]
-        id: u32,
+        /// Parameter ID, repeat with a type and value for each parameter
+        #[structopt(short = "i", long = "id", required = true, number_of_values = 1)]
+        ids: Vec<u32>,
 
-        /// Parameter value type
-        #[structopt(short = "t", long = "type", parse(try_from_str = parse_value_type))]
-        value_type: ParameterValueTypeId,
+        /// Parameter value type, arrays are given as type[len] (f32[4]),
+        /// enum parameters as enum and flags parameters as flags
+        #[structopt(
+            short = "t",
+            long = "type",
+            required = true,
+            number_of_values = 1,
+            parse(try_from_str = parse_value_type)
+        )]
+        value_types: Vec<ValueType>,
 
-        /// Parameter value
-        #[structopt(short = "v", long)]
-        value: String,
+        /// Parameter value, bytes are given in hex (0x0A0B, 0a:0b),
+        /// array elements are comma separated (1.0,2.0), enums by
+        /// variant name (heat) and flags by bit names (pump|fan)
+        #[structopt(short = "v", long = "value", required = true, number_of_values = 1)]
+        values: Vec<String>,
+
+        /// Apply all the parameters or none of them
+        #[structopt(long)]
+        atomic: bool,
+    },
+
+    /// Set parameter(s) by ID only if they hold the expected value
+    Cas {
+        /// TCP address:port
+        #[structopt(short = "a", long, default_value = "192.168.1.39:9877")]
+        address: SocketAddr,
+
+        /// Parameter ID, repeat with a type, expected and new value for
+        /// each parameter
+        #[structopt(short = "i", long = "id", required = true, number_of_values = 1)]
+        ids: Vec<u32>,
+
+        /// Parameter value type, given as for set
+        #[structopt(
+            short = "t",
+            long = "type",
+            required = true,
+            number_of_values = 1,
+            parse(try_from_str = parse_value_type)
+        )]
+        value_types: Vec<ValueType>,
+
+        /// Expected current value, given as for set
+        #[structopt(short = "e", long = "expected", required = true, number_of_values = 1)]
+        expected: Vec<String>,
+
+        /// New value, given as for set
+        #[structopt(short = "v", long = "value", required = true, number_of_values = 1)]
+        values: Vec<String>,
     },
 }
 
-fn parse_value_type(src: &str) -> Result<ParameterValueTypeId, String> {
-    match ParameterValueTypeId::from_str(src) {
-        Ok(t) => Ok(t),
-        _ => Err(format!("Invalid ParameterValueTypeId: {}", src)),
-    }
+#[derive(Debug)]
+enum ValueType {
+    Scalar(ParameterValueTypeId),
+    Array(ParameterValueTypeId, usize),
+    Enum,
+    Flags,
+}
+
+fn parse_value_type(src: &str) -> Result<ValueType, String> {
+    let t = if src == "enum" {
+        Ok(ValueType::Enum)
+    } else if src == "flags" {
+        Ok(ValueType::Flags)
+    } else if src.contains('[') {
+        ParameterValueArray::parse_type(src).map(|(elem, len)| ValueType::Array(elem, len))
+    } else {
+        ParameterValueTypeId::from_str(src).map(ValueType::Scalar)
+    };
+    t.map_err(|_| format!("Invalid ParameterValueTypeId: {}", src))
 }
 
 // TODO - lookup value type from param-desc/db instead of cli provided
@@ -78,29 +236,203 @@ fn main() {
         simple_logger::init_with_level(Level::Warn).unwrap();
     }
 
+    let keys = getset_cli::Keys {
+        auth: opts
+            .key_file
+            .map(|path| -> AuthKey { read_key(path).unwrap() }),
+        cipher: opts
+            .cipher_key_file
+            .map(|path| -> CipherKey { read_key(path).unwrap() }),
+    };
+
     match opts.cmd {
-        Command::Listen { address } => getset_cli::start_listening(address).unwrap(),
-        Command::ListAll { address } => getset_cli::list_all(address).unwrap(),
-        Command::Get { address, id } => getset_cli::get(address, id.into()).unwrap(),
-        Command::Set {
+        Command::Listen { address } => {
+            getset_cli::start_listening(address, keys.cipher.as_ref()).unwrap()
+        }
+        Command::Scan { address, timeout } => {
+            getset_cli::scan(address, Duration::from_secs(timeout)).unwrap()
+        }
+        Command::Ping {
             address,
-            id,
-            value_type,
+            udp,
+            count,
+            timeout,
+        } => {
+            let (transport, default_address) = if udp {
+                (getset_cli::Transport::Udp, "192.168.1.39:9876")
+            } else {
+                (getset_cli::Transport::Tcp, "192.168.1.39:9877")
+            };
+            let address = address.unwrap_or_else(|| default_address.parse().unwrap());
+            let stats = getset_cli::ping(
+                address,
+                transport,
+                count,
+                Duration::from_secs(timeout),
+                &keys,
+            )
+            .unwrap();
+            println!(
+                "{} sent, {} received, {:.1}% loss",
+                stats.sent,
+                stats.received(),
+                stats.loss()
+            );
+            if let (Some(min), Some(avg), Some(max)) = (stats.min(), stats.avg(), stats.max()) {
+                println!(
+                    "rtt min/avg/max = {:.3}/{:.3}/{:.3} ms",
+                    min.as_secs_f64() * 1000.0,
+                    avg.as_secs_f64() * 1000.0,
+                    max.as_secs_f64() * 1000.0
+                );
+            }
+            if stats.received() == 0 {
+                process::exit(1);
+            }
+        }
+        Command::ListAll { address } => getset_cli::list_all(address, &keys).unwrap(),
+        Command::Capabilities { address } => {
+            getset_cli::capabilities(address).unwrap();
+        }
+        Command::Describe { address } => getset_cli::describe(address, &keys).unwrap(),
+        Command::Get { address, id } => getset_cli::get(address, id.into(), &keys).unwrap(),
+        Command::GetChanged { address, since } => {
+            getset_cli::get_changed(address, since, &keys).unwrap()
+        }
+        Command::Subscribe { address, ids } => {
+            let ids: Vec<ParameterId> = ids.into_iter().map(ParameterId::from).collect();
+            getset_cli::subscribe(address, &ids, &keys).unwrap()
+        }
+        Command::Invoke {
+            address,
+            name,
             value,
         } => {
-            let value = match value_type {
-                ParameterValueTypeId::None => ParameterValue::None,
-                ParameterValueTypeId::Notification => ParameterValue::Notification,
-                ParameterValueTypeId::Bool => ParameterValue::Bool(bool::from_str(&value).unwrap()),
-                ParameterValueTypeId::U8 => ParameterValue::U8(u8::from_str(&value).unwrap()),
-                ParameterValueTypeId::I8 => ParameterValue::I8(i8::from_str(&value).unwrap()),
-                ParameterValueTypeId::U32 => ParameterValue::U32(u32::from_str(&value).unwrap()),
-                ParameterValueTypeId::I32 => ParameterValue::I32(i32::from_str(&value).unwrap()),
-                ParameterValueTypeId::U64 => ParameterValue::U64(u64::from_str(&value).unwrap()),
-                ParameterValueTypeId::I64 => ParameterValue::I64(i64::from_str(&value).unwrap()),
-                ParameterValueTypeId::F32 => ParameterValue::F32(f32::from_str(&value).unwrap()),
+            let command = param_command_by_name(&name)
+                .unwrap_or_else(|| panic!("Unknown command '{}'", name));
+            let id = u32::from(command.id());
+            let arg = match (command.arg(), value) {
+                (None, None) => ParameterValue::Notification,
+                (Some(zero), Some(v)) => {
+                    let t = match zero {
+                        ParameterValue::Array(a) => ValueType::Array(a.element_type_id(), a.len()),
+                        _ => ValueType::Scalar(zero.type_id()),
+                    };
+                    parse_value(id, &t, &v)
+                }
+                (None, Some(_)) => panic!("Command '{}' takes no argument", name),
+                (Some(zero), None) => {
+                    panic!("Command '{}' needs a {:?} argument", name, zero.type_id())
+                }
             };
-            getset_cli::set(address, id.into(), value).unwrap()
+            let result = getset_cli::invoke(address, command.id(), arg, &keys).unwrap();
+            if result != ParameterValue::Notification {
+                println!("{}", result);
+            }
+        }
+        Command::Set {
+            address,
+            ids,
+            value_types,
+            values,
+            atomic,
+        } => {
+            if ids.len() != value_types.len() || ids.len() != values.len() {
+                panic!("Each parameter ID needs a type and value");
+            }
+            let params: Vec<(ParameterId, ParameterValue)> = ids
+                .iter()
+                .zip(value_types.iter().zip(values.iter()))
+                .map(|(id, (t, v))| ((*id).into(), parse_value(*id, t, v)))
+                .collect();
+            let results = getset_cli::set_batch(address, &params, atomic, &keys).unwrap();
+            if results.is_empty() || results.iter().any(|r| !r.status().is_success()) {
+                process::exit(1);
+            }
+        }
+        Command::Cas {
+            address,
+            ids,
+            value_types,
+            expected,
+            values,
+        } => {
+            if ids.len() != value_types.len()
+                || ids.len() != expected.len()
+                || ids.len() != values.len()
+            {
+                panic!("Each parameter ID needs a type, expected value and value");
+            }
+            let entries: Vec<(ParameterId, ParameterValue, ParameterValue)> = ids
+                .iter()
+                .enumerate()
+                .map(|(index, id)| {
+                    let t = &value_types[index];
+                    (
+                        (*id).into(),
+                        parse_value(*id, t, &expected[index]),
+                        parse_value(*id, t, &values[index]),
+                    )
+                })
+                .collect();
+            let results = getset_cli::compare_and_set(address, &entries, &keys).unwrap();
+            if results.is_empty() || results.iter().any(|r| !r.status().is_success()) {
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// Parse a CLI value for the parameter
+fn parse_value(id: u32, value_type: &ValueType, value: &str) -> ParameterValue {
+    match *value_type {
+        ValueType::Array(elem, len) => {
+            let a = ParameterValueArray::parse(elem, value).unwrap();
+            if a.len() != len {
+                panic!("Expected {} array elements, got {}", len, a.len());
+            }
+            ParameterValue::Array(a)
+        }
+        ValueType::Enum => {
+            let names = param_enum_names(id.into())
+                .unwrap_or_else(|| panic!("Parameter ID {} is not an enum", id));
+            param_enum_value(id.into(), value).unwrap_or_else(|| {
+                panic!("Invalid variant '{}', expected one of {:?}", value, names)
+            })
+        }
+        ValueType::Flags => {
+            let names = param_flag_names(id.into())
+                .unwrap_or_else(|| panic!("Parameter ID {} is not a flags type", id));
+            param_flags_value(id.into(), value).unwrap_or_else(|| {
+                panic!(
+                    "Invalid bit set '{}', expected names from {:?}",
+                    value, names
+                )
+            })
         }
+        ValueType::Scalar(t) => match t {
+            ParameterValueTypeId::None => ParameterValue::None,
+            ParameterValueTypeId::Notification => ParameterValue::Notification,
+            ParameterValueTypeId::Bool => ParameterValue::Bool(bool::from_str(value).unwrap()),
+            ParameterValueTypeId::U8 => ParameterValue::U8(u8::from_str(value).unwrap()),
+            ParameterValueTypeId::I8 => ParameterValue::I8(i8::from_str(value).unwrap()),
+            ParameterValueTypeId::U16 => ParameterValue::U16(u16::from_str(value).unwrap()),
+            ParameterValueTypeId::I16 => ParameterValue::I16(i16::from_str(value).unwrap()),
+            ParameterValueTypeId::U32 => ParameterValue::U32(u32::from_str(value).unwrap()),
+            ParameterValueTypeId::I32 => ParameterValue::I32(i32::from_str(value).unwrap()),
+            ParameterValueTypeId::U64 => ParameterValue::U64(u64::from_str(value).unwrap()),
+            ParameterValueTypeId::I64 => ParameterValue::I64(i64::from_str(value).unwrap()),
+            ParameterValueTypeId::F32 => ParameterValue::F32(f32::from_str(value).unwrap()),
+            ParameterValueTypeId::F64 => ParameterValue::F64(f64::from_str(value).unwrap()),
+            ParameterValueTypeId::Str => {
+                ParameterValue::Str(ParameterValueStr::from_str(value).unwrap())
+            }
+            ParameterValueTypeId::Bytes => {
+                ParameterValue::Bytes(ParameterValueBytes::from_hex(value).unwrap())
+            }
+            ParameterValueTypeId::Array => {
+                panic!("Array types require an element type and length (f32[4])")
+            }
+        },
     }
 }
@@ -0,0 +1,212 @@
+use getset_client::{answers_v2, invalid_data, new_cipher, now_micros, read_frame, Keys};
+use log::{info, warn};
+use params::{
+    Cipher, GetSetFrame, GetSetOp, GetSetPayloadType, GetSetSequence, Request, Response,
+    GETSET_FLAG_CRC32, GETSET_FLAG_ENCRYPTED, GETSET_VERSION_2, NODE_ID_ANONYMOUS,
+};
+use std::io;
+use std::io::prelude::*;
+use std::net::{Ipv4Addr, SocketAddr, TcpStream, UdpSocket};
+use std::time::{Duration, Instant};
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Transport {
+    Tcp,
+    Udp,
+}
+
+/// Round-trip times of the pings that were answered
+#[derive(Clone, PartialEq, Debug)]
+pub struct PingStats {
+    pub sent: usize,
+    pub rtts: Vec<Duration>,
+}
+
+impl PingStats {
+    pub fn received(&self) -> usize {
+        self.rtts.len()
+    }
+
+    /// Percentage of the pings that went unanswered
+    pub fn loss(&self) -> f64 {
+        if self.sent == 0 {
+            0.0
+        } else {
+            100.0 * (self.sent - self.received()) as f64 / self.sent as f64
+        }
+    }
+
+    pub fn min(&self) -> Option<Duration> {
+        self.rtts.iter().min().copied()
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        self.rtts.iter().max().copied()
+    }
+
+    pub fn avg(&self) -> Option<Duration> {
+        if self.rtts.is_empty() {
+            None
+        } else {
+            Some(self.rtts.iter().sum::<Duration>() / self.rtts.len() as u32)
+        }
+    }
+}
+
+/// Connection the pings are exchanged over
+enum Link {
+    Tcp(TcpStream),
+    Udp(UdpSocket),
+}
+
+impl Link {
+    fn send(&mut self, bytes: &[u8]) -> io::Result<()> {
+        match self {
+            Link::Tcp(stream) => stream.write_all(bytes),
+            Link::Udp(socket) => socket.send(bytes).map(|_| ()),
+        }
+    }
+
+    fn recv(&mut self) -> io::Result<Vec<u8>> {
+        match self {
+            Link::Tcp(stream) => read_frame(stream),
+            Link::Udp(socket) => {
+                let mut bytes = vec![0; 1500];
+                let amt = socket.recv(&mut bytes)?;
+                bytes.truncate(amt);
+                Ok(bytes)
+            }
+        }
+    }
+}
+
+/// Ping the node count times, each attempt waits up to timeout for its
+/// reply. TCP pings share one connection, UDP pings are sent to the node's
+/// broadcast port.
+pub fn ping(
+    address: SocketAddr,
+    transport: Transport,
+    count: usize,
+    timeout: Duration,
+    keys: &Keys,
+) -> io::Result<PingStats> {
+    info!("Ping {} over {:?}", address, transport);
+
+    let mut link = match transport {
+        Transport::Tcp => {
+            let stream = TcpStream::connect_timeout(&address, timeout)?;
+            stream.set_nodelay(true)?;
+            Link::Tcp(stream)
+        }
+        Transport::Udp => {
+            let socket = UdpSocket::bind(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0))?;
+            socket.connect(address)?;
+            Link::Udp(socket)
+        }
+    };
+    let mut cipher = keys.cipher.map(|key| {
+        let mut cipher = new_cipher(key);
+        cipher.set_counter(now_micros());
+        cipher
+    });
+
+    let mut stats = PingStats {
+        sent: 0,
+        rtts: Vec::new(),
+    };
+    for sequence in 1..=count as GetSetSequence {
+        let bytes = ping_request(sequence, cipher.as_mut())?;
+        let start = Instant::now();
+        link.send(&bytes)?;
+        stats.sent += 1;
+
+        match wait_reply(&mut link, sequence, start + timeout, cipher.as_ref())? {
+            Some(resp) => {
+                let rtt = start.elapsed();
+                println!(
+                    "{} bytes from {}: seq={} time={:.3} ms uptime={} s node_time={} ms",
+                    resp.wire_size(),
+                    address,
+                    sequence,
+                    rtt.as_secs_f64() * 1000.0,
+                    resp.uptime(),
+                    resp.node_time_ms()
+                );
+                stats.rtts.push(rtt);
+            }
+            None => println!("Request timeout for seq={}", sequence),
+        }
+    }
+
+    Ok(stats)
+}
+
+/// The attempt's sequence number is also the echoed data
+fn ping_request(sequence: GetSetSequence, cipher: Option<&mut Cipher>) -> io::Result<Vec<u8>> {
+    let mut req = Request::new(
+        NODE_ID_ANONYMOUS,
+        GETSET_FLAG_CRC32,
+        GetSetOp::Ping,
+        GetSetPayloadType::PingPacket,
+    );
+    req.set_version(GETSET_VERSION_2);
+    req.set_sequence(sequence);
+    req.set_ping_data(&sequence.to_le_bytes())
+        .map_err(invalid_data)?;
+    if cipher.is_some() {
+        req.set_flags(req.flags() | GETSET_FLAG_ENCRYPTED);
+    }
+
+    let mut bytes = vec![0; req.wire_size()];
+    let mut frame = GetSetFrame::new_unchecked(&mut bytes[..]);
+    req.emit(&mut frame).map_err(invalid_data)?;
+    if let Some(cipher) = cipher {
+        cipher.seal(&mut frame).map_err(invalid_data)?;
+    }
+    Ok(bytes)
+}
+
+/// Replies to earlier attempts are skipped, None when the deadline passes
+fn wait_reply(
+    link: &mut Link,
+    sequence: GetSetSequence,
+    deadline: Instant,
+    cipher: Option<&Cipher>,
+) -> io::Result<Option<Response>> {
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining == Duration::from_secs(0) {
+            return Ok(None);
+        }
+        match link {
+            Link::Tcp(stream) => stream.set_read_timeout(Some(remaining))?,
+            Link::Udp(socket) => socket.set_read_timeout(Some(remaining))?,
+        }
+        let mut bytes = match link.recv() {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let mut frame = GetSetFrame::new_checked(&mut bytes[..]).map_err(invalid_data)?;
+        if let Some(cipher) = cipher {
+            cipher.open(&mut frame).map_err(invalid_data)?;
+        }
+        let frame = GetSetFrame::new_checked(&bytes[..]).map_err(invalid_data)?;
+        info!("{}", frame);
+        if !answers_v2(GetSetOp::Ping, sequence, &bytes) {
+            warn!("Ignoring reply with sequence {}", frame.sequence());
+            continue;
+        }
+        if frame.op() == Ok(GetSetOp::Error) {
+            return Err(invalid_data(Response::parse(&frame).unwrap_err()));
+        }
+        let resp = Response::parse(&frame).map_err(invalid_data)?;
+        if resp.op() != GetSetOp::Ping || resp.ping_data() != sequence.to_le_bytes() {
+            warn!("Ignoring reply that doesn't echo the ping");
+            continue;
+        }
+        return Ok(Some(resp));
+    }
+}
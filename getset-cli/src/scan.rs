@@ -0,0 +1,115 @@
+use getset_client::invalid_data;
+use log::{info, warn};
+use param_desc::node_name::node_name;
+use param_desc::paramdb::PARAMDB_HASH;
+use params::{
+    Announce, GetSetFrame, GetSetNodeId, GetSetOp, GetSetPayloadType, Request, GETSET_FLAG_CRC32,
+    NODE_ID_ANONYMOUS,
+};
+use std::collections::BTreeMap;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+/// A node that announced itself
+#[derive(Clone, PartialEq, Debug)]
+pub struct DiscoveredNode {
+    pub node_id: GetSetNodeId,
+    /// Resolved from the paramdb
+    pub name: Option<&'static str>,
+    pub announce: Announce,
+}
+
+impl DiscoveredNode {
+    /// TCP address:port of the node's get/set endpoint
+    pub fn tcp_address(&self) -> SocketAddr {
+        SocketAddr::new(
+            Ipv4Addr::from(self.announce.tcp_ip()).into(),
+            self.announce.tcp_port(),
+        )
+    }
+}
+
+/// Broadcast a Discover to the address and collect the nodes announcing
+/// themselves until the timeout, nodes broadcast their Announce to the same
+/// port so it's bound locally
+pub fn discover(address: SocketAddr, timeout: Duration) -> io::Result<Vec<DiscoveredNode>> {
+    info!("Discover nodes at {}", address);
+
+    let socket = UdpSocket::bind(SocketAddr::new(
+        Ipv4Addr::UNSPECIFIED.into(),
+        address.port(),
+    ))?;
+    socket.set_broadcast(true)?;
+
+    let req = Request::new(
+        NODE_ID_ANONYMOUS,
+        GETSET_FLAG_CRC32,
+        GetSetOp::Discover,
+        GetSetPayloadType::None,
+    );
+    let mut buf = vec![0; req.wire_size()];
+    let mut frame = GetSetFrame::new_unchecked(&mut buf[..]);
+    req.emit(&mut frame).map_err(invalid_data)?;
+    socket.send_to(&buf, address)?;
+
+    // Nodes announcing more than once are listed once
+    let mut nodes = BTreeMap::new();
+    let mut buf: Vec<u8> = vec![0; 1500];
+    let deadline = Instant::now() + timeout;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining == Duration::from_secs(0) {
+            break;
+        }
+        socket.set_read_timeout(Some(remaining))?;
+        let (amt, src) = match socket.recv_from(&mut buf) {
+            Ok(r) => r,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => break,
+            Err(e) => return Err(e),
+        };
+
+        // Parameter broadcasts and Discover requests share the port
+        let frame = match GetSetFrame::new_checked(&buf[..amt]) {
+            Ok(frame) if frame.op() == Ok(GetSetOp::Announce) => frame,
+            _ => continue,
+        };
+        info!("{}", frame);
+        match Announce::parse(&frame) {
+            Ok(announce) => {
+                let node_id = frame.node_id();
+                nodes.insert(
+                    node_id,
+                    DiscoveredNode {
+                        node_id,
+                        name: node_name(node_id),
+                        announce,
+                    },
+                );
+            }
+            Err(e) => warn!("Dropping announce from {} {:?}", src, e),
+        }
+    }
+
+    Ok(nodes.into_values().collect())
+}
+
+pub fn scan(address: SocketAddr, timeout: Duration) -> io::Result<()> {
+    for n in discover(address, timeout)? {
+        print!(
+            "{} ({}) {} fw {} versions {}..={}",
+            n.name.unwrap_or("Unknown"),
+            n.node_id,
+            n.tcp_address(),
+            n.announce.fw_version(),
+            n.announce.min_version(),
+            n.announce.max_version()
+        );
+        if n.announce.paramdb_hash() != PARAMDB_HASH {
+            print!(" paramdb 0x{:08X} differs", n.announce.paramdb_hash());
+        }
+        println!();
+    }
+    Ok(())
+}
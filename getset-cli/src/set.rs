@@ -1,44 +1,55 @@
+use crate::print_response;
+use getset_client::{invalid_data, transact, Keys};
 use log::info;
 use params::{
-    GetSetFlags, GetSetFrame, GetSetOp, GetSetPayloadType, Parameter, ParameterFlags, ParameterId,
-    ParameterValue, Request, Response, NODE_ID_ANONYMOUS,
+    GetSetFrame, GetSetOp, GetSetPayloadType, Parameter, ParameterFlags, ParameterId,
+    ParameterValue, Request, Response, SetResult, GETSET_FLAG_ATOMIC, GETSET_FLAG_CRC32,
+    GETSET_VERSION_2, NODE_ID_ANONYMOUS,
 };
 use std::io;
-use std::io::prelude::*;
 use std::net::SocketAddr;
-use std::net::TcpStream;
 
-pub fn set(address: SocketAddr, id: ParameterId, value: ParameterValue) -> io::Result<()> {
-    info!("Set parameter ID {} Value {} at {}", id, value, address);
-    let mut buf: Vec<u8> = vec![0; 1500];
+/// Returns the per-parameter results reported by the node
+pub fn set(
+    address: SocketAddr,
+    id: ParameterId,
+    value: ParameterValue,
+    keys: &Keys,
+) -> io::Result<Vec<SetResult>> {
+    set_batch(address, &[(id, value)], false, keys)
+}
 
-    let mut frame = GetSetFrame::new_unchecked(&mut buf[..]);
+/// Set several parameters in one request, an atomic batch is either
+/// applied as a whole or not at all
+pub fn set_batch(
+    address: SocketAddr,
+    params: &[(ParameterId, ParameterValue)],
+    atomic: bool,
+    keys: &Keys,
+) -> io::Result<Vec<SetResult>> {
+    let mut flags = GETSET_FLAG_CRC32;
+    if atomic {
+        flags |= GETSET_FLAG_ATOMIC;
+    }
     let mut req = Request::new(
         NODE_ID_ANONYMOUS,
-        GetSetFlags::default(),
+        flags,
         GetSetOp::Set,
         GetSetPayloadType::ParameterListPacket,
     );
-    let p = Parameter::new_with_value(id, ParameterFlags::default(), value);
-    req.push_parameter(p).unwrap();
-    req.emit(&mut frame).unwrap();
-    let wire_size = req.wire_size();
-
-    let mut stream = TcpStream::connect(address)?;
-
-    info!("Sending {} bytes : {}", wire_size, req);
-    stream.write(&buf[..wire_size])?;
-
-    let bytes_read = stream.read(&mut buf[..])?;
+    req.set_version(GETSET_VERSION_2);
+    for (id, value) in params {
+        info!("Set parameter ID {} Value {} at {}", id, value, address);
+        let p = Parameter::new_with_value(*id, ParameterFlags::default(), *value);
+        req.push_parameter(p).map_err(invalid_data)?;
+    }
 
-    info!("Recv'd {} bytes", bytes_read);
+    let bytes = transact(address, &mut req, keys)?;
 
-    if let Ok(frame) = GetSetFrame::new_checked(&buf[..bytes_read]) {
-        info!("{}", frame);
-        if let Ok(resp) = Response::parse(&frame) {
-            println!("{}", resp);
-        }
-    }
+    let frame = GetSetFrame::new_checked(&bytes[..]).map_err(invalid_data)?;
+    info!("{}", frame);
+    let resp = Response::parse(&frame).map_err(invalid_data)?;
+    print_response(&resp);
 
-    Ok(())
+    Ok(resp.results().to_vec())
 }
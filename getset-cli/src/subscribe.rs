@@ -0,0 +1,58 @@
+use crate::print_response;
+use getset_client::{invalid_data, new_cipher, read_frame, transact_stream, Keys};
+use log::{info, warn};
+use params::{
+    Error, GetSetFrame, GetSetOp, GetSetPayloadType, ParameterId, Request, Response,
+    GETSET_FLAG_CRC32, GETSET_VERSION_2, NODE_ID_ANONYMOUS,
+};
+use std::io;
+use std::net::SocketAddr;
+
+/// Subscribe to changes of the parameters, prints their current values and
+/// then every update the node pushes until the connection closes
+pub fn subscribe(address: SocketAddr, ids: &[ParameterId], keys: &Keys) -> io::Result<()> {
+    info!("Subscribe to {} parameters at {}", ids.len(), address);
+
+    let mut req = Request::new(
+        NODE_ID_ANONYMOUS,
+        GETSET_FLAG_CRC32,
+        GetSetOp::Subscribe,
+        GetSetPayloadType::ParameterIdListPacket,
+    );
+    req.set_version(GETSET_VERSION_2);
+    for id in ids {
+        req.push_id(*id).map_err(invalid_data)?;
+    }
+
+    let (mut stream, bytes) = transact_stream(address, &mut req, keys)?;
+
+    let frame = GetSetFrame::new_checked(&bytes[..]).map_err(invalid_data)?;
+    info!("{}", frame);
+    let resp = Response::parse(&frame).map_err(invalid_data)?;
+    print_response(&resp);
+
+    // Pushed updates are encrypted like the reply was
+    let cipher = keys.cipher.map(new_cipher);
+    loop {
+        let mut bytes = match read_frame(&mut stream) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                info!("Connection closed");
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(cipher) = cipher.as_ref() {
+            let mut frame = GetSetFrame::new_checked(&mut bytes[..]).map_err(invalid_data)?;
+            cipher.open(&mut frame).map_err(invalid_data)?;
+        }
+
+        let frame = GetSetFrame::new_checked(&bytes[..]).map_err(invalid_data)?;
+        info!("{}", frame);
+        match Response::parse(&frame) {
+            Ok(resp) => print_response(&resp),
+            Err(Error::Remote(e)) => eprintln!("{}", e),
+            Err(e) => warn!("Dropping update {:?}", e),
+        }
+    }
+}
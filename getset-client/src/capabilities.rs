@@ -0,0 +1,29 @@
+use crate::transaction::{invalid_data, transact, Keys};
+use log::info;
+use params::{
+    Capabilities, GetSetFrame, GetSetOp, GetSetPayloadType, Request, Response, GETSET_FLAG_CRC32,
+    NODE_ID_ANONYMOUS,
+};
+use std::io;
+use std::net::SocketAddr;
+
+/// Query the frame versions and ops a node supports and its auth epoch,
+/// the request is always sent as v1 so any node can answer it
+pub fn query_capabilities(address: SocketAddr) -> io::Result<Capabilities> {
+    let mut req = Request::new(
+        NODE_ID_ANONYMOUS,
+        GETSET_FLAG_CRC32,
+        GetSetOp::Capabilities,
+        GetSetPayloadType::None,
+    );
+
+    let bytes = transact(address, &mut req, &Keys::default())?;
+
+    let frame = GetSetFrame::new_checked(&bytes[..]).map_err(invalid_data)?;
+    info!("{}", frame);
+    if frame.op() == Ok(GetSetOp::Error) {
+        // Parsing an error response yields the remote error
+        return Err(invalid_data(Response::parse(&frame).unwrap_err()));
+    }
+    Capabilities::parse(&frame).map_err(invalid_data)
+}
@@ -0,0 +1,92 @@
+use crate::transaction::{invalid_data, transact, Keys};
+use log::info;
+use params::{
+    Description, GetSetFrame, GetSetOp, GetSetPayloadType, ParameterFlags, ParameterId,
+    ParameterValueTypeId, RefDescribeResponse, Request, GETSET_FLAG_CRC32, GETSET_VERSION_2,
+    NODE_ID_ANONYMOUS,
+};
+use std::fmt;
+use std::io;
+use std::net::SocketAddr;
+
+/// Owned copy of a parameter's description as reported by a node
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParameterDescription {
+    pub id: ParameterId,
+    pub flags: ParameterFlags,
+    pub value_type: ParameterValueTypeId,
+    pub name: String,
+    pub desc: String,
+    pub units: Option<String>,
+    pub range: Option<(f64, f64)>,
+}
+
+impl<'a> From<Description<'a>> for ParameterDescription {
+    fn from(d: Description<'a>) -> Self {
+        ParameterDescription {
+            id: d.id(),
+            flags: d.flags(),
+            value_type: d.value_type(),
+            name: String::from(d.name()),
+            desc: String::from(d.desc()),
+            units: d.units().map(String::from),
+            range: d.range(),
+        }
+    }
+}
+
+impl fmt::Display for ParameterDescription {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}) : {:?} {}",
+            self.name, self.id, self.value_type, self.flags
+        )?;
+        if let Some(units) = &self.units {
+            write!(f, " [{}]", units)?;
+        }
+        if let Some((min, max)) = self.range {
+            write!(f, " {}..={}", min, max)?;
+        }
+        write!(f, " - {}", self.desc)
+    }
+}
+
+/// Fetch the descriptions of all the node's parameters, pages are fetched
+/// until the node stops setting the more flag
+pub fn describe_all(address: SocketAddr, keys: &Keys) -> io::Result<Vec<ParameterDescription>> {
+    info!("Describe all parameters at {}", address);
+
+    let mut descriptions = Vec::new();
+    let mut version = GETSET_VERSION_2;
+    loop {
+        let offset = descriptions.len() as u16;
+        let payload_type = if offset == 0 {
+            GetSetPayloadType::None
+        } else {
+            GetSetPayloadType::PagePacket
+        };
+        let mut req = Request::new(
+            NODE_ID_ANONYMOUS,
+            GETSET_FLAG_CRC32,
+            GetSetOp::Describe,
+            payload_type,
+        );
+        req.set_version(version);
+        req.set_offset(offset);
+
+        let bytes = transact(address, &mut req, keys)?;
+        version = req.version();
+
+        let frame = GetSetFrame::new_checked(&bytes[..]).map_err(invalid_data)?;
+        info!("{}", frame);
+        let resp = RefDescribeResponse::parse(&frame).map_err(invalid_data)?;
+        let len = descriptions.len();
+        for d in resp.descriptions() {
+            descriptions.push(ParameterDescription::from(d.map_err(invalid_data)?));
+        }
+        if !resp.more() || descriptions.len() == len {
+            return Ok(descriptions);
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Auth and cipher keys are both this size
+pub const KEY_SIZE: usize = 32;
+
+/// Read a key file holding exactly the raw key bytes, callers give it the
+/// type of key it holds (`AuthKey` or `CipherKey`)
+pub fn read_key<P: AsRef<Path>>(path: P) -> io::Result<[u8; KEY_SIZE]> {
+    let bytes = fs::read(path)?;
+    let mut key = [0; KEY_SIZE];
+    if bytes.len() != key.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Key file must hold {} bytes, got {}", KEY_SIZE, bytes.len()),
+        ));
+    }
+    key.copy_from_slice(&bytes);
+    Ok(key)
+}
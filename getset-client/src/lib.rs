@@ -0,0 +1,9 @@
+mod capabilities;
+mod describe;
+mod key;
+mod transaction;
+
+pub use capabilities::*;
+pub use describe::*;
+pub use key::*;
+pub use transaction::*;
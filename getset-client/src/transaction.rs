@@ -0,0 +1,193 @@
+use crate::capabilities::query_capabilities;
+use log::{info, warn};
+use params::{
+    AuthKey, Authenticator, Cipher, CipherKey, Error, ErrorCode, GetSetFrame, GetSetOp,
+    GetSetSequence, Request, Response, GETSET_FLAG_AUTH, GETSET_FLAG_ENCRYPTED, GETSET_VERSION_1,
+    GETSET_VERSION_2,
+};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::io::prelude::*;
+use std::net::SocketAddr;
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Sequence 0 is never used, it marks the updates a node pushes to subscribers
+static NEXT_SEQUENCE: AtomicU32 = AtomicU32::new(1);
+
+/// How long a transaction waits to connect and for each read of the reply
+pub const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Keys used by a transaction, requests are sent in the clear without any
+/// of them
+#[derive(Clone, Default)]
+pub struct Keys {
+    /// Signs requests
+    pub auth: Option<AuthKey>,
+    /// Encrypts requests and decrypts replies, the addressed node's key
+    pub cipher: Option<CipherKey>,
+}
+
+/// Send a request and return the bytes of its reply frame.
+///
+/// v2 requests are tagged with a fresh sequence number and replies, errors
+/// included, that don't echo it are skipped. Nodes that only speak v1 answer
+/// with a v1 UnsupportedVersion error and are sent the request again as v1.
+///
+/// Requests are signed when an auth key is given, for the auth epoch the node
+/// picked at boot which is queried first. The auth counter starts at the
+/// current time so it keeps increasing across invocations. With a cipher
+/// key the request is encrypted, replies flagged as encrypted are returned
+/// decrypted.
+///
+/// Nodes that don't accept the connection or reply within `REPLY_TIMEOUT`
+/// fail the transaction with a `TimedOut` or `WouldBlock` error.
+pub fn transact(address: SocketAddr, req: &mut Request, keys: &Keys) -> io::Result<Vec<u8>> {
+    transact_stream(address, req, keys).map(|(_, bytes)| bytes)
+}
+
+/// Like `transact`, also returns the connection the reply was read from
+/// so further frames the node pushes can be read from it, reads from it
+/// no longer time out
+pub fn transact_stream(
+    address: SocketAddr,
+    req: &mut Request,
+    keys: &Keys,
+) -> io::Result<(TcpStream, Vec<u8>)> {
+    let mut auth = match keys.auth {
+        Some(key) => {
+            let mut auth = Authenticator::new(key);
+            auth.set_epoch(query_capabilities(address)?.auth_epoch());
+            auth.set_counter(now_micros());
+            req.set_flags(req.flags() | GETSET_FLAG_AUTH);
+            Some(auth)
+        }
+        None => None,
+    };
+    let mut cipher = keys.cipher.map(|key| {
+        let mut cipher = new_cipher(key);
+        cipher.set_counter(now_micros());
+        req.set_flags(req.flags() | GETSET_FLAG_ENCRYPTED);
+        cipher
+    });
+
+    loop {
+        let sequence = NEXT_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        req.set_sequence(sequence);
+        let mut buf = vec![0; req.wire_size()];
+        let mut frame = GetSetFrame::new_unchecked(&mut buf[..]);
+        req.emit(&mut frame).map_err(invalid_data)?;
+        if let Some(cipher) = cipher.as_mut() {
+            cipher.seal(&mut frame).map_err(invalid_data)?;
+        }
+        if let Some(auth) = auth.as_mut() {
+            auth.sign(&mut frame).map_err(invalid_data)?;
+        }
+
+        let mut stream = TcpStream::connect_timeout(&address, REPLY_TIMEOUT)?;
+        stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+        stream.set_write_timeout(Some(REPLY_TIMEOUT))?;
+
+        info!("Sending {} bytes : {}", buf.len(), req);
+        stream.write_all(&buf[..])?;
+
+        let bytes = loop {
+            let mut bytes = read_frame(&mut stream)?;
+            info!("Recv'd {} bytes", bytes.len());
+            let mut frame = GetSetFrame::new_checked(&mut bytes[..]).map_err(invalid_data)?;
+            // Errors about frames the node couldn't read come back in the clear
+            if let Some(cipher) = cipher.as_ref() {
+                if frame.flags() & GETSET_FLAG_ENCRYPTED != 0 {
+                    cipher.open(&mut frame).map_err(invalid_data)?;
+                }
+            }
+            let frame = GetSetFrame::new_unchecked(&bytes[..]);
+            if req.version() >= GETSET_VERSION_2 && !answers_v2(req.op(), sequence, &bytes) {
+                warn!(
+                    "Ignoring v{} {:?} reply with sequence {}, expected {}",
+                    frame.version(),
+                    frame.op(),
+                    frame.sequence(),
+                    sequence
+                );
+                continue;
+            }
+            break bytes;
+        };
+
+        if req.version() > GETSET_VERSION_1 && unsupported_version(&bytes) {
+            info!("Node doesn't speak v{}, falling back to v1", req.version());
+            req.set_version(GETSET_VERSION_1);
+            continue;
+        }
+
+        stream.set_read_timeout(None)?;
+        return Ok((stream, bytes));
+    }
+}
+
+/// Nonces are salted with a random value so hosts sharing a node's key
+/// never reuse one
+pub fn new_cipher(key: CipherKey) -> Cipher {
+    let salt = RandomState::new().build_hasher().finish();
+    Cipher::new(key, salt as u32)
+}
+
+pub fn now_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_micros() as u64)
+}
+
+/// Read exactly one frame, the header determines how much follows
+pub fn read_frame(stream: &mut TcpStream) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![0; GetSetFrame::<&[u8]>::header_len()];
+    stream.read_exact(&mut bytes[..])?;
+    let frame = GetSetFrame::new_unchecked(&bytes[..]);
+    frame.check_preamble().map_err(invalid_data)?;
+    frame.check_version().map_err(invalid_data)?;
+    let len = GetSetFrame::<&[u8]>::frame_len(
+        frame.version(),
+        frame.flags(),
+        usize::from(frame.payload_size()),
+    );
+    let header_len = bytes.len();
+    bytes.resize(len, 0);
+    stream.read_exact(&mut bytes[header_len..])?;
+    Ok(bytes)
+}
+
+/// Whether a decrypted reply answers the v2 request with the op and sequence
+/// number. Replies echo the sequence, only UnsupportedVersion errors and
+/// Capabilities replies from nodes that always answered in v1 carry none.
+pub fn answers_v2(op: GetSetOp, sequence: GetSetSequence, bytes: &[u8]) -> bool {
+    let frame = GetSetFrame::new_unchecked(bytes);
+    if frame.version() >= GETSET_VERSION_2 {
+        frame.sequence() == sequence
+    } else if op == GetSetOp::Capabilities && frame.op() == Ok(GetSetOp::Capabilities) {
+        true
+    } else {
+        unsupported_version(bytes)
+    }
+}
+
+fn unsupported_version(bytes: &[u8]) -> bool {
+    let frame = GetSetFrame::new_unchecked(bytes);
+    if frame.op() != Ok(GetSetOp::Error) {
+        return false;
+    }
+    match Response::parse(&frame) {
+        Err(Error::Remote(e)) => e.code() == ErrorCode::UnsupportedVersion,
+        _ => false,
+    }
+}
+
+/// Remote errors are reported as is, anything else is invalid data
+pub fn invalid_data(e: Error) -> io::Error {
+    match e {
+        Error::Remote(e) => io::Error::other(e.to_string()),
+        e => io::Error::new(io::ErrorKind::InvalidData, format!("{:?}", e)),
+    }
+}
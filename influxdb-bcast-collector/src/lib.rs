@@ -1,18 +1,46 @@
+use getset_client::{describe_all, Keys};
 use influx_db_client::{Client, Point, Precision, Value};
-use log::info;
+use log::{info, warn};
 use param_desc::node_name::node_name;
+use param_desc::param_enum::param_enum_name;
+use param_desc::param_flags::param_flag_names;
 use param_desc::param_name::param_name;
-use params::{GetSetFrame, ParameterValue, Response};
+use params::{
+    Cipher, CipherKey, Error, GetSetFrame, GetSetNodeId, ParameterId, ParameterValue, Response,
+    GETSET_FLAG_ENCRYPTED,
+};
+use std::collections::{HashMap, HashSet};
 use std::io;
 use std::net::SocketAddr;
 use std::net::UdpSocket;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Nodes that failed to describe themselves are asked again after this long
+const DESCRIBE_RETRY_INTERVAL: Duration = Duration::from_secs(60);
 
 // TODO - should precision be set to seconds or ms?
 //
 // should local_time_ms be another tag/field?
 //
 // store version as a tag?
-pub fn start_listening(address: SocketAddr, client: String, db: String) -> io::Result<()> {
+/// Encrypted broadcasts are decrypted with the key of the node they come from.
+///
+/// With a `describe_port`, parameters missing from param-desc are named by
+/// asking the node to describe itself over TCP on that port, once per node.
+/// Nodes are described in the background so broadcasts keep being logged,
+/// their parameters go unnamed until the description arrives. Nodes that
+/// don't answer within the transaction timeout are asked again after
+/// `DESCRIBE_RETRY_INTERVAL`.
+pub fn start_listening(
+    address: SocketAddr,
+    keys: HashMap<GetSetNodeId, CipherKey>,
+    client: String,
+    db: String,
+    expand_flags: bool,
+    describe_port: Option<u16>,
+) -> io::Result<()> {
     info!("Setup client at {}, database '{}'", client, db);
 
     let client = Client::new(client.to_string(), db.clone());
@@ -20,6 +48,14 @@ pub fn start_listening(address: SocketAddr, client: String, db: String) -> io::R
 
     client.create_database(&db).unwrap();
 
+    // Only opens frames, the salt is never used
+    let ciphers: HashMap<GetSetNodeId, Cipher> = keys
+        .iter()
+        .map(|(node_id, key)| (*node_id, Cipher::new(*key, 0)))
+        .collect();
+
+    let mut described = DescribedNames::new();
+
     info!("Listening for broadcast GetSetFrame's on {}", address);
     let socket = UdpSocket::bind(address)?;
     let mut buf: Vec<u8> = vec![0; 1500];
@@ -29,38 +65,85 @@ pub fn start_listening(address: SocketAddr, client: String, db: String) -> io::R
         info!("Got {} bytes from {}", amt, src);
 
         if amt >= GetSetFrame::<&[u8]>::header_len() {
-            if let Ok(frame) = GetSetFrame::new_checked(&buf[..amt]) {
+            let frame = GetSetFrame::new_checked(&mut buf[..amt]).and_then(|mut frame| {
+                if frame.flags() & GETSET_FLAG_ENCRYPTED != 0 {
+                    ciphers
+                        .get(&frame.node_id())
+                        .ok_or(Error::EncryptionRequired)?
+                        .open(&mut frame)?;
+                }
+                Ok(GetSetFrame::new_unchecked(frame.into_inner() as &[u8]))
+            });
+            match frame {
+                Err(Error::WireChecksum) => warn!("Dropping frame from {} with a bad CRC32", src),
+                Err(Error::EncryptionRequired) => {
+                    warn!("Dropping encrypted frame from {}, no key for the node", src)
+                }
+                Err(Error::DecryptFailed) => {
+                    warn!("Dropping frame from {} that fails to decrypt", src)
+                }
+                _ => (),
+            }
+            if let Ok(frame) = frame {
                 info!("{}", frame);
                 let node_id = frame.node_id();
                 let node_name =
-                    node_name(node_id).map_or(format!("Unkown({})", node_id), |s| String::from(s));
+                    node_name(node_id).map_or(format!("Unkown({})", node_id), String::from);
                 if let Ok(resp) = Response::parse(&frame) {
                     for p in resp.parameters() {
+                        let described_name = match (param_name(p.id()), describe_port) {
+                            (None, Some(port)) => {
+                                let keys = Keys {
+                                    auth: None,
+                                    cipher: keys.get(&node_id).copied(),
+                                };
+                                described
+                                    .names(node_id, SocketAddr::new(src.ip(), port), &keys)
+                                    .and_then(|names| names.get(&p.id()))
+                            }
+                            _ => None,
+                        };
                         let param_name = param_name(p.id())
-                            .map_or(format!("Unkown({})", p.id()), |s| String::from(s));
+                            .map(String::from)
+                            .or_else(|| described_name.cloned())
+                            .unwrap_or_else(|| format!("Unkown({})", p.id()));
 
                         let mut point = Point::new(&param_name);
 
                         // local_time_ms is another field?
                         //point.timestamp = Some(p.local_time_ms() as i64);
 
-                        let val = match p.value() {
-                            // Use desc string for None/Notif?
-                            ParameterValue::None => Value::String(String::from("None")),
-                            ParameterValue::Notification => {
-                                Value::String(String::from("Notification"))
+                        match p.value() {
+                            // Arrays get a field per element, value_0..value_N
+                            ParameterValue::Array(a) => {
+                                for (index, v) in a.iter().enumerate() {
+                                    point.add_field(format!("value_{}", index), field_value(v));
+                                }
                             }
-                            ParameterValue::Bool(v) => Value::Boolean(v),
-                            ParameterValue::U8(v) => Value::Integer(v as i64),
-                            ParameterValue::I8(v) => Value::Integer(v as i64),
-                            ParameterValue::U32(v) => Value::Integer(v as i64),
-                            ParameterValue::I32(v) => Value::Integer(v as i64),
-                            ParameterValue::U64(v) => Value::Integer(v as i64),
-                            ParameterValue::I64(v) => Value::Integer(v as i64),
-                            ParameterValue::F32(v) => Value::Float(v.into()),
-                        };
+                            v => {
+                                point.add_field("value", field_value(v));
+                            }
+                        }
+
+                        // Enums also store the variant name
+                        if let Some(name) = param_enum_name(p.id(), p.value()) {
+                            point.add_field("value_name", Value::String(String::from(name)));
+                        }
+
+                        // Optionally a boolean field per named bit of flags
+                        if expand_flags {
+                            if let (Some(names), ParameterValue::U32(bits)) =
+                                (param_flag_names(p.id()), p.value())
+                            {
+                                for (bit, name) in names.iter().enumerate() {
+                                    point.add_field(
+                                        format!("value_{}", name),
+                                        Value::Boolean(bits & (1 << bit) != 0),
+                                    );
+                                }
+                            }
+                        }
 
-                        point.add_field("value", val);
                         point.add_tag("node_id", Value::String(node_name.clone()));
 
                         info!("Logging {:?}", point);
@@ -74,3 +157,106 @@ pub fn start_listening(address: SocketAddr, client: String, db: String) -> io::R
         }
     }
 }
+
+type NodeNames = HashMap<ParameterId, String>;
+
+/// Outcome of describing a node in the background
+type Described = (GetSetNodeId, SocketAddr, io::Result<NodeNames>);
+
+/// Parameter names reported by each node's Describe
+struct DescribedNames {
+    names: HashMap<GetSetNodeId, NodeNames>,
+    /// Nodes being described in the background
+    pending: HashSet<GetSetNodeId>,
+    /// When the nodes that couldn't be described were last asked
+    failed: HashMap<GetSetNodeId, Instant>,
+    tx: Sender<Described>,
+    rx: Receiver<Described>,
+}
+
+impl DescribedNames {
+    fn new() -> Self {
+        let (tx, rx) = channel();
+        DescribedNames {
+            names: HashMap::new(),
+            pending: HashSet::new(),
+            failed: HashMap::new(),
+            tx,
+            rx,
+        }
+    }
+
+    /// Names of the node's described parameters, the node is asked in the
+    /// background on first use and again once the retry interval passed
+    /// since it last failed. Never waits for the node.
+    fn names(
+        &mut self,
+        node_id: GetSetNodeId,
+        address: SocketAddr,
+        keys: &Keys,
+    ) -> Option<&NodeNames> {
+        self.collect();
+        if !self.names.contains_key(&node_id) && !self.pending.contains(&node_id) {
+            if let Some(at) = self.failed.get(&node_id) {
+                if at.elapsed() < DESCRIBE_RETRY_INTERVAL {
+                    return None;
+                }
+            }
+            self.pending.insert(node_id);
+            let tx = self.tx.clone();
+            let keys = keys.clone();
+            thread::spawn(move || {
+                let names = describe_all(address, &keys).map(|descriptions| {
+                    descriptions
+                        .into_iter()
+                        .filter(|d| !d.name.is_empty())
+                        .map(|d| (d.id, d.name))
+                        .collect()
+                });
+                // The receiver only goes away with the collector
+                let _ = tx.send((node_id, address, names));
+            });
+        }
+        self.names.get(&node_id)
+    }
+
+    /// Cache the nodes described since the last call
+    fn collect(&mut self) {
+        for (node_id, address, names) in self.rx.try_iter() {
+            self.pending.remove(&node_id);
+            match names {
+                Ok(names) => {
+                    info!("Described {} parameters at {}", names.len(), address);
+                    self.failed.remove(&node_id);
+                    self.names.insert(node_id, names);
+                }
+                Err(e) => {
+                    warn!("Failed to describe the parameters at {}: {}", address, e);
+                    self.failed.insert(node_id, Instant::now());
+                }
+            }
+        }
+    }
+}
+
+fn field_value(value: ParameterValue) -> Value {
+    match value {
+        // Use desc string for None/Notif?
+        ParameterValue::None => Value::String(String::from("None")),
+        ParameterValue::Notification => Value::String(String::from("Notification")),
+        ParameterValue::Bool(v) => Value::Boolean(v),
+        ParameterValue::U8(v) => Value::Integer(v as i64),
+        ParameterValue::I8(v) => Value::Integer(v as i64),
+        ParameterValue::U16(v) => Value::Integer(v as i64),
+        ParameterValue::I16(v) => Value::Integer(v as i64),
+        ParameterValue::U32(v) => Value::Integer(v as i64),
+        ParameterValue::I32(v) => Value::Integer(v as i64),
+        ParameterValue::U64(v) => Value::Integer(v as i64),
+        ParameterValue::I64(v) => Value::Integer(v),
+        ParameterValue::F32(v) => Value::Float(v.into()),
+        ParameterValue::F64(v) => Value::Float(v),
+        ParameterValue::Str(v) => Value::String(String::from(v.as_str())),
+        ParameterValue::Bytes(v) => Value::String(v.to_string()),
+        ParameterValue::Array(v) => Value::String(v.to_string()),
+    }
+}
@@ -1,5 +1,8 @@
+use getset_client::read_key;
 use log::Level;
+use params::{CipherKey, GetSetNodeId};
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 // TODO - user/pass for auth
@@ -20,6 +23,31 @@ struct CLIOptions {
     /// Database name
     #[structopt(short = "d", long, default_value = "parameters")]
     database: String,
+
+    /// Expand flags parameters into a boolean field per named bit
+    #[structopt(short = "e", long)]
+    expand_flags: bool,
+
+    /// Decrypt broadcasts of a node with the key in a file, given as
+    /// node_id=path, repeat for each node
+    #[structopt(short = "k", long = "key-file", parse(try_from_str = parse_node_key_file))]
+    key_files: Vec<(GetSetNodeId, PathBuf)>,
+
+    /// Name parameters unknown to param-desc by describing the node over
+    /// TCP on this port (9877)
+    #[structopt(short = "p", long)]
+    describe_port: Option<u16>,
+}
+
+fn parse_node_key_file(src: &str) -> Result<(GetSetNodeId, PathBuf), String> {
+    let mut parts = src.splitn(2, '=');
+    match (parts.next(), parts.next()) {
+        (Some(id), Some(path)) => id
+            .parse()
+            .map(|id| (id, PathBuf::from(path)))
+            .map_err(|_| format!("Invalid node ID: {}", id)),
+        _ => Err(format!("Expected node_id=path, got {}", src)),
+    }
 }
 
 fn main() {
@@ -31,5 +59,22 @@ fn main() {
         simple_logger::init_with_level(Level::Warn).unwrap();
     }
 
-    influxdb_bcast_collector::start_listening(opts.address, opts.client, opts.database).unwrap();
+    let keys = opts
+        .key_files
+        .iter()
+        .map(|(node_id, path)| {
+            let key: CipherKey = read_key(path).unwrap();
+            (*node_id, key)
+        })
+        .collect();
+
+    influxdb_bcast_collector::start_listening(
+        opts.address,
+        keys,
+        opts.client,
+        opts.database,
+        opts.expand_flags,
+        opts.describe_port,
+    )
+    .unwrap();
 }
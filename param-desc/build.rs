@@ -1,4 +1,7 @@
-use params::ParameterValueTypeId;
+use params::{
+    crc32, ParameterValueArray, ParameterValueBytes, ParameterValueStr, ParameterValueTypeId,
+    MAX_ARRAY_WIRE_SIZE, MAX_BYTES_LEN, MAX_STR_LEN,
+};
 use serde_derive::Deserialize;
 use std::collections::HashSet;
 use std::fs::File;
@@ -23,35 +26,56 @@ fn main() {
     let param_name_gen = out_dir.join("param_name_gen.rs");
     let param_desc_gen = out_dir.join("param_desc_gen.rs");
     let param_gen = out_dir.join("param_gen.rs");
+    let param_enum_gen = out_dir.join("param_enum_gen.rs");
+    let param_flags_gen = out_dir.join("param_flags_gen.rs");
+    let param_description_gen = out_dir.join("param_description_gen.rs");
+    let param_command_gen = out_dir.join("param_command_gen.rs");
+    let paramdb_gen = out_dir.join("paramdb_gen.rs");
 
     println!("rerun-if-changed={}", toml.display());
 
     let toml_str = std::fs::read_to_string(toml).unwrap();
     let desc: Desc = toml::from_str(&toml_str).unwrap();
 
-    desc.node.as_ref().map(|nodes| {
+    // Generate the paramdb hash nodes announce
+    let mut paramdb_gen_file = File::create(paramdb_gen).unwrap();
+    paramdb_gen_file
+        .write_all(
+            format!(
+                r#"
+/// CRC32 of paramdb.toml, nodes and clients built from the same paramdb
+/// agree on the parameters
+pub const PARAMDB_HASH: u32 = 0x{:08X};
+"#,
+                crc32(toml_str.as_bytes())
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+    if let Some(nodes) = &desc.node {
         let ids: Vec<u32> = nodes.iter().map(|p| p.id).collect();
         assert!(has_unique_elements(ids));
-    });
+    }
 
-    desc.parameter.as_ref().map(|params| {
+    if let Some(params) = &desc.parameter {
         let ids: Vec<u32> = params.iter().map(|p| p.id).collect();
         assert!(has_unique_elements(ids));
-    });
+    }
 
     // Generate node ID/desc/name
     let mut node_id_gen_file = File::create(node_id_gen).unwrap();
     let mut node_name_gen_file = File::create(node_name_gen).unwrap();
     let mut node_desc_gen_file = File::create(node_desc_gen).unwrap();
 
-    desc.node.as_ref().map(|nodes| {
+    if let Some(nodes) = &desc.node {
         nodes.iter().for_each(|n| {
             node_id_gen_file.write_all(n.gen_id().as_bytes()).unwrap();
             node_desc_gen_file
                 .write_all(n.gen_desc().as_bytes())
                 .unwrap();
         })
-    });
+    }
 
     node_desc_gen_file
         .write_all(
@@ -62,13 +86,13 @@ match id {
             .as_bytes(),
         )
         .unwrap();
-    desc.node.as_ref().map(|nodes| {
+    if let Some(nodes) = &desc.node {
         nodes.iter().for_each(|n| {
             node_desc_gen_file
                 .write_all(format!("{} => Some(\"{}\"),\n", n.id, n.desc).as_bytes())
                 .unwrap()
         })
-    });
+    }
     node_desc_gen_file.write_all(b"_ => None,\n").unwrap();
     node_desc_gen_file.write_all(b"}}\n").unwrap();
 
@@ -81,13 +105,13 @@ match id {
             .as_bytes(),
         )
         .unwrap();
-    desc.node.as_ref().map(|nodes| {
+    if let Some(nodes) = &desc.node {
         nodes.iter().for_each(|n| {
             node_name_gen_file
                 .write_all(format!("{} => Some(\"{}\"),\n", n.id, n.name).as_bytes())
                 .unwrap()
         })
-    });
+    }
     node_name_gen_file.write_all(b"_ => None,\n").unwrap();
     node_name_gen_file.write_all(b"}}\n").unwrap();
 
@@ -96,14 +120,14 @@ match id {
     let mut param_name_gen_file = File::create(param_name_gen).unwrap();
     let mut param_desc_gen_file = File::create(param_desc_gen).unwrap();
 
-    desc.parameter.as_ref().map(|params| {
+    if let Some(params) = &desc.parameter {
         params.iter().for_each(|p| {
             param_id_gen_file.write_all(p.gen_id().as_bytes()).unwrap();
             param_desc_gen_file
                 .write_all(p.gen_desc().as_bytes())
                 .unwrap();
         })
-    });
+    }
 
     param_desc_gen_file
         .write_all(
@@ -114,13 +138,13 @@ match id.0 {
             .as_bytes(),
         )
         .unwrap();
-    desc.parameter.as_ref().map(|params| {
+    if let Some(params) = &desc.parameter {
         params.iter().for_each(|p| {
             param_desc_gen_file
                 .write_all(format!("{} => Some(\"{}\"),\n", p.id, p.desc).as_bytes())
                 .unwrap();
         })
-    });
+    }
     param_desc_gen_file.write_all(b"_ => None,\n").unwrap();
     param_desc_gen_file.write_all(b"}}\n").unwrap();
 
@@ -133,24 +157,141 @@ match id.0 {
             .as_bytes(),
         )
         .unwrap();
-    desc.parameter.as_ref().map(|params| {
+    if let Some(params) = &desc.parameter {
         params.iter().for_each(|p| {
             param_name_gen_file
                 .write_all(format!("{} => Some(\"{}\"),\n", p.id, p.name).as_bytes())
                 .unwrap();
         })
-    });
+    }
     param_name_gen_file.write_all(b"_ => None,\n").unwrap();
     param_name_gen_file.write_all(b"}}\n").unwrap();
 
     // Generate parameter consts
     let mut param_gen_file = File::create(param_gen).unwrap();
 
-    desc.parameter.as_ref().map(|params| {
+    if let Some(params) = &desc.parameter {
         params.iter().for_each(|p| {
             param_gen_file.write_all(p.gen_param().as_bytes()).unwrap();
         })
-    });
+    }
+
+    // Generate enum types and variant name tables
+    let mut param_enum_gen_file = File::create(param_enum_gen).unwrap();
+
+    if let Some(params) = &desc.parameter {
+        params.iter().filter(|p| p.is_enum()).for_each(|p| {
+            param_enum_gen_file
+                .write_all(p.gen_enum().as_bytes())
+                .unwrap();
+        })
+    }
+
+    param_enum_gen_file
+        .write_all(
+            r#"
+pub fn param_enum_names(id: ParameterId) -> Option<&'static [&'static str]> {
+match id.0 {
+"#
+            .as_bytes(),
+        )
+        .unwrap();
+    if let Some(params) = &desc.parameter {
+        params.iter().filter(|p| p.is_enum()).for_each(|p| {
+            param_enum_gen_file
+                .write_all(format!("{} => Some(&{}::NAMES),\n", p.id, p.enum_name()).as_bytes())
+                .unwrap();
+        })
+    }
+    param_enum_gen_file.write_all(b"_ => None,\n").unwrap();
+    param_enum_gen_file.write_all(b"}}\n").unwrap();
+
+    // Generate bitfield types and bit name tables
+    let mut param_flags_gen_file = File::create(param_flags_gen).unwrap();
+
+    if let Some(params) = &desc.parameter {
+        params.iter().filter(|p| p.is_flags()).for_each(|p| {
+            param_flags_gen_file
+                .write_all(p.gen_flags().as_bytes())
+                .unwrap();
+        })
+    }
+
+    param_flags_gen_file
+        .write_all(
+            r#"
+pub fn param_flag_names(id: ParameterId) -> Option<&'static [&'static str]> {
+match id.0 {
+"#
+            .as_bytes(),
+        )
+        .unwrap();
+    if let Some(params) = &desc.parameter {
+        params.iter().filter(|p| p.is_flags()).for_each(|p| {
+            param_flags_gen_file
+                .write_all(format!("{} => Some(&{}::NAMES),\n", p.id, p.enum_name()).as_bytes())
+                .unwrap();
+        })
+    }
+    param_flags_gen_file.write_all(b"_ => None,\n").unwrap();
+    param_flags_gen_file.write_all(b"}}\n").unwrap();
+
+    // Generate the description table reported by the Describe op
+    let mut param_description_gen_file = File::create(param_description_gen).unwrap();
+    let descriptions: Vec<String> = desc
+        .parameter
+        .iter()
+        .flatten()
+        .map(|p| p.gen_description())
+        .collect();
+    param_description_gen_file
+        .write_all(
+            format!(
+                r#"
+pub const PARAM_DESCRIPTIONS: [Description<'static>; {}] = [
+{}];
+
+pub fn param_description(id: ParameterId) -> Option<&'static Description<'static>> {{
+    PARAM_DESCRIPTIONS.iter().find(|d| d.id() == id)
+}}
+"#,
+                descriptions.len(),
+                descriptions.concat(),
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+    // Generate the command table the Invoke op dispatches through
+    let mut param_command_gen_file = File::create(param_command_gen).unwrap();
+    let commands: Vec<String> = desc
+        .parameter
+        .iter()
+        .flatten()
+        .filter(|p| p.is_command())
+        .map(|p| p.gen_command())
+        .collect();
+    param_command_gen_file
+        .write_all(
+            format!(
+                r#"
+pub const PARAM_COMMANDS: [Command; {}] = [
+{}];
+
+pub fn param_command(id: ParameterId) -> Option<&'static Command> {{
+    PARAM_COMMANDS.iter().find(|c| c.id() == id)
+}}
+
+pub fn param_command_by_name(name: &str) -> Option<&'static Command> {{
+    PARAM_COMMANDS.iter().find(|c| c.name() == name)
+}}
+"#,
+                commands.len(),
+                commands.concat(),
+            )
+            .as_bytes(),
+        )
+        .unwrap();
 }
 
 #[derive(Debug, Deserialize)]
@@ -166,17 +307,24 @@ struct NodeDesc {
     desc: String,
 }
 
-#[derive(Debug, Deserialize, Hash, PartialEq, Eq)]
+#[derive(Debug, Deserialize, PartialEq)]
 struct ParamDesc {
     id: u32,
     name: String,
     desc: String,
     value_type: String,
     value: Option<String>,
+    variants: Option<Vec<String>>,
+    bits: Option<Vec<String>>,
     ro: Option<bool>,
     bcast: Option<bool>,
     bcast_on_change: Option<bool>,
     constant: Option<bool>,
+    units: Option<String>,
+    min: Option<f64>,
+    max: Option<f64>,
+    arg_type: Option<String>,
+    result_type: Option<String>,
 }
 
 impl NodeDesc {
@@ -190,7 +338,7 @@ impl NodeDesc {
 
     fn gen_desc(&self) -> String {
         format!(
-            "pub const {}_DESC: &'static str = \"{}\";\n",
+            "pub const {}_DESC: &str = \"{}\";\n",
             self.name.to_ascii_uppercase(),
             self.desc,
         )
@@ -208,69 +356,459 @@ impl ParamDesc {
 
     fn gen_desc(&self) -> String {
         format!(
-            "pub const {}_DESC: &'static str = \"{}\";\n",
+            "pub const {}_DESC: &str = \"{}\";\n",
             self.name.to_ascii_uppercase(),
             self.desc,
         )
     }
 
+    fn is_enum(&self) -> bool {
+        self.value_type == "enum"
+    }
+
+    fn enum_name(&self) -> String {
+        to_pascal_case(&self.name)
+    }
+
+    fn enum_variants(&self) -> &[String] {
+        let variants = self
+            .variants
+            .as_ref()
+            .unwrap_or_else(|| panic!("{} enum has no variants", self.name));
+        assert!(!variants.is_empty(), "{} enum has no variants", self.name);
+        assert!(
+            variants.len() <= 256,
+            "{} enum has too many variants",
+            self.name
+        );
+        assert!(has_unique_elements(variants.iter()));
+        variants
+    }
+
+    /// Enums are carried on the wire as their U8 variant index
+    fn gen_enum_value(&self) -> String {
+        let variants = self.enum_variants();
+        let index = match &self.value {
+            Some(v) => variants
+                .iter()
+                .position(|name| name == v)
+                .unwrap_or_else(|| panic!("{} has no variant named {}", self.name, v)),
+            None => 0,
+        };
+        format!("ParameterValue::U8({})", index)
+    }
+
+    fn gen_enum(&self) -> String {
+        let name = self.enum_name();
+        let variants = self.enum_variants();
+        let idents: Vec<String> = variants.iter().map(|v| to_pascal_case(v)).collect();
+        let decls: Vec<String> = idents
+            .iter()
+            .enumerate()
+            .map(|(index, v)| format!("    {} = {},\n", v, index))
+            .collect();
+        let paths: Vec<String> = idents.iter().map(|v| format!("{}::{}", name, v)).collect();
+        let names: Vec<String> = variants.iter().map(|v| format!("\"{}\"", v)).collect();
+
+        format!(
+            r#"
+/// {desc}
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum {name} {{
+{decls}}}
+
+impl {name} {{
+    pub const VARIANTS: [{name}; {len}] = [{paths}];
+    pub const NAMES: [&'static str; {len}] = [{names}];
+
+    pub fn name(self) -> &'static str {{
+        Self::NAMES[self as usize]
+    }}
+
+    pub fn from_name(name: &str) -> Option<Self> {{
+        Self::NAMES
+            .iter()
+            .position(|n| *n == name)
+            .map(|index| Self::VARIANTS[index])
+    }}
+}}
+
+impl TryFrom<ParameterValue> for {name} {{
+    type Error = Error;
+
+    fn try_from(value: ParameterValue) -> Result<Self, Self::Error> {{
+        match value {{
+            ParameterValue::U8(index) => Self::VARIANTS
+                .get(usize::from(index))
+                .copied()
+                .ok_or(Error::ParseValue),
+            _ => Err(Error::ValueTypeMismatch),
+        }}
+    }}
+}}
+
+impl From<{name}> for ParameterValue {{
+    fn from(v: {name}) -> Self {{
+        ParameterValue::U8(v as u8)
+    }}
+}}
+
+impl FromStr for {name} {{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {{
+        Self::from_name(s).ok_or(Error::ParseValue)
+    }}
+}}
+
+impl fmt::Display for {name} {{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {{
+        write!(f, "{{}}", self.name())
+    }}
+}}
+"#,
+            desc = self.desc,
+            name = name,
+            decls = decls.concat(),
+            len = variants.len(),
+            paths = paths.join(", "),
+            names = names.join(", "),
+        )
+    }
+
+    fn is_flags(&self) -> bool {
+        self.value_type == "flags"
+    }
+
+    fn flag_bits(&self) -> &[String] {
+        let bits = self
+            .bits
+            .as_ref()
+            .unwrap_or_else(|| panic!("{} flags has no bits", self.name));
+        assert!(bits.len() <= 32, "{} flags has too many bits", self.name);
+        assert!(has_unique_elements(bits.iter()));
+        bits.iter().for_each(|b| {
+            assert!(
+                b.starts_with(|c: char| c.is_ascii_lowercase())
+                    && b.chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'),
+                "{} flags bit name {} must be snake case",
+                self.name,
+                b
+            )
+        });
+        bits
+    }
+
+    /// Flags are carried on the wire as a U32, the value is a set of bit names
+    /// ("pump|fan")
+    fn gen_flags_value(&self) -> String {
+        let bits = self.flag_bits();
+        let value = self
+            .value
+            .as_ref()
+            .map_or("0", String::as_str)
+            .split('|')
+            .map(str::trim)
+            .filter(|n| !n.is_empty() && *n != "0")
+            .fold(0_u32, |value, n| {
+                let bit = bits
+                    .iter()
+                    .position(|b| b == n)
+                    .unwrap_or_else(|| panic!("{} has no bit named {}", self.name, n));
+                value | (1 << bit)
+            });
+        format!("ParameterValue::U32(0x{:X})", value)
+    }
+
+    fn gen_flags(&self) -> String {
+        let name = self.enum_name();
+        let bits = self.flag_bits();
+        let fields: Vec<String> = bits
+            .iter()
+            .enumerate()
+            .map(|(index, b)| format!("    pub {}, set_{}: {};\n", b, b, index))
+            .collect();
+        let consts: Vec<String> = bits
+            .iter()
+            .enumerate()
+            .map(|(index, b)| {
+                format!(
+                    "    pub const {}: u32 = 1 << {};\n",
+                    b.to_ascii_uppercase(),
+                    index
+                )
+            })
+            .collect();
+        let names: Vec<String> = bits.iter().map(|b| format!("\"{}\"", b)).collect();
+
+        format!(
+            r#"
+bitfield! {{
+    /// {desc}
+    #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+    pub struct {name}(u32);
+    u32;
+{fields}}}
+
+impl {name} {{
+{consts}
+    pub const NAMES: [&'static str; {len}] = [{names}];
+
+    pub const fn new() -> Self {{
+        {name}(0)
+    }}
+
+    pub const fn new_from_flags(flags: u32) -> Self {{
+        {name}(flags)
+    }}
+}}
+
+impl From<u32> for {name} {{
+    fn from(f: u32) -> Self {{
+        {name}(f)
+    }}
+}}
+
+impl From<{name}> for u32 {{
+    fn from(f: {name}) -> Self {{
+        f.0
+    }}
+}}
+
+impl TryFrom<ParameterValue> for {name} {{
+    type Error = Error;
+
+    fn try_from(value: ParameterValue) -> Result<Self, Self::Error> {{
+        match value {{
+            ParameterValue::U32(f) => Ok({name}(f)),
+            _ => Err(Error::ValueTypeMismatch),
+        }}
+    }}
+}}
+
+impl From<{name}> for ParameterValue {{
+    fn from(f: {name}) -> Self {{
+        ParameterValue::U32(f.0)
+    }}
+}}
+
+impl FromStr for {name} {{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {{
+        parse_flag_names(&Self::NAMES, s).map({name})
+    }}
+}}
+
+impl fmt::Display for {name} {{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {{
+        write_flag_names(f, &Self::NAMES, self.0)
+    }}
+}}
+"#,
+            desc = self.desc,
+            name = name,
+            fields = fields.concat(),
+            consts = consts.concat(),
+            len = bits.len(),
+            names = names.join(", "),
+        )
+    }
+
+    fn is_command(&self) -> bool {
+        self.value_type == "command"
+    }
+
+    /// Commands take and return Notification when they have no argument or
+    /// result, otherwise they're typed by a zero value
+    fn gen_command(&self) -> String {
+        assert!(
+            self.value.is_none(),
+            "{} command can't have a value",
+            self.name
+        );
+        let zero = |t: &Option<String>| match t {
+            Some(t) => format!("Some({})", gen_zero_value(&self.name, t)),
+            None => String::from("None"),
+        };
+        format!(
+            "    Command::new(ParameterId::new({}), {:?}, {}, {}),\n",
+            self.id,
+            self.name,
+            zero(&self.arg_type),
+            zero(&self.result_type),
+        )
+    }
+
+    fn value_type_id(&self) -> ParameterValueTypeId {
+        // Arrays are described by their element type and length, "f32[4]"
+        if self.is_command() {
+            ParameterValueTypeId::Notification
+        } else if self.is_enum() {
+            ParameterValueTypeId::U8
+        } else if self.is_flags() {
+            ParameterValueTypeId::U32
+        } else if self.value_type.contains('[') {
+            ParameterValueTypeId::Array
+        } else {
+            ParameterValueTypeId::from_str(&self.value_type).unwrap()
+        }
+    }
+
+    fn gen_param_flags(&self) -> String {
+        let ro = self.ro.unwrap_or(false);
+        let bcast = self.bcast.unwrap_or(false);
+        let bcast_on_change = self.bcast_on_change.unwrap_or(false);
+        // Commands are invoked, never set
+        let constant = self.constant.unwrap_or(false) || self.is_command();
+
+        let flags: Vec<&str> = [
+            (ro, "RO"),
+            (bcast, "BCAST"),
+            (constant, "CONST"),
+            (bcast_on_change, "BCAST_ON_CHANGE"),
+        ]
+        .iter()
+        .filter(|(set, _)| *set)
+        .map(|(_, name)| *name)
+        .collect();
+        format!(
+            "ParameterFlags::new_from_flags({})",
+            if flags.is_empty() {
+                String::from("0")
+            } else {
+                flags.join(" | ")
+            }
+        )
+    }
+
+    /// Units and range are optional, a range needs both min and max
+    fn gen_description(&self) -> String {
+        // Strings are length prefixed by a u8 on the wire
+        for s in [&self.name, &self.desc]
+            .iter()
+            .chain(self.units.as_ref().iter())
+        {
+            assert!(s.len() <= 255, "{} description string too long", self.name);
+        }
+        let units = match &self.units {
+            Some(u) => format!(".with_units({:?})", u),
+            None => String::new(),
+        };
+        let range = match (self.min, self.max) {
+            (Some(min), Some(max)) => {
+                assert!(min <= max, "{} min is greater than max", self.name);
+                format!(".with_range({:?}_f64, {:?}_f64)", min, max)
+            }
+            (None, None) => String::new(),
+            _ => panic!("{} range needs both min and max", self.name),
+        };
+        format!(
+            "    Description::new(ParameterId::new({}), {}, ParameterValueTypeId::{:?}, {:?}, {:?}){}{},\n",
+            self.id,
+            self.gen_param_flags(),
+            self.value_type_id(),
+            self.name,
+            self.desc,
+            units,
+            range,
+        )
+    }
+
     fn gen_param(&self) -> String {
-        let value_type = ParameterValueTypeId::from_str(&self.value_type).unwrap();
+        let value_type = self.value_type_id();
 
         let value = match value_type {
+            _ if self.is_enum() => self.gen_enum_value(),
+            _ if self.is_flags() => self.gen_flags_value(),
             ParameterValueTypeId::None => String::from("ParameterValue::None"),
             ParameterValueTypeId::Notification => String::from("ParameterValue::Notification"),
-            ParameterValueTypeId::Bool => String::from(format!(
+            ParameterValueTypeId::Bool => format!(
                 "ParameterValue::Bool({})",
                 bool::from_str(self.value.as_ref().unwrap()).unwrap()
-            )),
-            ParameterValueTypeId::U8 => String::from(format!(
+            ),
+            ParameterValueTypeId::U8 => format!(
                 "ParameterValue::U8({})",
                 u8::from_str(self.value.as_ref().unwrap()).unwrap()
-            )),
-            ParameterValueTypeId::I8 => String::from(format!(
+            ),
+            ParameterValueTypeId::I8 => format!(
                 "ParameterValue::I8({})",
                 i8::from_str(self.value.as_ref().unwrap()).unwrap()
-            )),
-            ParameterValueTypeId::U32 => String::from(format!(
+            ),
+            ParameterValueTypeId::U16 => format!(
+                "ParameterValue::U16({})",
+                u16::from_str(self.value.as_ref().unwrap()).unwrap()
+            ),
+            ParameterValueTypeId::I16 => format!(
+                "ParameterValue::I16({})",
+                i16::from_str(self.value.as_ref().unwrap()).unwrap()
+            ),
+            ParameterValueTypeId::U32 => format!(
                 "ParameterValue::U32({})",
                 u32::from_str(self.value.as_ref().unwrap()).unwrap()
-            )),
-            ParameterValueTypeId::I32 => String::from(format!(
+            ),
+            ParameterValueTypeId::I32 => format!(
                 "ParameterValue::I32({})",
                 i32::from_str(self.value.as_ref().unwrap()).unwrap()
-            )),
-            ParameterValueTypeId::U64 => String::from(format!(
+            ),
+            ParameterValueTypeId::U64 => format!(
                 "ParameterValue::U64({})",
                 u64::from_str(self.value.as_ref().unwrap()).unwrap()
-            )),
-            ParameterValueTypeId::I64 => String::from(format!(
+            ),
+            ParameterValueTypeId::I64 => format!(
                 "ParameterValue::I64({})",
                 i64::from_str(self.value.as_ref().unwrap()).unwrap()
-            )),
-            ParameterValueTypeId::F32 => String::from(format!(
+            ),
+            ParameterValueTypeId::F32 => format!(
                 "ParameterValue::F32({}_f32)",
                 f32::from_str(self.value.as_ref().unwrap()).unwrap()
-            )),
+            ),
+            ParameterValueTypeId::F64 => format!(
+                "ParameterValue::F64({}_f64)",
+                f64::from_str(self.value.as_ref().unwrap()).unwrap()
+            ),
+            ParameterValueTypeId::Str => {
+                let text = self.value.as_ref().map_or("", String::as_str);
+                let s = ParameterValueStr::from_str(text).unwrap();
+                let mut buf = [0_u8; MAX_STR_LEN];
+                buf[..s.len()].copy_from_slice(s.as_bytes());
+                format!(
+                    "ParameterValue::Str(ParameterValueStr::from_raw_parts({}, {:?}))",
+                    s.len(),
+                    buf
+                )
+            }
+            ParameterValueTypeId::Bytes => {
+                let b =
+                    ParameterValueBytes::from_hex(self.value.as_ref().map_or("", String::as_str))
+                        .unwrap();
+                let mut buf = [0_u8; MAX_BYTES_LEN];
+                buf[..b.len()].copy_from_slice(b.as_slice());
+                format!(
+                    "ParameterValue::Bytes(ParameterValueBytes::from_raw_parts({}, {:?}))",
+                    b.len(),
+                    buf
+                )
+            }
+            ParameterValueTypeId::Array => {
+                let (elem, len) = ParameterValueArray::parse_type(&self.value_type).unwrap();
+                // Defaults to all zeros when no value is given
+                let mut buf = [0_u8; MAX_ARRAY_WIRE_SIZE];
+                if let Some(v) = &self.value {
+                    let a = ParameterValueArray::parse(elem, v).unwrap();
+                    assert_eq!(a.len(), len, "{} array length mismatch", self.name);
+                    buf[..a.as_le_bytes().len()].copy_from_slice(a.as_le_bytes());
+                }
+                format!(
+                    "ParameterValue::Array(ParameterValueArray::from_raw_parts(ParameterValueTypeId::{:?}, {}, {:?}))",
+                    elem,
+                    len,
+                    buf
+                )
+            }
         };
 
-        let ro = self.ro.unwrap_or(false);
-        let bcast = self.bcast.unwrap_or(false);
-        let bcast_on_change = self.bcast_on_change.unwrap_or(false);
-        let constant = self.constant.unwrap_or(false);
-
-        let flags = String::from(format!(
-            "ParameterFlags::new_from_flags({} | {} | {} | {})",
-            if ro { "RO" } else { "0" },
-            if bcast { "BCAST" } else { "0" },
-            if constant { "CONST" } else { "0" },
-            if bcast_on_change {
-                "BCAST_ON_CHANGE"
-            } else {
-                "0"
-            },
-        ));
-
         format!(
             r#"
 pub const {}: Parameter = Parameter::new_with_value(
@@ -281,12 +819,46 @@ pub const {}: Parameter = Parameter::new_with_value(
 "#,
             self.name.to_ascii_uppercase(),
             self.id,
-            flags,
+            self.gen_param_flags(),
             value,
         )
     }
 }
 
+/// Zero value of a value type, arrays are given as type[len] ("f32[4]")
+fn gen_zero_value(name: &str, value_type: &str) -> String {
+    if value_type.contains('[') {
+        let (elem, len) = ParameterValueArray::parse_type(value_type)
+            .unwrap_or_else(|_| panic!("{} has an invalid array type {}", name, value_type));
+        return format!(
+            "ParameterValue::Array(ParameterValueArray::from_raw_parts(ParameterValueTypeId::{:?}, {}, [0; {}]))",
+            elem, len, MAX_ARRAY_WIRE_SIZE
+        );
+    }
+    let t = ParameterValueTypeId::from_str(value_type)
+        .unwrap_or_else(|_| panic!("{} has an invalid type {}", name, value_type));
+    match t {
+        ParameterValueTypeId::None => String::from("ParameterValue::None"),
+        ParameterValueTypeId::Notification => String::from("ParameterValue::Notification"),
+        ParameterValueTypeId::Bool => String::from("ParameterValue::Bool(false)"),
+        ParameterValueTypeId::F32 => String::from("ParameterValue::F32(0.0)"),
+        ParameterValueTypeId::F64 => String::from("ParameterValue::F64(0.0)"),
+        ParameterValueTypeId::Str => format!(
+            "ParameterValue::Str(ParameterValueStr::from_raw_parts(0, [0; {}]))",
+            MAX_STR_LEN
+        ),
+        ParameterValueTypeId::Bytes => format!(
+            "ParameterValue::Bytes(ParameterValueBytes::from_raw_parts(0, [0; {}]))",
+            MAX_BYTES_LEN
+        ),
+        ParameterValueTypeId::Array => {
+            panic!("{} array types need an element type and length", name)
+        }
+        // The integer types
+        t => format!("ParameterValue::{:?}(0)", t),
+    }
+}
+
 fn has_unique_elements<T>(iter: T) -> bool
 where
     T: IntoIterator,
@@ -295,3 +867,17 @@ where
     let mut uniq = HashSet::new();
     iter.into_iter().all(move |x| uniq.insert(x))
 }
+
+/// snake_case to PascalCase, used for generated type and variant names
+fn to_pascal_case(s: &str) -> String {
+    s.split(|c| c == '_' || c == '-' || c == ' ')
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
@@ -4,6 +4,11 @@ pub mod node_desc;
 pub mod node_id;
 pub mod node_name;
 pub mod param;
+pub mod param_command;
 pub mod param_desc;
+pub mod param_description;
+pub mod param_enum;
+pub mod param_flags;
 pub mod param_id;
 pub mod param_name;
+pub mod paramdb;
@@ -1,6 +1,9 @@
 #![allow(unused_imports)]
 
 use params::flags::{BCAST, BCAST_ON_CHANGE, CONST, RO};
-use params::{Parameter, ParameterFlags, ParameterId, ParameterValue};
+use params::{
+    Parameter, ParameterFlags, ParameterId, ParameterValue, ParameterValueArray,
+    ParameterValueBytes, ParameterValueStr, ParameterValueTypeId,
+};
 
 include! {concat!(env!("OUT_DIR"), "/param_gen.rs")}
@@ -0,0 +1,108 @@
+#![allow(unused_imports)]
+
+use params::{
+    Error, Parameter, ParameterFlags, ParameterId, ParameterValue, ParameterValueArray,
+    ParameterValueBytes, ParameterValueStr, ParameterValueTypeId,
+};
+
+/// A command parameter, invoked with the Invoke op
+///
+/// The argument and result are typed by zero values, `None` means the
+/// command takes or returns a Notification.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Command {
+    id: ParameterId,
+    name: &'static str,
+    arg: Option<ParameterValue>,
+    result: Option<ParameterValue>,
+}
+
+impl Command {
+    pub const fn new(
+        id: ParameterId,
+        name: &'static str,
+        arg: Option<ParameterValue>,
+        result: Option<ParameterValue>,
+    ) -> Self {
+        Command {
+            id,
+            name,
+            arg,
+            result,
+        }
+    }
+
+    pub fn id(&self) -> ParameterId {
+        self.id
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Zero value of the argument type
+    pub fn arg(&self) -> Option<ParameterValue> {
+        self.arg
+    }
+
+    /// Zero value of the result type
+    pub fn result(&self) -> Option<ParameterValue> {
+        self.result
+    }
+
+    pub fn check_arg(&self, value: ParameterValue) -> Result<(), Error> {
+        check_value(self.arg, value)
+    }
+
+    pub fn check_result(&self, value: ParameterValue) -> Result<(), Error> {
+        check_value(self.result, value)
+    }
+}
+
+fn check_value(zero: Option<ParameterValue>, value: ParameterValue) -> Result<(), Error> {
+    let mut p = Parameter::new_with_value(
+        ParameterId::new(0),
+        ParameterFlags::default(),
+        zero.unwrap_or(ParameterValue::Notification),
+    );
+    p.set_value(value)
+}
+
+include! {concat!(env!("OUT_DIR"), "/param_command_gen.rs")}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::param::{REBOOT, SELF_TEST};
+    use crate::param_description::param_description;
+
+    #[test]
+    fn lookups() {
+        let c = param_command(REBOOT.id()).unwrap();
+        assert_eq!(c.name(), "reboot");
+        assert_eq!(c.arg(), None);
+        assert_eq!(c.check_arg(ParameterValue::Notification), Ok(()));
+        assert_eq!(
+            c.check_arg(ParameterValue::U32(1)),
+            Err(Error::ValueTypeMismatch)
+        );
+        assert!(REBOOT.flags().constant());
+
+        let c = param_command_by_name("self_test").unwrap();
+        assert_eq!(c.id(), SELF_TEST.id());
+        assert_eq!(c.arg(), Some(ParameterValue::U32(0)));
+        assert_eq!(c.check_arg(ParameterValue::U32(3)), Ok(()));
+        assert_eq!(c.check_result(ParameterValue::U32(0)), Ok(()));
+        assert_eq!(
+            c.check_result(ParameterValue::Notification),
+            Err(Error::ValueTypeMismatch)
+        );
+        assert_eq!(
+            param_description(SELF_TEST.id()).map(|d| d.value_type()),
+            Some(ParameterValueTypeId::Notification)
+        );
+
+        assert_eq!(param_command(ParameterId::new(100)), None);
+        assert_eq!(param_command_by_name("uptime"), None);
+    }
+}
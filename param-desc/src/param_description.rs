@@ -0,0 +1,33 @@
+#![allow(unused_imports)]
+
+use params::flags::{BCAST, BCAST_ON_CHANGE, CONST, RO};
+use params::{Description, ParameterFlags, ParameterId, ParameterValueTypeId};
+
+include! {concat!(env!("OUT_DIR"), "/param_description_gen.rs")}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::param::{BCAST_INTERVAL, HVAC_MODE, ZONE_TEMPERATURES};
+    use crate::param_name::param_name;
+
+    #[test]
+    fn lookups() {
+        let d = param_description(BCAST_INTERVAL.id()).unwrap();
+        assert_eq!(Some(d.name()), param_name(BCAST_INTERVAL.id()));
+        assert_eq!(d.flags(), BCAST_INTERVAL.flags());
+        assert_eq!(d.value_type(), BCAST_INTERVAL.value().type_id());
+        assert_eq!(d.units(), Some("s"));
+        assert_eq!(d.range(), Some((1.0, 3600.0)));
+
+        let d = param_description(HVAC_MODE.id()).unwrap();
+        assert_eq!(d.value_type(), ParameterValueTypeId::U8);
+        assert_eq!(d.units(), None);
+        assert_eq!(d.range(), None);
+
+        let d = param_description(ZONE_TEMPERATURES.id()).unwrap();
+        assert_eq!(d.value_type(), ParameterValueTypeId::Array);
+
+        assert_eq!(param_description(ParameterId::new(0)), None);
+    }
+}
@@ -0,0 +1,65 @@
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+use params::{Error, ParameterId, ParameterValue};
+
+include! {concat!(env!("OUT_DIR"), "/param_enum_gen.rs")}
+
+/// Variant name of an enum parameter value
+pub fn param_enum_name(id: ParameterId, value: ParameterValue) -> Option<&'static str> {
+    match value {
+        ParameterValue::U8(index) => param_enum_names(id)?.get(usize::from(index)).copied(),
+        _ => None,
+    }
+}
+
+/// Enum parameter value from a variant name
+pub fn param_enum_value(id: ParameterId, name: &str) -> Option<ParameterValue> {
+    param_enum_names(id)?
+        .iter()
+        .position(|n| *n == name)
+        .map(|index| ParameterValue::U8(index as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::param_id::HVAC_MODE;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(HvacMode::from_name("heat"), Some(HvacMode::Heat));
+        assert_eq!(HvacMode::from_str("cool"), Ok(HvacMode::Cool));
+        assert_eq!(HvacMode::from_str("warm"), Err(Error::ParseValue));
+        assert_eq!(HvacMode::Auto.name(), "auto");
+        assert_eq!(ParameterValue::from(HvacMode::Heat), ParameterValue::U8(1));
+        assert_eq!(
+            HvacMode::try_from(ParameterValue::U8(3)),
+            Ok(HvacMode::Auto)
+        );
+        assert_eq!(
+            HvacMode::try_from(ParameterValue::U8(4)),
+            Err(Error::ParseValue)
+        );
+        assert_eq!(
+            HvacMode::try_from(ParameterValue::U32(1)),
+            Err(Error::ValueTypeMismatch)
+        );
+    }
+
+    #[test]
+    fn lookups() {
+        assert_eq!(param_enum_names(HVAC_MODE), Some(&HvacMode::NAMES[..]));
+        assert_eq!(param_enum_names(ParameterId::new(0)), None);
+        assert_eq!(
+            param_enum_name(HVAC_MODE, ParameterValue::U8(2)),
+            Some("cool")
+        );
+        assert_eq!(param_enum_name(HVAC_MODE, ParameterValue::U8(9)), None);
+        assert_eq!(
+            param_enum_value(HVAC_MODE, "heat"),
+            Some(ParameterValue::U8(1))
+        );
+        assert_eq!(param_enum_value(HVAC_MODE, "warm"), None);
+    }
+}
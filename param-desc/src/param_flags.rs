@@ -0,0 +1,124 @@
+use bitfield::bitfield;
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+use params::{Error, ParameterId, ParameterValue};
+
+include! {concat!(env!("OUT_DIR"), "/param_flags_gen.rs")}
+
+/// Bit set of a flags parameter value from `|` separated bit names
+pub fn param_flags_value(id: ParameterId, s: &str) -> Option<ParameterValue> {
+    parse_flag_names(param_flag_names(id)?, s)
+        .ok()
+        .map(ParameterValue::U32)
+}
+
+/// Names of the bits set in `bits`, `names[n]` is the name of bit n
+pub fn flag_names_set<'a>(names: &'a [&'a str], bits: u32) -> impl Iterator<Item = &'a str> + 'a {
+    names
+        .iter()
+        .enumerate()
+        .filter(move |(bit, _)| bits & (1_u32 << bit) != 0)
+        .map(|(_, name)| *name)
+}
+
+/// Parse `|` separated bit names (`pump|fan`), unnamed bits can be given
+/// in hex (`0x100`) and `0` is the empty set
+pub fn parse_flag_names(names: &[&str], s: &str) -> Result<u32, Error> {
+    s.split('|')
+        .map(str::trim)
+        .filter(|n| !n.is_empty() && *n != "0")
+        .try_fold(0, |bits, n| {
+            if n.starts_with("0x") {
+                u32::from_str_radix(&n[2..], 16)
+                    .map(|v| bits | v)
+                    .map_err(|_| Error::ParseValue)
+            } else {
+                names
+                    .iter()
+                    .position(|name| *name == n)
+                    .map(|bit| bits | (1 << bit))
+                    .ok_or(Error::ParseValue)
+            }
+        })
+}
+
+/// Write `bits` as `|` separated bit names, unnamed bits are written in hex
+/// and the empty set as `0`
+pub fn write_flag_names<W: fmt::Write>(w: &mut W, names: &[&str], bits: u32) -> fmt::Result {
+    let named = (!0_u32).checked_shr(32 - names.len() as u32).unwrap_or(0);
+    let mut sep = "";
+    for name in flag_names_set(names, bits) {
+        write!(w, "{}{}", sep, name)?;
+        sep = "|";
+    }
+    let unnamed = bits & !named;
+    if unnamed != 0 {
+        write!(w, "{}0x{:X}", sep, unnamed)
+    } else if bits == 0 {
+        write!(w, "0")
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::param_id::IO_STATE;
+    extern crate std;
+    use std::string::String;
+
+    #[test]
+    fn bitfield() {
+        let mut f = IoState::new();
+        f.set_fan(true);
+        f.set_alarm(true);
+        assert_eq!(f, IoState::new_from_flags(IoState::FAN | IoState::ALARM));
+        assert_eq!(ParameterValue::from(f), ParameterValue::U32(0b1010));
+        assert_eq!(IoState::try_from(ParameterValue::U32(0b1010)), Ok(f));
+        assert_eq!(
+            IoState::try_from(ParameterValue::U8(1)),
+            Err(Error::ValueTypeMismatch)
+        );
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(
+            IoState::from_str("pump|fan"),
+            Ok(IoState::new_from_flags(0b11))
+        );
+        assert_eq!(
+            IoState::from_str(" heater "),
+            Ok(IoState::new_from_flags(0b100))
+        );
+        assert_eq!(IoState::from_str("0"), Ok(IoState::new()));
+        assert_eq!(
+            IoState::from_str("pump|0x100"),
+            Ok(IoState::new_from_flags(0x101))
+        );
+        assert_eq!(IoState::from_str("pump|door"), Err(Error::ParseValue));
+
+        let mut s = String::new();
+        write_flag_names(&mut s, &IoState::NAMES, 0b11).unwrap();
+        assert_eq!(s, "pump|fan");
+        s.clear();
+        write_flag_names(&mut s, &IoState::NAMES, 0x101).unwrap();
+        assert_eq!(s, "pump|0x100");
+        s.clear();
+        write_flag_names(&mut s, &IoState::NAMES, 0).unwrap();
+        assert_eq!(s, "0");
+    }
+
+    #[test]
+    fn lookups() {
+        assert_eq!(param_flag_names(IO_STATE), Some(&IoState::NAMES[..]));
+        assert_eq!(param_flag_names(ParameterId::new(0)), None);
+        assert_eq!(
+            param_flags_value(IO_STATE, "fan|alarm"),
+            Some(ParameterValue::U32(0b1010))
+        );
+        assert_eq!(param_flags_value(IO_STATE, "door"), None);
+    }
+}
@@ -0,0 +1,231 @@
+use crate::value_str::Str;
+use crate::{
+    AnnouncePacket, Error, GetSetFlags, GetSetFrame, GetSetNodeId, GetSetOp, GetSetPayloadType,
+    GetSetSequence, GetSetVersion, PREAMBLE_WORD,
+};
+use core::fmt;
+
+/// What a node announces about itself, broadcast at boot and in
+/// response to a Discover request
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Announce {
+    min_version: GetSetVersion,
+    max_version: GetSetVersion,
+    paramdb_hash: u32,
+    tcp_ip: [u8; 4],
+    tcp_port: u16,
+    fw_version: Str,
+}
+
+impl Announce {
+    /// The firmware version is limited to MAX_STR_LEN bytes
+    pub fn new(
+        min_version: GetSetVersion,
+        max_version: GetSetVersion,
+        paramdb_hash: u32,
+        tcp_ip: [u8; 4],
+        tcp_port: u16,
+        fw_version: &str,
+    ) -> Result<Self, Error> {
+        Ok(Announce {
+            min_version,
+            max_version,
+            paramdb_hash,
+            tcp_ip,
+            tcp_port,
+            fw_version: Str::from_utf8(fw_version.as_bytes())?,
+        })
+    }
+
+    pub fn min_version(&self) -> GetSetVersion {
+        self.min_version
+    }
+
+    pub fn max_version(&self) -> GetSetVersion {
+        self.max_version
+    }
+
+    /// CRC32 of the paramdb the node was built with, nodes built
+    /// from a different paramdb may disagree on parameter IDs
+    pub fn paramdb_hash(&self) -> u32 {
+        self.paramdb_hash
+    }
+
+    pub fn tcp_ip(&self) -> [u8; 4] {
+        self.tcp_ip
+    }
+
+    pub fn tcp_port(&self) -> u16 {
+        self.tcp_port
+    }
+
+    pub fn fw_version(&self) -> &str {
+        self.fw_version.as_str()
+    }
+
+    /// Size of the emitted frame in the given version's layout, the flags
+    /// determine its trailers
+    pub fn wire_size(&self, version: GetSetVersion, flags: GetSetFlags) -> usize {
+        GetSetFrame::<&[u8]>::frame_len(
+            version,
+            flags,
+            AnnouncePacket::<&[u8]>::buffer_len(self.fw_version.len()),
+        )
+    }
+
+    /// Parse an Announce frame
+    pub fn parse<T: AsRef<[u8]> + ?Sized>(frame: &GetSetFrame<&T>) -> Result<Self, Error> {
+        frame.check_len()?;
+        frame.check_preamble()?;
+        frame.check_version()?;
+        frame.check_payload_len()?;
+        frame.check_checksum()?;
+        if frame.op()? != GetSetOp::Announce {
+            return Err(Error::WireInvalidOp);
+        }
+        if frame.payload_type()? != GetSetPayloadType::AnnouncePacket {
+            return Err(Error::WireInvalidPayloadType);
+        }
+        let p = AnnouncePacket::new_checked(frame.checked_payload()?)?;
+        Announce::new(
+            p.min_version(),
+            p.max_version(),
+            p.paramdb_hash(),
+            p.tcp_ip(),
+            p.tcp_port(),
+            p.fw_version()?,
+        )
+    }
+
+    /// Emit an Announce frame. Broadcasts use the v1 layout so any client
+    /// can read them, replies to Discover echo the request's version and
+    /// sequence number like a Response does.
+    pub fn emit<T: AsRef<[u8]> + AsMut<[u8]>>(
+        &self,
+        node_id: GetSetNodeId,
+        flags: GetSetFlags,
+        version: GetSetVersion,
+        sequence: GetSetSequence,
+        frame: &mut GetSetFrame<T>,
+    ) -> Result<(), Error> {
+        let payload_len = AnnouncePacket::<&[u8]>::buffer_len(self.fw_version.len());
+        frame.set_preamble(PREAMBLE_WORD);
+        frame.set_node_id(node_id);
+        frame.set_flags(flags);
+        frame.set_version(version);
+        frame.set_sequence(sequence);
+        frame.set_op(GetSetOp::Announce);
+        frame.set_payload_type(GetSetPayloadType::AnnouncePacket);
+        frame.set_payload_size(payload_len as u16);
+        let payload = frame
+            .payload_mut()
+            .get_mut(..payload_len)
+            .ok_or(Error::WireTruncated)?;
+        let mut p = AnnouncePacket::new_unchecked(payload);
+        p.set_min_version(self.min_version);
+        p.set_max_version(self.max_version);
+        p.set_paramdb_hash(self.paramdb_hash);
+        p.set_tcp_ip(self.tcp_ip);
+        p.set_tcp_port(self.tcp_port);
+        p.set_fw_version(self.fw_version());
+        frame.fill_checksum();
+        Ok(())
+    }
+}
+
+impl fmt::Display for Announce {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ip = self.tcp_ip;
+        write!(
+            f,
+            "Announce {{ tcp: {}.{}.{}.{}:{} fw: {} versions: {}..={} paramdb: 0x{:08X} }}",
+            ip[0],
+            ip[1],
+            ip[2],
+            ip[3],
+            self.tcp_port,
+            self.fw_version(),
+            self.min_version,
+            self.max_version,
+            self.paramdb_hash
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        GETSET_FLAG_CRC32, GETSET_MAX_VERSION, GETSET_MIN_VERSION, GETSET_VERSION_1,
+        GETSET_VERSION_2,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn announce() -> Announce {
+        Announce::new(
+            GETSET_MIN_VERSION,
+            GETSET_MAX_VERSION,
+            0x1234_5678,
+            [192, 168, 1, 39],
+            9877,
+            "0.1.0",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let a = announce();
+        let mut bytes = [0xFF; 64];
+        let size = a.wire_size(GETSET_VERSION_1, GETSET_FLAG_CRC32);
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..]);
+        assert_eq!(
+            a.emit(1, GETSET_FLAG_CRC32, GETSET_VERSION_1, 0, &mut frame),
+            Ok(())
+        );
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(frame.node_id(), 1);
+        assert_eq!(frame.version(), GETSET_VERSION_1);
+        assert_eq!(Announce::parse(&frame), Ok(a));
+        assert_eq!(a.fw_version(), "0.1.0");
+    }
+
+    #[test]
+    fn round_trip_v2() {
+        let a = announce();
+        let mut bytes = [0xFF; 64];
+        let size = a.wire_size(GETSET_VERSION_2, GETSET_FLAG_CRC32);
+        assert_eq!(size, a.wire_size(GETSET_VERSION_1, GETSET_FLAG_CRC32) + 4);
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..]);
+        assert_eq!(
+            a.emit(1, GETSET_FLAG_CRC32, GETSET_VERSION_2, 0xABCD, &mut frame),
+            Ok(())
+        );
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(frame.version(), GETSET_VERSION_2);
+        assert_eq!(frame.sequence(), 0xABCD);
+        assert_eq!(Announce::parse(&frame), Ok(a));
+    }
+
+    #[test]
+    fn parse_rejects_other_ops() {
+        let mut bytes = [0xFF; 64];
+        let size = announce().wire_size(GETSET_VERSION_1, 0);
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..]);
+        announce()
+            .emit(1, 0, GETSET_VERSION_1, 0, &mut frame)
+            .unwrap();
+        frame.set_op(GetSetOp::Get);
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(Announce::parse(&frame), Err(Error::WireInvalidOp));
+    }
+
+    #[test]
+    fn fw_version_capacity() {
+        let long = "0.1.0-0123456789abcdef0123456789abcdef";
+        assert_eq!(
+            Announce::new(1, 2, 0, [0; 4], 0, long),
+            Err(Error::Capacity)
+        );
+    }
+}
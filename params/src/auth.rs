@@ -0,0 +1,186 @@
+use crate::{Error, GetSetFrame, GETSET_FLAG_AUTH};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+pub const KEY_SIZE: usize = 32;
+
+pub type Key = [u8; KEY_SIZE];
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Signs and verifies frames with a key shared between two nodes.
+///
+/// Each signed frame carries a counter that must be higher than the last one
+/// accepted, so captured frames cannot be replayed. The counter is only kept
+/// in memory, senders should derive it from a clock so it keeps increasing
+/// across restarts.
+///
+/// The tag also covers the verifier's epoch, which it picks at random every
+/// boot and senders learn from its Capabilities. Frames captured before a
+/// restart no longer verify after it, although the counter starts over.
+#[derive(Clone)]
+pub struct Authenticator {
+    key: Key,
+    epoch: u64,
+    counter: u64,
+}
+
+impl Authenticator {
+    pub fn new(key: Key) -> Self {
+        Authenticator {
+            key,
+            epoch: 0,
+            counter: 0,
+        }
+    }
+
+    /// Epoch of the verifying node covered by the tags
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn set_epoch(&mut self, epoch: u64) {
+        self.epoch = epoch;
+    }
+
+    /// Last counter signed or accepted
+    pub fn counter(&self) -> u64 {
+        self.counter
+    }
+
+    pub fn set_counter(&mut self, counter: u64) {
+        self.counter = counter;
+    }
+
+    /// Sign an emitted frame with the next counter, the frame must have the
+    /// auth flag set. The CRC32 trailer is refilled since it covers the tag.
+    pub fn sign<T: AsRef<[u8]> + AsMut<[u8]>>(
+        &mut self,
+        frame: &mut GetSetFrame<T>,
+    ) -> Result<(), Error> {
+        if frame.flags() & GETSET_FLAG_AUTH == 0 {
+            return Err(Error::AuthRequired);
+        }
+        frame.check_payload_len()?;
+        let counter = self.counter.checked_add(1).ok_or(Error::AuthReplay)?;
+        frame.set_auth_counter(counter);
+        let mut mac = self.mac();
+        mac.input(&self.epoch.to_le_bytes());
+        mac.input(frame.auth_data());
+        frame.auth_tag_mut().copy_from_slice(&mac.result().code());
+        frame.fill_checksum();
+        self.counter = counter;
+        Ok(())
+    }
+
+    /// Verify the tag and counter of a received frame, the counter is
+    /// recorded once the frame is accepted
+    pub fn verify<T: AsRef<[u8]>>(&mut self, frame: &GetSetFrame<T>) -> Result<(), Error> {
+        frame.check_payload_len()?;
+        let (counter, tag) = match (frame.auth_counter(), frame.auth_tag()) {
+            (Some(counter), Some(tag)) => (counter, tag),
+            _ => return Err(Error::AuthRequired),
+        };
+        let mut mac = self.mac();
+        mac.input(&self.epoch.to_le_bytes());
+        mac.input(frame.auth_data());
+        mac.verify(tag).map_err(|_| Error::AuthFailed)?;
+        if counter <= self.counter {
+            return Err(Error::AuthReplay);
+        }
+        self.counter = counter;
+        Ok(())
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        // HMAC accepts keys of any length
+        HmacSha256::new_varkey(&self.key[..]).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GetSetOp, GetSetPayloadType, Request, GETSET_FLAG_CRC32};
+    use pretty_assertions::assert_eq;
+
+    const KEY: Key = [0x5A; KEY_SIZE];
+
+    fn signed_request(auth: &mut Authenticator, flags: u32) -> ([u8; 64], usize) {
+        let req = Request::new(1, flags, GetSetOp::ListAll, GetSetPayloadType::None);
+        let mut bytes = [0xFF; 64];
+        let size = req.wire_size();
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..size]);
+        assert_eq!(req.emit(&mut frame), Ok(()));
+        assert_eq!(auth.sign(&mut frame), Ok(()));
+        (bytes, size)
+    }
+
+    #[test]
+    fn sign_verify() {
+        let mut tx = Authenticator::new(KEY);
+        let mut rx = Authenticator::new(KEY);
+        let (bytes, size) = signed_request(&mut tx, GETSET_FLAG_AUTH | GETSET_FLAG_CRC32);
+        assert_eq!(tx.counter(), 1);
+        assert_eq!(size, 17 + 40 + 4);
+
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(frame.auth_counter(), Some(1));
+        assert_eq!(rx.verify(&frame), Ok(()));
+        assert_eq!(rx.counter(), 1);
+
+        // Replayed
+        assert_eq!(rx.verify(&frame), Err(Error::AuthReplay));
+
+        let (bytes, size) = signed_request(&mut tx, GETSET_FLAG_AUTH);
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(rx.verify(&frame), Ok(()));
+        assert_eq!(rx.counter(), 2);
+    }
+
+    #[test]
+    fn restart() {
+        let mut tx = Authenticator::new(KEY);
+        tx.set_epoch(0x1234);
+        let mut rx = Authenticator::new(KEY);
+        rx.set_epoch(0x1234);
+        let (bytes, size) = signed_request(&mut tx, GETSET_FLAG_AUTH);
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(rx.verify(&frame), Ok(()));
+
+        // The restarted node lost its counter but picked a new epoch
+        let mut rx = Authenticator::new(KEY);
+        rx.set_epoch(0x5678);
+        assert_eq!(rx.verify(&frame), Err(Error::AuthFailed));
+        assert_eq!(rx.counter(), 0);
+
+        // Senders that learned the new epoch are accepted
+        tx.set_epoch(rx.epoch());
+        let (bytes, size) = signed_request(&mut tx, GETSET_FLAG_AUTH);
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(rx.verify(&frame), Ok(()));
+    }
+
+    #[test]
+    fn verify_invalid() {
+        let mut tx = Authenticator::new(KEY);
+        let mut rx = Authenticator::new([0xA5; KEY_SIZE]);
+        let (bytes, size) = signed_request(&mut tx, GETSET_FLAG_AUTH);
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(rx.verify(&frame), Err(Error::AuthFailed));
+        assert_eq!(rx.counter(), 0);
+
+        let mut rx = Authenticator::new(KEY);
+        let mut tampered = bytes;
+        tampered[4] ^= 0x01;
+        let frame = GetSetFrame::new_checked(&tampered[..size]).unwrap();
+        assert_eq!(rx.verify(&frame), Err(Error::AuthFailed));
+
+        let req = Request::new(1, 0, GetSetOp::ListAll, GetSetPayloadType::None);
+        let mut bytes = [0xFF; 17];
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..]);
+        assert_eq!(req.emit(&mut frame), Ok(()));
+        assert_eq!(tx.sign(&mut frame), Err(Error::AuthRequired));
+        assert_eq!(rx.verify(&frame), Err(Error::AuthRequired));
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{
+    CapabilitiesPacket, Error, GetSetFlags, GetSetFrame, GetSetNodeId, GetSetOp, GetSetPayloadType,
+    GetSetSequence, GetSetVersion, PREAMBLE_WORD,
+};
+use core::fmt;
+
+/// Frame versions and ops a node supports and the epoch its auth tags
+/// cover, sent in response to a Capabilities request
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Capabilities {
+    min_version: GetSetVersion,
+    max_version: GetSetVersion,
+    ops: u32,
+    auth_epoch: u64,
+}
+
+impl Capabilities {
+    /// No ops are supported until added
+    pub fn new(min_version: GetSetVersion, max_version: GetSetVersion) -> Self {
+        Capabilities {
+            min_version,
+            max_version,
+            ops: 0,
+            auth_epoch: 0,
+        }
+    }
+
+    pub fn min_version(&self) -> GetSetVersion {
+        self.min_version
+    }
+
+    pub fn max_version(&self) -> GetSetVersion {
+        self.max_version
+    }
+
+    pub fn supports_version(&self, version: GetSetVersion) -> bool {
+        version >= self.min_version && version <= self.max_version
+    }
+
+    /// Highest version both sides support, if any
+    pub fn negotiate(&self, other: &Capabilities) -> Option<GetSetVersion> {
+        let max = self.max_version.min(other.max_version);
+        if max >= self.min_version.max(other.min_version) {
+            Some(max)
+        } else {
+            None
+        }
+    }
+
+    pub fn add_op(&mut self, op: GetSetOp) {
+        self.ops |= 1 << op.as_u8();
+    }
+
+    pub fn supports_op(&self, op: GetSetOp) -> bool {
+        self.ops & (1 << op.as_u8()) != 0
+    }
+
+    /// Signed requests must be signed for this epoch, see `Authenticator`
+    pub fn auth_epoch(&self) -> u64 {
+        self.auth_epoch
+    }
+
+    pub fn set_auth_epoch(&mut self, epoch: u64) {
+        self.auth_epoch = epoch;
+    }
+
+    /// Size of the emitted frame in the given version's layout, the flags
+    /// determine its trailers
+    pub fn wire_size(&self, version: GetSetVersion, flags: GetSetFlags) -> usize {
+        GetSetFrame::<&[u8]>::frame_len(version, flags, CapabilitiesPacket::<&[u8]>::buffer_len())
+    }
+
+    /// Parse a Capabilities response frame
+    pub fn parse<T: AsRef<[u8]> + ?Sized>(frame: &GetSetFrame<&T>) -> Result<Self, Error> {
+        frame.check_len()?;
+        frame.check_preamble()?;
+        frame.check_version()?;
+        frame.check_payload_len()?;
+        frame.check_checksum()?;
+        if frame.op()? != GetSetOp::Capabilities {
+            return Err(Error::WireInvalidOp);
+        }
+        if frame.payload_type()? != GetSetPayloadType::CapabilitiesPacket {
+            return Err(Error::WireInvalidPayloadType);
+        }
+        let p = CapabilitiesPacket::new_checked(frame.checked_payload()?)?;
+        Ok(Capabilities {
+            min_version: p.min_version(),
+            max_version: p.max_version(),
+            ops: p.ops(),
+            auth_epoch: p.auth_epoch(),
+        })
+    }
+
+    /// Emit a Capabilities response frame answering a request of the given
+    /// version and sequence number, which it echoes like a Response does
+    pub fn emit<T: AsRef<[u8]> + AsMut<[u8]>>(
+        &self,
+        node_id: GetSetNodeId,
+        flags: GetSetFlags,
+        version: GetSetVersion,
+        sequence: GetSetSequence,
+        frame: &mut GetSetFrame<T>,
+    ) -> Result<(), Error> {
+        frame.set_preamble(PREAMBLE_WORD);
+        frame.set_node_id(node_id);
+        frame.set_flags(flags);
+        frame.set_version(version);
+        frame.set_sequence(sequence);
+        frame.set_op(GetSetOp::Capabilities);
+        frame.set_payload_type(GetSetPayloadType::CapabilitiesPacket);
+        frame.set_payload_size(CapabilitiesPacket::<&[u8]>::buffer_len() as u16);
+        let mut p = CapabilitiesPacket::new_checked(frame.payload_mut())?;
+        p.set_min_version(self.min_version);
+        p.set_max_version(self.max_version);
+        p.set_ops(self.ops);
+        p.set_auth_epoch(self.auth_epoch);
+        frame.fill_checksum();
+        Ok(())
+    }
+}
+
+impl fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Capabilities {{ versions: {}..={} ops: 0x{:X} auth_epoch: 0x{:X} }}",
+            self.min_version, self.max_version, self.ops, self.auth_epoch
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GETSET_MAX_VERSION, GETSET_MIN_VERSION, GETSET_VERSION_1, GETSET_VERSION_2};
+    use pretty_assertions::assert_eq;
+
+    static FRAME_BYTES: [u8; 31] = [
+        0xAB, 0xCD, 0xEF, 0xFF, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x04, 0x05,
+        0x0E, 0x00, 0x01, 0x02, 0x17, 0x00, 0x00, 0x00, 0xEF, 0xBE, 0xAD, 0xDE, 0x00, 0x00, 0x00,
+        0x00,
+    ];
+
+    fn caps() -> Capabilities {
+        let mut c = Capabilities::new(GETSET_MIN_VERSION, GETSET_MAX_VERSION);
+        c.add_op(GetSetOp::ListAll);
+        c.add_op(GetSetOp::Get);
+        c.add_op(GetSetOp::Set);
+        c.add_op(GetSetOp::Capabilities);
+        c.set_auth_epoch(0xDEAD_BEEF);
+        c
+    }
+
+    #[test]
+    fn ops_and_versions() {
+        let c = caps();
+        assert!(c.supports_op(GetSetOp::Get));
+        assert!(!c.supports_op(GetSetOp::Error));
+        assert!(c.supports_version(GETSET_VERSION_1));
+        assert!(!c.supports_version(3));
+        assert_eq!(
+            c.negotiate(&Capabilities::new(1, 1)),
+            Some(GETSET_VERSION_1)
+        );
+        assert_eq!(
+            c.negotiate(&Capabilities::new(1, 5)),
+            Some(GETSET_VERSION_2)
+        );
+        assert_eq!(c.negotiate(&Capabilities::new(3, 4)), None);
+    }
+
+    #[test]
+    fn emit() {
+        let mut bytes = [0xFF; 31];
+        assert_eq!(caps().wire_size(GETSET_VERSION_1, 0), bytes.len());
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..]);
+        assert_eq!(caps().emit(1, 0, GETSET_VERSION_1, 0, &mut frame), Ok(()));
+        assert_eq!(&bytes[..], &FRAME_BYTES[..]);
+    }
+
+    #[test]
+    fn emit_v2() {
+        let mut bytes = [0xFF; 35];
+        assert_eq!(caps().wire_size(GETSET_VERSION_2, 0), bytes.len());
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..]);
+        assert_eq!(
+            caps().emit(1, 0, GETSET_VERSION_2, 0x1234, &mut frame),
+            Ok(())
+        );
+        let frame = GetSetFrame::new_checked(&bytes[..]).unwrap();
+        assert_eq!(frame.version(), GETSET_VERSION_2);
+        assert_eq!(frame.sequence(), 0x1234);
+        assert_eq!(Capabilities::parse(&frame), Ok(caps()));
+    }
+
+    #[test]
+    fn parse() {
+        let frame = GetSetFrame::new_checked(&FRAME_BYTES[..]).unwrap();
+        assert_eq!(Capabilities::parse(&frame), Ok(caps()));
+        let mut bytes = FRAME_BYTES;
+        bytes[13] = GetSetOp::Get.as_u8();
+        let frame = GetSetFrame::new_checked(&bytes[..]).unwrap();
+        assert_eq!(Capabilities::parse(&frame), Err(Error::WireInvalidOp));
+    }
+}
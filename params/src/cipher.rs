@@ -0,0 +1,204 @@
+use crate::{Error, GetSetFrame, GETSET_FLAG_ENCRYPTED};
+use chacha20poly1305::aead::{AeadInPlace, NewAead};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+
+pub const KEY_SIZE: usize = 32;
+
+pub const NONCE_SIZE: usize = 12;
+
+pub type Key = [u8; KEY_SIZE];
+
+/// Encrypts and decrypts frame payloads with ChaCha20-Poly1305 using a key
+/// provisioned for a node.
+///
+/// The header is authenticated but left in the clear so frames can still be
+/// routed and matched to their request. Nonces are the salt followed by a
+/// counter, every sender sharing a key must use its own salt, picked at
+/// random each time the counter restarts.
+#[derive(Clone)]
+pub struct Cipher {
+    key: Key,
+    salt: u32,
+    counter: u64,
+}
+
+impl Cipher {
+    pub fn new(key: Key, salt: u32) -> Self {
+        Cipher {
+            key,
+            salt,
+            counter: 0,
+        }
+    }
+
+    pub fn salt(&self) -> u32 {
+        self.salt
+    }
+
+    /// Counter of the last nonce used
+    pub fn counter(&self) -> u64 {
+        self.counter
+    }
+
+    pub fn set_counter(&mut self, counter: u64) {
+        self.counter = counter;
+    }
+
+    /// Encrypt the payload of an emitted frame in place with the next nonce,
+    /// the frame must have the encrypted flag set. Seal before signing,
+    /// the CRC32 trailer is refilled since it covers the ciphertext.
+    pub fn seal<T: AsRef<[u8]> + AsMut<[u8]>>(
+        &mut self,
+        frame: &mut GetSetFrame<T>,
+    ) -> Result<(), Error> {
+        if frame.flags() & GETSET_FLAG_ENCRYPTED == 0 {
+            return Err(Error::EncryptionRequired);
+        }
+        frame.check_payload_len()?;
+        let counter = self.counter.checked_add(1).ok_or(Error::Capacity)?;
+        let mut nonce = [0; NONCE_SIZE];
+        nonce[..4].copy_from_slice(&self.salt.to_le_bytes());
+        nonce[4..].copy_from_slice(&counter.to_le_bytes());
+
+        let (header, payload, trailer) = frame.cipher_parts_mut();
+        let tag = self
+            .aead()
+            .encrypt_in_place_detached(&Nonce::from(nonce), header, payload)
+            .map_err(|_| Error::Capacity)?;
+        let (trailer_nonce, trailer_tag) = trailer.split_at_mut(NONCE_SIZE);
+        trailer_nonce.copy_from_slice(&nonce);
+        trailer_tag.copy_from_slice(&tag);
+        frame.fill_checksum();
+        self.counter = counter;
+        Ok(())
+    }
+
+    /// Decrypt the payload of a received frame in place. The CRC32 trailer is
+    /// refilled so the frame parses as usual, verify any auth tag first.
+    pub fn open<T: AsRef<[u8]> + AsMut<[u8]>>(
+        &self,
+        frame: &mut GetSetFrame<T>,
+    ) -> Result<(), Error> {
+        if frame.flags() & GETSET_FLAG_ENCRYPTED == 0 {
+            return Err(Error::EncryptionRequired);
+        }
+        frame.check_payload_len()?;
+        let (header, payload, trailer) = frame.cipher_parts_mut();
+        let (nonce, tag) = trailer.split_at(NONCE_SIZE);
+        self.aead()
+            .decrypt_in_place_detached(nonce.into(), header, payload, tag.into())
+            .map_err(|_| Error::DecryptFailed)?;
+        frame.fill_checksum();
+        Ok(())
+    }
+
+    fn aead(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(&self.key.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Authenticator, GetSetOp, GetSetPayloadType, ParameterId, RefRequest, Request,
+        GETSET_FLAG_AUTH, GETSET_FLAG_CRC32,
+    };
+    use pretty_assertions::assert_eq;
+
+    const KEY: Key = [0x3C; KEY_SIZE];
+
+    fn sealed_request(cipher: &mut Cipher, flags: u32) -> ([u8; 128], usize) {
+        let mut req = Request::new(
+            1,
+            flags,
+            GetSetOp::Get,
+            GetSetPayloadType::ParameterIdListPacket,
+        );
+        req.push_id(ParameterId::new(0x0A0B)).unwrap();
+        let mut bytes = [0xFF; 128];
+        let size = req.wire_size();
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..size]);
+        assert_eq!(req.emit(&mut frame), Ok(()));
+        assert_eq!(cipher.seal(&mut frame), Ok(()));
+        (bytes, size)
+    }
+
+    #[test]
+    fn seal_open() {
+        let mut tx = Cipher::new(KEY, 0x0102_0304);
+        let rx = Cipher::new(KEY, 0);
+        let (mut bytes, size) = sealed_request(&mut tx, GETSET_FLAG_ENCRYPTED | GETSET_FLAG_CRC32);
+        assert_eq!(tx.counter(), 1);
+        // Header, id list, cipher trailer and CRC32
+        assert_eq!(size, 17 + 5 + 28 + 4);
+
+        let mut frame = GetSetFrame::new_checked(&mut bytes[..size]).unwrap();
+        assert_eq!(
+            frame.cipher_nonce(),
+            Some(&[4, 3, 2, 1, 1, 0, 0, 0, 0, 0, 0, 0][..])
+        );
+        assert_ne!(frame.payload_mut()[1..5], [0x0B, 0x0A, 0x00, 0x00]);
+        assert_eq!(rx.open(&mut frame), Ok(()));
+
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        let req = RefRequest::parse(&frame).unwrap();
+        assert_eq!(req.ids().next(), Some(Ok(ParameterId::new(0x0A0B))));
+
+        // Nonces never repeat
+        let (bytes, size) = sealed_request(&mut tx, GETSET_FLAG_ENCRYPTED);
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(
+            frame.cipher_nonce(),
+            Some(&[4, 3, 2, 1, 2, 0, 0, 0, 0, 0, 0, 0][..])
+        );
+    }
+
+    #[test]
+    fn seal_then_sign() {
+        let mut cipher = Cipher::new(KEY, 1);
+        let mut tx = Authenticator::new([0x5A; 32]);
+        let mut rx = Authenticator::new([0x5A; 32]);
+        let flags = GETSET_FLAG_ENCRYPTED | GETSET_FLAG_AUTH | GETSET_FLAG_CRC32;
+        let (mut bytes, size) = sealed_request(&mut cipher, flags);
+        assert_eq!(size, 17 + 5 + 28 + 40 + 4);
+        let mut frame = GetSetFrame::new_checked(&mut bytes[..size]).unwrap();
+        assert_eq!(tx.sign(&mut frame), Ok(()));
+
+        let mut frame = GetSetFrame::new_checked(&mut bytes[..size]).unwrap();
+        assert_eq!(rx.verify(&frame), Ok(()));
+        assert_eq!(cipher.open(&mut frame), Ok(()));
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert!(RefRequest::parse(&frame).is_ok());
+    }
+
+    #[test]
+    fn open_invalid() {
+        let mut tx = Cipher::new(KEY, 1);
+        let (bytes, size) = sealed_request(&mut tx, GETSET_FLAG_ENCRYPTED);
+
+        let mut wrong_key = bytes;
+        let mut frame = GetSetFrame::new_checked(&mut wrong_key[..size]).unwrap();
+        let rx = Cipher::new([0xC3; KEY_SIZE], 0);
+        assert_eq!(rx.open(&mut frame), Err(Error::DecryptFailed));
+
+        // The header is authenticated too
+        let rx = Cipher::new(KEY, 0);
+        let mut tampered = bytes;
+        tampered[4] ^= 0x01;
+        let mut frame = GetSetFrame::new_checked(&mut tampered[..size]).unwrap();
+        assert_eq!(rx.open(&mut frame), Err(Error::DecryptFailed));
+
+        let mut tampered = bytes;
+        tampered[19] ^= 0x01;
+        let mut frame = GetSetFrame::new_checked(&mut tampered[..size]).unwrap();
+        assert_eq!(rx.open(&mut frame), Err(Error::DecryptFailed));
+
+        let req = Request::new(1, 0, GetSetOp::ListAll, GetSetPayloadType::None);
+        let mut bytes = [0xFF; 17];
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..]);
+        assert_eq!(req.emit(&mut frame), Ok(()));
+        assert_eq!(tx.seal(&mut frame), Err(Error::EncryptionRequired));
+        assert_eq!(rx.open(&mut frame), Err(Error::EncryptionRequired));
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{
+    Description, DescriptionListIter, DescriptionListPacket, Error, ErrorPacket, GetSetFlags,
+    GetSetFrame, GetSetNodeId, GetSetOp, GetSetPayloadType, GetSetSequence, GetSetVersion,
+    RemoteError, GETSET_FLAG_MORE, GETSET_VERSION_1, MAX_PARAMS_PER_OP, PREAMBLE_WORD,
+};
+use core::convert::TryFrom;
+use core::fmt;
+
+/// Response to a Describe request, emitted by nodes from their static
+/// description tables
+#[derive(Clone, PartialEq, Debug)]
+pub struct DescribeResponse<'a> {
+    node_id: GetSetNodeId,
+    flags: GetSetFlags,
+    version: GetSetVersion,
+    sequence: GetSetSequence,
+    descriptions: &'a [Description<'a>],
+}
+
+impl<'a> DescribeResponse<'a> {
+    pub fn new(
+        node_id: GetSetNodeId,
+        flags: GetSetFlags,
+        descriptions: &'a [Description<'a>],
+    ) -> Self {
+        DescribeResponse {
+            node_id,
+            flags,
+            version: GETSET_VERSION_1,
+            sequence: 0,
+            descriptions,
+        }
+    }
+
+    pub fn flags(&self) -> GetSetFlags {
+        self.flags
+    }
+
+    pub fn set_flags(&mut self, flags: GetSetFlags) {
+        self.flags = flags;
+    }
+
+    /// Responses should use the version of the request they answer
+    pub fn set_version(&mut self, version: GetSetVersion) {
+        self.version = version;
+    }
+
+    /// Responders echo the sequence number of the request
+    pub fn set_sequence(&mut self, sequence: GetSetSequence) {
+        self.sequence = sequence;
+    }
+
+    /// Number of leading descriptions that fit in a frame of at most
+    /// `max_len` bytes, the rest belong on the following pages
+    pub fn page_len(&self, max_len: usize) -> usize {
+        let mut size = GetSetFrame::<&[u8]>::frame_len(
+            self.version,
+            self.flags,
+            DescriptionListPacket::<&[u8]>::buffer_len(0),
+        );
+        self.descriptions
+            .iter()
+            .take(MAX_PARAMS_PER_OP)
+            .take_while(|d| {
+                size += d.wire_size();
+                size <= max_len
+            })
+            .count()
+    }
+
+    pub fn wire_size(&self) -> usize {
+        GetSetFrame::<&[u8]>::frame_len(self.version, self.flags, self.payload_wire_size())
+    }
+
+    fn payload_wire_size(&self) -> usize {
+        DescriptionListPacket::<&[u8]>::buffer_len(
+            self.descriptions.iter().map(|d| d.wire_size()).sum(),
+        )
+    }
+
+    pub fn emit<T: AsRef<[u8]> + AsMut<[u8]>>(
+        &self,
+        frame: &mut GetSetFrame<T>,
+    ) -> Result<(), Error> {
+        let count = u8::try_from(self.descriptions.len()).map_err(|_| Error::Capacity)?;
+        frame.set_preamble(PREAMBLE_WORD);
+        frame.set_node_id(self.node_id);
+        frame.set_flags(self.flags);
+        frame.set_version(self.version);
+        frame.set_sequence(self.sequence);
+        frame.set_op(GetSetOp::Describe);
+        frame.set_payload_type(GetSetPayloadType::DescriptionListPacket);
+        frame.set_payload_size(self.payload_wire_size() as u16);
+        let mut p = DescriptionListPacket::new_unchecked(frame.payload_mut());
+        p.set_count(count);
+        for (index, d) in self.descriptions.iter().enumerate() {
+            p.set_description_at(index, d)?;
+        }
+        frame.fill_checksum();
+        Ok(())
+    }
+}
+
+/// Borrowed counterpart to `DescribeResponse`, the descriptions are read
+/// from the frame's payload on demand
+#[derive(Clone, PartialEq, Debug)]
+pub struct RefDescribeResponse<'a> {
+    node_id: GetSetNodeId,
+    flags: GetSetFlags,
+    payload: &'a [u8],
+}
+
+impl<'a> RefDescribeResponse<'a> {
+    pub fn node_id(&self) -> GetSetNodeId {
+        self.node_id
+    }
+
+    pub fn flags(&self) -> GetSetFlags {
+        self.flags
+    }
+
+    /// The node has more descriptions than this page holds
+    pub fn more(&self) -> bool {
+        self.flags & GETSET_FLAG_MORE != 0
+    }
+
+    pub fn descriptions(&self) -> DescriptionListIter<'a> {
+        DescriptionListPacket::new_unchecked(self.payload).iter()
+    }
+
+    /// Parse and validate the frame, an Error response is returned as
+    /// `Error::Remote`
+    pub fn parse<T: AsRef<[u8]> + ?Sized>(frame: &GetSetFrame<&'a T>) -> Result<Self, Error> {
+        frame.check_len()?;
+        frame.check_preamble()?;
+        frame.check_version()?;
+        frame.check_payload_len()?;
+        frame.check_checksum()?;
+        let payload = frame.checked_payload()?;
+        match frame.payload_type()? {
+            GetSetPayloadType::DescriptionListPacket => {
+                let p = DescriptionListPacket::new_checked(payload)?;
+                for d in p.iter() {
+                    d?;
+                }
+            }
+            GetSetPayloadType::ErrorPacket => {
+                let p = ErrorPacket::new_checked(payload)?;
+                return Err(Error::Remote(RemoteError::parse(&p)?));
+            }
+            _ => return Err(Error::WireInvalidPayloadType),
+        }
+        Ok(RefDescribeResponse {
+            node_id: frame.node_id(),
+            flags: frame.flags(),
+            payload,
+        })
+    }
+}
+
+impl<'a> fmt::Display for RefDescribeResponse<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "RefDescribeResponse {{ nid: {} }}", self.node_id)?;
+        for d in self.descriptions().flatten() {
+            writeln!(f, "{}", d)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ErrorCode, ParameterFlags, ParameterId, ParameterValueTypeId, GETSET_VERSION_2};
+    use pretty_assertions::assert_eq;
+
+    static DESCRIPTIONS: [Description; 3] = [
+        Description::new(
+            ParameterId::new(0x0A),
+            ParameterFlags(0),
+            ParameterValueTypeId::U32,
+            "interval",
+            "Interval, seconds",
+        )
+        .with_units("s")
+        .with_range(1.0, 60.0),
+        Description::new(
+            ParameterId::new(0x0B),
+            ParameterFlags(1),
+            ParameterValueTypeId::Str,
+            "version",
+            "Version",
+        ),
+        Description::new(
+            ParameterId::new(0x0C),
+            ParameterFlags(0),
+            ParameterValueTypeId::Bool,
+            "enabled",
+            "Enabled",
+        ),
+    ];
+
+    #[test]
+    fn round_trip() {
+        let mut resp = DescribeResponse::new(1, GETSET_FLAG_MORE, &DESCRIPTIONS[..]);
+        resp.set_version(GETSET_VERSION_2);
+        resp.set_sequence(7);
+        let mut bytes = [0xFF; 256];
+        let size = resp.wire_size();
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..size]);
+        assert_eq!(resp.emit(&mut frame), Ok(()));
+
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(frame.op(), Ok(GetSetOp::Describe));
+        assert_eq!(frame.sequence(), 7);
+        let ref_resp = RefDescribeResponse::parse(&frame).unwrap();
+        assert_eq!(ref_resp.node_id(), 1);
+        assert!(ref_resp.more());
+        let mut iter = ref_resp.descriptions();
+        for d in DESCRIPTIONS.iter() {
+            assert_eq!(iter.next(), Some(Ok(*d)));
+        }
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn page_len() {
+        let resp = DescribeResponse::new(1, 0, &DESCRIPTIONS[..]);
+        let one = DescribeResponse::new(1, 0, &DESCRIPTIONS[..1]).wire_size();
+        let two = DescribeResponse::new(1, 0, &DESCRIPTIONS[..2]).wire_size();
+        assert_eq!(resp.page_len(one - 1), 0);
+        assert_eq!(resp.page_len(one), 1);
+        assert_eq!(resp.page_len(two), 2);
+        assert_eq!(resp.page_len(usize::MAX), 3);
+    }
+
+    #[test]
+    fn parse_error() {
+        let err = RemoteError::new(ErrorCode::NotFound, GetSetOp::Describe.as_u8());
+        let mut bytes = [0xFF; 64];
+        let size = err.wire_size(GETSET_VERSION_1, 0);
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..size]);
+        assert_eq!(err.emit(1, 0, GETSET_VERSION_1, 0, &mut frame), Ok(()));
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(RefDescribeResponse::parse(&frame), Err(Error::Remote(err)));
+    }
+}
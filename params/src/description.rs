@@ -0,0 +1,162 @@
+use crate::{DescriptionPacket, Error, ParameterFlags, ParameterId, ParameterValueTypeId};
+use core::fmt;
+
+/// Metadata of a parameter a node reports about itself, so hosts can work
+/// with parameters they have no description of
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Description<'a> {
+    id: ParameterId,
+    flags: ParameterFlags,
+    value_type: ParameterValueTypeId,
+    name: &'a str,
+    desc: &'a str,
+    units: Option<&'a str>,
+    range: Option<(f64, f64)>,
+}
+
+impl<'a> Description<'a> {
+    pub const fn new(
+        id: ParameterId,
+        flags: ParameterFlags,
+        value_type: ParameterValueTypeId,
+        name: &'a str,
+        desc: &'a str,
+    ) -> Self {
+        Description {
+            id,
+            flags,
+            value_type,
+            name,
+            desc,
+            units: None,
+            range: None,
+        }
+    }
+
+    pub const fn with_units(self, units: &'a str) -> Self {
+        Description {
+            units: Some(units),
+            ..self
+        }
+    }
+
+    /// Inclusive range of valid values
+    pub const fn with_range(self, min: f64, max: f64) -> Self {
+        Description {
+            range: Some((min, max)),
+            ..self
+        }
+    }
+
+    pub fn id(&self) -> ParameterId {
+        self.id
+    }
+
+    pub fn flags(&self) -> ParameterFlags {
+        self.flags
+    }
+
+    pub fn value_type(&self) -> ParameterValueTypeId {
+        self.value_type
+    }
+
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    pub fn desc(&self) -> &'a str {
+        self.desc
+    }
+
+    pub fn units(&self) -> Option<&'a str> {
+        self.units
+    }
+
+    pub fn range(&self) -> Option<(f64, f64)> {
+        self.range
+    }
+
+    pub fn wire_size(&self) -> usize {
+        DescriptionPacket::<&[u8]>::buffer_len(
+            self.name.len() + self.desc.len() + self.units.map_or(0, str::len),
+        )
+    }
+
+    pub fn parse<T: AsRef<[u8]> + ?Sized>(
+        packet: &DescriptionPacket<&'a T>,
+    ) -> Result<Self, Error> {
+        packet.check_len()?;
+        Ok(Description {
+            id: packet.id(),
+            flags: packet.flags(),
+            value_type: packet.value_type_id()?,
+            name: packet.name()?,
+            desc: packet.desc()?,
+            units: packet.units()?,
+            range: packet.range(),
+        })
+    }
+
+    pub fn emit<T: AsRef<[u8]> + AsMut<[u8]>>(
+        &self,
+        packet: &mut DescriptionPacket<T>,
+    ) -> Result<(), Error> {
+        packet.set_id(self.id);
+        packet.set_flags(self.flags);
+        packet.set_value_type_id(self.value_type);
+        packet.set_range(self.range);
+        packet.set_strings(self.name, self.desc, self.units.unwrap_or(""))
+    }
+}
+
+impl<'a> fmt::Display for Description<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Description {{ id: {} name: {} type: {:?} flags: {} }}",
+            self.id, self.name, self.value_type, self.flags,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn round_trip() {
+        let d = Description::new(
+            ParameterId::new(0x0A),
+            ParameterFlags(0),
+            ParameterValueTypeId::F32,
+            "temperature",
+            "Temperature",
+        )
+        .with_units("°C")
+        .with_range(-40.0, 85.0);
+        assert_eq!(d.units(), Some("°C"));
+        assert_eq!(d.range(), Some((-40.0, 85.0)));
+        assert_eq!(d.wire_size(), 29 + 11 + 11 + 3);
+
+        let mut bytes = [0xFF; 64];
+        let size = d.wire_size();
+        let mut p = DescriptionPacket::new_unchecked(&mut bytes[..size]);
+        assert_eq!(d.emit(&mut p), Ok(()));
+        let p = DescriptionPacket::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(Description::parse(&p), Ok(d));
+
+        let d = Description::new(
+            ParameterId::new(0x0B),
+            ParameterFlags(0),
+            ParameterValueTypeId::None,
+            "reset",
+            "",
+        );
+        let size = d.wire_size();
+        let mut p = DescriptionPacket::new_unchecked(&mut bytes[..size]);
+        assert_eq!(d.emit(&mut p), Ok(()));
+        let p = DescriptionPacket::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(Description::parse(&p), Ok(d));
+    }
+}
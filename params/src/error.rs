@@ -1,10 +1,33 @@
+use crate::RemoteError;
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub enum Error {
     WireTruncated,
     WireIndexOutOfBounds,
     WirePreamble,
+    WireUnsupportedVersion,
+    WireChecksum,
     WireInvalidPayloadType,
+    WireInvalidOp,
+    WireInvalidValueType,
+    WireInvalidSetStatus,
+    WireInvalidErrorCode,
+    /// A CompareAndSet parameter list isn't made of expected and new
+    /// value pairs of the same parameter
+    WireInvalidCompareAndSet,
+    /// The frame isn't signed but must be
+    AuthRequired,
+    /// The auth tag doesn't match the frame
+    AuthFailed,
+    /// The auth counter isn't newer than the last one accepted
+    AuthReplay,
+    /// The frame isn't encrypted but must be
+    EncryptionRequired,
+    /// The payload doesn't decrypt with the key or was tampered with
+    DecryptFailed,
     ParseValue,
     Capacity,
     ValueTypeMismatch,
+    /// The remote node replied with an error
+    Remote(RemoteError),
 }
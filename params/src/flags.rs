@@ -55,9 +55,9 @@ impl From<u32> for Flags {
     }
 }
 
-impl Into<u32> for Flags {
-    fn into(self) -> u32 {
-        self.0
+impl From<Flags> for u32 {
+    fn from(f: Flags) -> Self {
+        f.0
     }
 }
 
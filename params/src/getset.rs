@@ -1,3 +1,5 @@
+use crate::Error;
+use core::convert::TryFrom;
 use core::fmt;
 use heapless::consts::U64;
 use typenum::Unsigned;
@@ -12,20 +14,112 @@ pub const NODE_ID_ANONYMOUS: NodeId = 0;
 pub type NodeId = u32;
 pub type Flags = u32;
 pub type Version = u8;
+pub type Sequence = u32;
+
+/// The frame ends with a CRC32 trailer covering everything before it
+pub const FLAG_CRC32: Flags = 1 << 0;
+
+/// The frame carries an auth counter and HMAC-SHA256 tag after the payload
+pub const FLAG_AUTH: Flags = 1 << 1;
+
+/// The payload is encrypted, a nonce and Poly1305 tag follow it
+pub const FLAG_ENCRYPTED: Flags = 1 << 2;
+
+/// Set requests are applied all or nothing, the reply carries the flag too
+pub const FLAG_ATOMIC: Flags = 1 << 3;
+
+/// The response holds one page of the parameters, more follow it
+pub const FLAG_MORE: Flags = 1 << 4;
+
+/// Original frame layout
+pub const VERSION_1: Version = 1;
+
+/// Adds a sequence number to the frame header
+pub const VERSION_2: Version = 2;
+
+/// Range of versions this crate can encode and decode
+pub const MIN_VERSION: Version = VERSION_1;
+pub const MAX_VERSION: Version = VERSION_2;
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub enum Op {
-    /// Request payload: None
-    /// Response payload: ParameterListPacket
+    /// Request payload: None, or PagePacket for the pages after the first
+    /// Response payload: ParameterListPacket, the more flag is set when
+    /// the parameters don't fit in one frame
     ListAll = 0,
 
     /// Request payload: ParameterIdListPacket
-    /// Response payload: ParameterListPacket
+    /// Response payload: ParameterListPacket, the IDs are answered in order
+    /// and the more flag is set when they don't fit in one frame, the
+    /// rest follow the ID of the last parameter returned
     Get = 1,
 
     /// Request payload: ParameterListPacket
-    /// Response payload: ParameterListPacket
+    /// Response payload: SetResultListPacket
     Set = 2,
+
+    /// Response only, sent instead of the usual response when
+    /// the request failed
+    /// Response payload: ErrorPacket
+    Error = 3,
+
+    /// Always exchanged using the v1 layout so any node can answer it
+    /// Request payload: None
+    /// Response payload: CapabilitiesPacket
+    Capabilities = 4,
+
+    /// Request payload: ParameterListPacket, the expected then the new
+    /// value of each parameter
+    /// Response payload: CompareAndSetResultPacket
+    CompareAndSet = 5,
+
+    /// Request payload: None, or PagePacket for the pages after the first
+    /// Response payload: DescriptionListPacket, paged like ListAll
+    Describe = 6,
+
+    /// Request payload: ParameterIdListPacket, unknown IDs are ignored
+    /// Response payload: ParameterListPacket, the current values of the
+    /// subscribed parameters. Afterwards the node pushes a response
+    /// with sequence 0 on the same connection whenever they change,
+    /// until unsubscribed or disconnected
+    Subscribe = 7,
+
+    /// Request payload: ParameterIdListPacket, or None for all of them
+    /// Response payload: ParameterListPacket, empty
+    Unsubscribe = 8,
+
+    /// Request payload: SincePacket
+    /// Response payload: ChangedListPacket, the node's current time and
+    /// the parameters whose local time is at or after the requested one, the
+    /// more flag is set when they don't fit in one frame and the rest are
+    /// paged by offset like ListAll. The time is sampled before the
+    /// parameters, requesting changes since it misses none but may repeat
+    /// those made in that millisecond.
+    GetChanged = 9,
+
+    /// Request payload: ParameterListPacket, a single command parameter
+    /// carrying its argument, Notification when it takes none
+    /// Response payload: ParameterListPacket, the command parameter
+    /// carrying its result, Notification when it returns none
+    Invoke = 10,
+
+    /// Sent by nodes only, broadcast at boot and in response to a Discover,
+    /// always in the v1 layout and broadcasts are never encrypted so any
+    /// client can read them
+    /// Response payload: AnnouncePacket
+    Announce = 11,
+
+    /// Broadcast to have the nodes announce themselves, over TCP the node
+    /// replies with its Announce directly
+    /// Request payload: None
+    Discover = 12,
+
+    /// Also answered over UDP on the broadcast port, the reply is sent
+    /// back to the requester
+    /// Request payload: PingPacket, opaque data
+    /// Response payload: PingPacket, the same data with the node's uptime
+    /// and local time
+    Ping = 13,
 }
 
 impl Default for Op {
@@ -35,18 +129,31 @@ impl Default for Op {
 }
 
 impl Op {
-    pub(crate) fn as_u8(&self) -> u8 {
+    pub fn as_u8(&self) -> u8 {
         *self as u8
     }
 }
 
-impl From<u8> for Op {
-    fn from(v: u8) -> Self {
+impl TryFrom<u8> for Op {
+    type Error = Error;
+
+    fn try_from(v: u8) -> Result<Self, Error> {
         match v {
-            0 => Op::ListAll,
-            1 => Op::Get,
-            2 => Op::Set,
-            _ => Op::ListAll,
+            0 => Ok(Op::ListAll),
+            1 => Ok(Op::Get),
+            2 => Ok(Op::Set),
+            3 => Ok(Op::Error),
+            4 => Ok(Op::Capabilities),
+            5 => Ok(Op::CompareAndSet),
+            6 => Ok(Op::Describe),
+            7 => Ok(Op::Subscribe),
+            8 => Ok(Op::Unsubscribe),
+            9 => Ok(Op::GetChanged),
+            10 => Ok(Op::Invoke),
+            11 => Ok(Op::Announce),
+            12 => Ok(Op::Discover),
+            13 => Ok(Op::Ping),
+            _ => Err(Error::WireInvalidOp),
         }
     }
 }
@@ -62,6 +169,18 @@ pub enum PayloadType {
     None = 0,
     ParameterIdListPacket = 1,
     ParameterListPacket = 2,
+    SetResultListPacket = 3,
+    ErrorPacket = 4,
+    CapabilitiesPacket = 5,
+    /// A SetResultListPacket followed by a ParameterListPacket
+    CompareAndSetResultPacket = 6,
+    PagePacket = 7,
+    DescriptionListPacket = 8,
+    SincePacket = 9,
+    /// A TimePacket followed by a ParameterListPacket
+    ChangedListPacket = 10,
+    AnnouncePacket = 11,
+    PingPacket = 12,
 }
 
 impl Default for PayloadType {
@@ -76,13 +195,25 @@ impl PayloadType {
     }
 }
 
-impl From<u8> for PayloadType {
-    fn from(v: u8) -> Self {
+impl TryFrom<u8> for PayloadType {
+    type Error = Error;
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
         match v {
-            0 => PayloadType::None,
-            1 => PayloadType::ParameterIdListPacket,
-            2 => PayloadType::ParameterListPacket,
-            _ => PayloadType::None,
+            0 => Ok(PayloadType::None),
+            1 => Ok(PayloadType::ParameterIdListPacket),
+            2 => Ok(PayloadType::ParameterListPacket),
+            3 => Ok(PayloadType::SetResultListPacket),
+            4 => Ok(PayloadType::ErrorPacket),
+            5 => Ok(PayloadType::CapabilitiesPacket),
+            6 => Ok(PayloadType::CompareAndSetResultPacket),
+            7 => Ok(PayloadType::PagePacket),
+            8 => Ok(PayloadType::DescriptionListPacket),
+            9 => Ok(PayloadType::SincePacket),
+            10 => Ok(PayloadType::ChangedListPacket),
+            11 => Ok(PayloadType::AnnouncePacket),
+            12 => Ok(PayloadType::PingPacket),
+            _ => Err(Error::WireInvalidPayloadType),
         }
     }
 }
@@ -23,9 +23,9 @@ impl From<u32> for Id {
     }
 }
 
-impl Into<u32> for Id {
-    fn into(self) -> u32 {
-        self.0
+impl From<Id> for u32 {
+    fn from(id: Id) -> Self {
+        id.0
     }
 }
 
@@ -0,0 +1,203 @@
+use crate::{
+    Error, GetSetFlags, GetSetFrame, GetSetNodeId, GetSetOp, GetSetPayloadType, GetSetSequence,
+    GetSetVersion, MaxParamsPerOp, Parameter, ParameterId, ParameterListPacket, GETSET_VERSION_1,
+    PREAMBLE_WORD,
+};
+use core::fmt;
+use heapless::Vec;
+
+/// Response holding the IDs of the parameters to send, in order, they are
+/// looked up in a borrowed parameter table when emitted.
+///
+/// Unlike RefResponse the parameters don't have to be adjacent, and unlike
+/// Response they aren't copied, so the request's IDs can be collected before
+/// the reply is emitted over it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct IdListResponse<'a> {
+    node_id: GetSetNodeId,
+    flags: GetSetFlags,
+    version: GetSetVersion,
+    sequence: GetSetSequence,
+    op: GetSetOp,
+    params: &'a [Parameter],
+    ids: Vec<ParameterId, MaxParamsPerOp>,
+    params_size: usize,
+}
+
+impl<'a> IdListResponse<'a> {
+    pub fn new(
+        node_id: GetSetNodeId,
+        flags: GetSetFlags,
+        op: GetSetOp,
+        params: &'a [Parameter],
+    ) -> Self {
+        IdListResponse {
+            node_id,
+            flags,
+            version: GETSET_VERSION_1,
+            sequence: 0,
+            op,
+            params,
+            ids: Vec::new(),
+            params_size: 0,
+        }
+    }
+
+    pub fn flags(&self) -> GetSetFlags {
+        self.flags
+    }
+
+    pub fn set_flags(&mut self, flags: GetSetFlags) {
+        self.flags = flags;
+    }
+
+    pub fn op(&self) -> GetSetOp {
+        self.op
+    }
+
+    /// Responses should use the version of the request they answer
+    pub fn set_version(&mut self, version: GetSetVersion) {
+        self.version = version;
+    }
+
+    /// Responders echo the sequence number of the request
+    pub fn set_sequence(&mut self, sequence: GetSetSequence) {
+        self.sequence = sequence;
+    }
+
+    /// Append the parameter with the ID, IDs missing from the table are
+    /// ignored and true is returned when it was found
+    pub fn push(&mut self, id: ParameterId) -> Result<bool, Error> {
+        match self.params.iter().find(|p| p.id() == id) {
+            Some(p) => {
+                self.ids.push(id).map_err(|_| Error::Capacity)?;
+                self.params_size += p.wire_size();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// The parameters of the pushed IDs, in order
+    pub fn parameters(&self) -> impl Iterator<Item = &Parameter> + '_ {
+        let params = self.params;
+        self.ids
+            .iter()
+            .filter_map(move |id| params.iter().find(|p| p.id() == *id))
+    }
+
+    pub fn wire_size(&self) -> usize {
+        GetSetFrame::<&[u8]>::frame_len(self.version, self.flags, self.payload_wire_size())
+    }
+
+    fn payload_wire_size(&self) -> usize {
+        ParameterListPacket::<&[u8]>::buffer_len(self.params_size)
+    }
+
+    pub fn emit<T: AsRef<[u8]> + AsMut<[u8]>>(
+        &self,
+        frame: &mut GetSetFrame<T>,
+    ) -> Result<(), Error> {
+        frame.set_preamble(PREAMBLE_WORD);
+        frame.set_node_id(self.node_id);
+        frame.set_flags(self.flags);
+        frame.set_version(self.version);
+        frame.set_sequence(self.sequence);
+        frame.set_op(self.op);
+        frame.set_payload_type(GetSetPayloadType::ParameterListPacket);
+        frame.set_payload_size(self.payload_wire_size() as u16);
+        let mut p = ParameterListPacket::new_unchecked(frame.payload_mut());
+        p.set_count(self.ids.len() as _);
+        for (index, param) in self.parameters().enumerate() {
+            p.set_parameter_at(index, *param)?;
+        }
+        frame.fill_checksum();
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Display for IdListResponse<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "IdListResponse {{ nid: {} op: {} }}",
+            self.node_id,
+            self.op()
+        )?;
+        for p in self.parameters() {
+            writeln!(f, "{}", p)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParameterFlags, ParameterValue, RefResponse, Response, MAX_PARAMS_PER_OP};
+    use pretty_assertions::assert_eq;
+
+    const PARAMS: [Parameter; 3] = [
+        Parameter::new_with_value(
+            ParameterId::new(0x0A),
+            ParameterFlags(0),
+            ParameterValue::I32(-1234),
+        ),
+        Parameter::new_with_value(
+            ParameterId::new(0x0B),
+            ParameterFlags(0),
+            ParameterValue::Bool(true),
+        ),
+        Parameter::new_with_value(
+            ParameterId::new(0x0C),
+            ParameterFlags(0),
+            ParameterValue::U8(12),
+        ),
+    ];
+
+    #[test]
+    fn push() {
+        let mut resp = IdListResponse::new(0, 0, GetSetOp::Get, &PARAMS[..]);
+        assert_eq!(resp.push(ParameterId::new(0x0C)), Ok(true));
+        assert_eq!(resp.push(ParameterId::new(0x0F)), Ok(false));
+        assert_eq!(resp.push(ParameterId::new(0x0A)), Ok(true));
+        let ids: [ParameterId; 2] = [ParameterId::new(0x0C), ParameterId::new(0x0A)];
+        assert!(resp.parameters().map(|p| p.id()).eq(ids.iter().cloned()));
+        assert_eq!(
+            resp.wire_size(),
+            RefResponse::new(0, 0, GetSetOp::Get, &PARAMS[..1]).wire_size() + PARAMS[2].wire_size()
+        );
+
+        for _ in 2..MAX_PARAMS_PER_OP {
+            assert_eq!(resp.push(ParameterId::new(0x0B)), Ok(true));
+        }
+        assert_eq!(resp.push(ParameterId::new(0x0B)), Err(Error::Capacity));
+    }
+
+    #[test]
+    fn emit() {
+        let mut resp = IdListResponse::new(1, 0, GetSetOp::Get, &PARAMS[..]);
+        resp.set_version(crate::GETSET_VERSION_2);
+        resp.set_sequence(7);
+        assert_eq!(resp.push(ParameterId::new(0x0C)), Ok(true));
+        assert_eq!(resp.push(ParameterId::new(0x0A)), Ok(true));
+
+        let mut bytes = [0xFF; 128];
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..]);
+        assert_eq!(resp.emit(&mut frame), Ok(()));
+        let frame = GetSetFrame::new_checked(&bytes[..resp.wire_size()]).unwrap();
+        assert_eq!(frame.sequence(), 7);
+
+        // Same frame as the copying response
+        let mut copied = Response::new(1, 0, GetSetOp::Get);
+        copied.set_version(crate::GETSET_VERSION_2);
+        copied.set_sequence(7);
+        copied.push(PARAMS[2]).unwrap();
+        copied.push(PARAMS[0]).unwrap();
+        assert_eq!(copied.wire_size(), resp.wire_size());
+        let mut expected = [0xFF; 128];
+        let mut frame = GetSetFrame::new_unchecked(&mut expected[..]);
+        assert_eq!(copied.emit(&mut frame), Ok(()));
+        assert_eq!(&bytes[..], &expected[..]);
+    }
+}
@@ -1,30 +1,75 @@
 #![no_std]
 
+pub use crate::announce::Announce;
+pub use crate::auth::{Authenticator, Key as AuthKey, KEY_SIZE as AUTH_KEY_SIZE};
+pub use crate::capabilities::Capabilities;
+pub use crate::cipher::{Cipher, Key as CipherKey, KEY_SIZE as CIPHER_KEY_SIZE};
+pub use crate::describe_response::{DescribeResponse, RefDescribeResponse};
+pub use crate::description::Description;
 pub use crate::error::Error;
 pub use crate::flags::Flags as ParameterFlags;
 pub use crate::getset::{
     Flags as GetSetFlags, MaxParamsPerOp, NodeId as GetSetNodeId, Op as GetSetOp,
-    PayloadType as GetSetPayloadType, Version as GetSetVersion, MAX_PARAMS_PER_OP,
-    NODE_ID_ANONYMOUS, PREAMBLE_WORD,
+    PayloadType as GetSetPayloadType, Sequence as GetSetSequence, Version as GetSetVersion,
+    FLAG_ATOMIC as GETSET_FLAG_ATOMIC, FLAG_AUTH as GETSET_FLAG_AUTH,
+    FLAG_CRC32 as GETSET_FLAG_CRC32, FLAG_ENCRYPTED as GETSET_FLAG_ENCRYPTED,
+    FLAG_MORE as GETSET_FLAG_MORE, MAX_PARAMS_PER_OP, MAX_VERSION as GETSET_MAX_VERSION,
+    MIN_VERSION as GETSET_MIN_VERSION, NODE_ID_ANONYMOUS, PREAMBLE_WORD,
+    VERSION_1 as GETSET_VERSION_1, VERSION_2 as GETSET_VERSION_2,
 };
 pub use crate::id::Id as ParameterId;
+pub use crate::id_list_response::IdListResponse;
 pub use crate::parameter::Parameter;
+pub use crate::ref_request::{CompareAndSetIter, RefRequest};
 pub use crate::ref_response::RefResponse;
+pub use crate::remote_error::{ErrorCode, RemoteError};
 pub use crate::request::Request;
 pub use crate::response::Response;
+pub use crate::set_result::{SetResult, Status as SetStatus};
 pub use crate::value::{TypeId as ParameterValueTypeId, Value as ParameterValue};
+pub use crate::value_array::{Array as ParameterValueArray, MAX_ARRAY_WIRE_SIZE};
+pub use crate::value_bytes::{Bytes as ParameterValueBytes, MAX_BYTES_LEN};
+pub use crate::value_str::{Str as ParameterValueStr, MAX_STR_LEN};
+pub use crate::wire::announce::Packet as AnnouncePacket;
+pub use crate::wire::capabilities::Packet as CapabilitiesPacket;
+pub use crate::wire::crc32::crc32;
+pub use crate::wire::description::Packet as DescriptionPacket;
+pub use crate::wire::description_list::{
+    Iter as DescriptionListIter, Packet as DescriptionListPacket,
+};
+pub use crate::wire::error::Packet as ErrorPacket;
 pub use crate::wire::getset::Frame as GetSetFrame;
+pub use crate::wire::page::Packet as PagePacket;
 pub use crate::wire::parameter::Packet as ParameterPacket;
-pub use crate::wire::parameter_id_list::Packet as ParameterIdListPacket;
-pub use crate::wire::parameter_list::Packet as ParameterListPacket;
+pub use crate::wire::parameter_id_list::{
+    Iter as ParameterIdListIter, Packet as ParameterIdListPacket,
+};
+pub use crate::wire::parameter_list::{Iter as ParameterListIter, Packet as ParameterListPacket};
+pub use crate::wire::ping::Packet as PingPacket;
+pub use crate::wire::set_result_list::{Iter as SetResultListIter, Packet as SetResultListPacket};
+pub use crate::wire::since::Packet as SincePacket;
+pub use crate::wire::time::Packet as TimePacket;
 
+mod announce;
+mod auth;
+mod capabilities;
+mod cipher;
+mod describe_response;
+mod description;
 mod error;
 pub mod flags;
 mod getset;
 mod id;
+mod id_list_response;
 mod parameter;
+mod ref_request;
 mod ref_response;
+mod remote_error;
 mod request;
 mod response;
+mod set_result;
 mod value;
+mod value_array;
+mod value_bytes;
+mod value_str;
 mod wire;
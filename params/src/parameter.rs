@@ -1,4 +1,3 @@
-use crate::value::TypeId;
 use crate::{Error, ParameterFlags, ParameterId, ParameterPacket, ParameterValue};
 use core::fmt;
 
@@ -54,7 +53,14 @@ impl Parameter {
     }
 
     pub fn set_value(&mut self, value: ParameterValue) -> Result<(), Error> {
-        if self.value.type_id() != value.type_id() {
+        let shape_mismatch = match (self.value, value) {
+            // Arrays are fixed-length, the element type and count must match
+            (ParameterValue::Array(a), ParameterValue::Array(b)) => {
+                a.element_type_id() != b.element_type_id() || a.len() != b.len()
+            }
+            _ => false,
+        };
+        if self.value.type_id() != value.type_id() || shape_mismatch {
             Err(Error::ValueTypeMismatch)
         } else {
             self.value = value;
@@ -63,8 +69,7 @@ impl Parameter {
     }
 
     pub fn wire_size(&self) -> usize {
-        let value_size = TypeId::from(self.value()).wire_size();
-        ParameterPacket::<&[u8]>::buffer_len(value_size)
+        ParameterPacket::<&[u8]>::buffer_len(self.value.wire_size())
     }
 
     pub fn parse<T: AsRef<[u8]> + ?Sized>(frame: &ParameterPacket<&T>) -> Result<Self, Error> {
@@ -73,7 +78,7 @@ impl Parameter {
             local_time_ms: frame.local_time_ms(),
             id: frame.id(),
             flags: frame.flags(),
-            value: frame.value(),
+            value: frame.value()?,
         })
     }
 
@@ -101,6 +106,7 @@ impl fmt::Display for Parameter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{ParameterValueArray, ParameterValueStr, ParameterValueTypeId};
     use core::mem;
 
     #[test]
@@ -116,6 +122,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn wire_size_str() {
+        let p = Parameter::new_with_value(
+            ParameterId::new(0x0F),
+            ParameterFlags(0),
+            ParameterValue::Str(ParameterValueStr::from_utf8(b"abc").unwrap()),
+        );
+        assert_eq!(
+            p.wire_size(),
+            ParameterPacket::<&[u8]>::header_len() + mem::size_of::<u8>() + 3
+        );
+    }
+
     #[test]
     fn getter_methods() {
         let p = Parameter::new_with_value(
@@ -142,4 +161,27 @@ mod tests {
             Err(Error::ValueTypeMismatch)
         );
     }
+
+    #[test]
+    fn set_array_shape() {
+        let array = |elem, s| ParameterValue::Array(ParameterValueArray::parse(elem, s).unwrap());
+        let mut p = Parameter::new_with_value(
+            ParameterId::new(0x0A),
+            ParameterFlags::default(),
+            array(ParameterValueTypeId::U8, "1, 2, 3"),
+        );
+        assert_eq!(
+            p.set_value(array(ParameterValueTypeId::U8, "4, 5, 6")),
+            Ok(())
+        );
+        assert_eq!(p.value(), array(ParameterValueTypeId::U8, "4, 5, 6"));
+        assert_eq!(
+            p.set_value(array(ParameterValueTypeId::U8, "1, 2")),
+            Err(Error::ValueTypeMismatch)
+        );
+        assert_eq!(
+            p.set_value(array(ParameterValueTypeId::I8, "1, 2, 3")),
+            Err(Error::ValueTypeMismatch)
+        );
+    }
 }
@@ -0,0 +1,359 @@
+use crate::{
+    Error, GetSetFlags, GetSetFrame, GetSetNodeId, GetSetOp, GetSetPayloadType, GetSetSequence,
+    GetSetVersion, PagePacket, Parameter, ParameterId, ParameterIdListIter, ParameterIdListPacket,
+    ParameterListIter, ParameterListPacket, PingPacket, SincePacket, MAX_PARAMS_PER_OP,
+};
+use core::fmt;
+
+/// Borrowed counterpart to `Request`, the IDs and parameters are
+/// read from the frame's payload on demand instead of being copied
+#[derive(Clone, PartialEq, Debug)]
+pub struct RefRequest<'a> {
+    node_id: GetSetNodeId,
+    flags: GetSetFlags,
+    version: GetSetVersion,
+    sequence: GetSetSequence,
+    op: GetSetOp,
+    payload_type: GetSetPayloadType,
+    payload: &'a [u8],
+}
+
+impl<'a> RefRequest<'a> {
+    pub fn node_id(&self) -> GetSetNodeId {
+        self.node_id
+    }
+
+    pub fn flags(&self) -> GetSetFlags {
+        self.flags
+    }
+
+    pub fn version(&self) -> GetSetVersion {
+        self.version
+    }
+
+    /// Sequence number to echo in the response, zero for v1 frames
+    pub fn sequence(&self) -> GetSetSequence {
+        self.sequence
+    }
+
+    pub fn op(&self) -> GetSetOp {
+        self.op
+    }
+
+    pub fn payload_type(&self) -> GetSetPayloadType {
+        self.payload_type
+    }
+
+    /// The requested IDs, empty unless the payload is a `ParameterIdListPacket`
+    pub fn ids(&self) -> ParameterIdListIter<'a> {
+        match self.payload_type {
+            GetSetPayloadType::ParameterIdListPacket => {
+                ParameterIdListPacket::new_unchecked(self.payload).iter()
+            }
+            _ => ParameterIdListIter::default(),
+        }
+    }
+
+    /// The parameters, empty unless the payload is a `ParameterListPacket`
+    pub fn parameters(&self) -> ParameterListIter<'a> {
+        match self.payload_type {
+            GetSetPayloadType::ParameterListPacket => {
+                ParameterListPacket::new_unchecked(self.payload).iter()
+            }
+            _ => ParameterListIter::default(),
+        }
+    }
+
+    /// Index of the first parameter of the requested page, zero unless
+    /// the payload is a `PagePacket`
+    pub fn offset(&self) -> u16 {
+        match self.payload_type {
+            GetSetPayloadType::PagePacket => PagePacket::new_unchecked(self.payload).offset(),
+            _ => 0,
+        }
+    }
+
+    /// Lowest ID of the requested page of changed parameters, zero unless
+    /// the payload is a `SincePacket`
+    pub fn start_id(&self) -> ParameterId {
+        match self.payload_type {
+            GetSetPayloadType::SincePacket => SincePacket::new_unchecked(self.payload).start_id(),
+            _ => ParameterId::default(),
+        }
+    }
+
+    /// Node time after which changed parameters are wanted, zero unless
+    /// the payload is a `SincePacket`
+    pub fn since_ms(&self) -> u64 {
+        match self.payload_type {
+            GetSetPayloadType::SincePacket => SincePacket::new_unchecked(self.payload).since_ms(),
+            _ => 0,
+        }
+    }
+
+    /// Opaque data to echo, empty unless the payload is a `PingPacket`
+    pub fn ping_data(&self) -> &'a [u8] {
+        match self.payload_type {
+            GetSetPayloadType::PingPacket => PingPacket::new_unchecked(self.payload).data(),
+            _ => &[],
+        }
+    }
+
+    /// The expected and new value pairs of a CompareAndSet, empty unless
+    /// the payload is a `ParameterListPacket`
+    pub fn compare_and_set_pairs(&self) -> CompareAndSetIter<'a> {
+        CompareAndSetIter {
+            params: self.parameters(),
+        }
+    }
+
+    /// Parse and validate the frame, the whole payload is walked once so
+    /// the iterators will not yield errors afterwards
+    pub fn parse<T: AsRef<[u8]> + ?Sized>(frame: &GetSetFrame<&'a T>) -> Result<Self, Error> {
+        frame.check_len()?;
+        frame.check_preamble()?;
+        frame.check_version()?;
+        frame.check_payload_len()?;
+        frame.check_checksum()?;
+        let payload_type = frame.payload_type()?;
+        let payload = frame.checked_payload()?;
+        match payload_type {
+            GetSetPayloadType::None => (),
+            GetSetPayloadType::ParameterIdListPacket => {
+                let p = ParameterIdListPacket::new_checked(payload)?;
+                check_count(p.count())?;
+                for id in p.iter() {
+                    id?;
+                }
+            }
+            GetSetPayloadType::ParameterListPacket => {
+                let p = ParameterListPacket::new_checked(payload)?;
+                check_count(p.count())?;
+                for param in p.iter() {
+                    param?;
+                }
+                if frame.op()? == GetSetOp::CompareAndSet {
+                    let params = p.iter();
+                    for pair in (CompareAndSetIter { params }) {
+                        pair?;
+                    }
+                }
+            }
+            GetSetPayloadType::PagePacket => {
+                PagePacket::new_checked(payload)?;
+            }
+            GetSetPayloadType::SincePacket => {
+                SincePacket::new_checked(payload)?;
+            }
+            GetSetPayloadType::PingPacket => {
+                PingPacket::new_checked(payload)?;
+            }
+            GetSetPayloadType::SetResultListPacket
+            | GetSetPayloadType::ErrorPacket
+            | GetSetPayloadType::CapabilitiesPacket
+            | GetSetPayloadType::CompareAndSetResultPacket
+            | GetSetPayloadType::DescriptionListPacket
+            | GetSetPayloadType::ChangedListPacket
+            | GetSetPayloadType::AnnouncePacket => return Err(Error::WireInvalidPayloadType),
+        }
+        Ok(RefRequest {
+            node_id: frame.node_id(),
+            flags: frame.flags(),
+            version: frame.version(),
+            sequence: frame.sequence(),
+            op: frame.op()?,
+            payload_type,
+            payload,
+        })
+    }
+}
+
+/// Iterator over the expected and new value pairs of a CompareAndSet,
+/// stops after the first error
+#[derive(Debug, Clone, Default)]
+pub struct CompareAndSetIter<'a> {
+    params: ParameterListIter<'a>,
+}
+
+impl<'a> Iterator for CompareAndSetIter<'a> {
+    type Item = Result<(Parameter, Parameter), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let expected = match self.params.next()? {
+            Ok(p) => p,
+            Err(e) => return Some(Err(e)),
+        };
+        let pair = match self.params.next() {
+            Some(Ok(value)) if value.id() == expected.id() => Ok((expected, value)),
+            Some(Err(e)) => Err(e),
+            _ => Err(Error::WireInvalidCompareAndSet),
+        };
+        if pair.is_err() {
+            self.params = ParameterListIter::default();
+        }
+        Some(pair)
+    }
+}
+
+/// Same limit `Request` enforces through its capacity
+fn check_count(count: u8) -> Result<(), Error> {
+    if usize::from(count) > MAX_PARAMS_PER_OP {
+        Err(Error::Capacity)
+    } else {
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Display for RefRequest<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "RefRequest {{ nid: {} op: {} }}",
+            self.node_id,
+            self.op()
+        )?;
+        for p in self.parameters().flatten() {
+            writeln!(f, "{}", p)?;
+        }
+        for id in self.ids().flatten() {
+            writeln!(f, "{}", id)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parameter, ParameterFlags, ParameterId, ParameterValue, Request};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_ids() {
+        let mut req = Request::new(
+            1,
+            0,
+            GetSetOp::Get,
+            GetSetPayloadType::ParameterIdListPacket,
+        );
+        assert_eq!(req.push_id(ParameterId::new(0x0A)), Ok(()));
+        assert_eq!(req.push_id(ParameterId::new(0x0B)), Ok(()));
+        let mut bytes = [0xFF; 64];
+        let size = req.wire_size();
+        assert_eq!(
+            req.emit(&mut GetSetFrame::new_unchecked(&mut bytes[..size])),
+            Ok(())
+        );
+
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        let ref_req = RefRequest::parse(&frame).unwrap();
+        assert_eq!(ref_req.node_id(), 1);
+        assert_eq!(ref_req.op(), GetSetOp::Get);
+        let mut ids = ref_req.ids();
+        assert_eq!(ids.next(), Some(Ok(ParameterId::new(0x0A))));
+        assert_eq!(ids.next(), Some(Ok(ParameterId::new(0x0B))));
+        assert_eq!(ids.next(), None);
+        assert_eq!(ref_req.parameters().next(), None);
+    }
+
+    #[test]
+    fn parse_parameters() {
+        let params = [
+            Parameter::new_with_value(
+                ParameterId::new(0x0A),
+                ParameterFlags(0),
+                ParameterValue::I32(-1234),
+            ),
+            Parameter::new_with_value(
+                ParameterId::new(0x0B),
+                ParameterFlags(0),
+                ParameterValue::Bool(true),
+            ),
+        ];
+        let mut req = Request::new(1, 0, GetSetOp::Set, GetSetPayloadType::ParameterListPacket);
+        for p in params.iter() {
+            assert_eq!(req.push_parameter(*p), Ok(()));
+        }
+        let mut bytes = [0xFF; 128];
+        let size = req.wire_size();
+        assert_eq!(
+            req.emit(&mut GetSetFrame::new_unchecked(&mut bytes[..size])),
+            Ok(())
+        );
+
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        let ref_req = RefRequest::parse(&frame).unwrap();
+        assert_eq!(ref_req.op(), GetSetOp::Set);
+        assert_eq!(ref_req.ids().next(), None);
+        let mut iter = ref_req.parameters();
+        assert_eq!(iter.next(), Some(Ok(params[0])));
+        assert_eq!(iter.next(), Some(Ok(params[1])));
+        assert_eq!(iter.next(), None);
+
+        // Corrupt the second value's type
+        bytes[size - 2] = 0xEE;
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(RefRequest::parse(&frame), Err(Error::WireInvalidValueType));
+    }
+
+    #[test]
+    fn parse_compare_and_set() {
+        let id = ParameterId::new(0x0A);
+        let mut req = Request::new(
+            1,
+            0,
+            GetSetOp::CompareAndSet,
+            GetSetPayloadType::ParameterListPacket,
+        );
+        let (expected, value) = (ParameterValue::U8(1), ParameterValue::U8(2));
+        assert_eq!(req.push_compare_and_set(id, expected, value), Ok(()));
+        let mut bytes = [0xFF; 64];
+        let size = req.wire_size();
+        assert_eq!(
+            req.emit(&mut GetSetFrame::new_unchecked(&mut bytes[..size])),
+            Ok(())
+        );
+
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        let ref_req = RefRequest::parse(&frame).unwrap();
+        let mut pairs = ref_req.compare_and_set_pairs();
+        let (e, v) = pairs.next().unwrap().unwrap();
+        assert_eq!((e.id(), e.value()), (id, expected));
+        assert_eq!((v.id(), v.value()), (id, value));
+        assert_eq!(pairs.next(), None);
+
+        // The second value of a pair must be for the same parameter
+        let mut req = Request::new(
+            1,
+            0,
+            GetSetOp::CompareAndSet,
+            GetSetPayloadType::ParameterListPacket,
+        );
+        for id in 0..2 {
+            let p = Parameter::new_with_value(ParameterId::new(id), ParameterFlags(0), value);
+            assert_eq!(req.push_parameter(p), Ok(()));
+        }
+        let size = req.wire_size();
+        assert_eq!(
+            req.emit(&mut GetSetFrame::new_unchecked(&mut bytes[..size])),
+            Ok(())
+        );
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(
+            RefRequest::parse(&frame),
+            Err(Error::WireInvalidCompareAndSet)
+        );
+
+        req.pop_parameter();
+        let size = req.wire_size();
+        assert_eq!(
+            req.emit(&mut GetSetFrame::new_unchecked(&mut bytes[..size])),
+            Ok(())
+        );
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(
+            RefRequest::parse(&frame),
+            Err(Error::WireInvalidCompareAndSet)
+        );
+    }
+}
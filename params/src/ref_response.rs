@@ -1,13 +1,16 @@
 use crate::{
-    Error, GetSetFlags, GetSetFrame, GetSetNodeId, GetSetOp, GetSetPayloadType, Parameter,
-    ParameterListPacket, PREAMBLE_WORD,
+    Error, GetSetFlags, GetSetFrame, GetSetNodeId, GetSetOp, GetSetPayloadType, GetSetSequence,
+    GetSetVersion, Parameter, ParameterListPacket, GETSET_VERSION_1, MAX_PARAMS_PER_OP,
+    NODE_ID_ANONYMOUS, PREAMBLE_WORD,
 };
 use core::fmt;
 
-#[derive(Clone, PartialEq, Debug, Default)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct RefResponse<P: AsRef<[Parameter]>> {
     node_id: GetSetNodeId,
     flags: GetSetFlags,
+    version: GetSetVersion,
+    sequence: GetSetSequence,
     op: GetSetOp,
     params: P,
 }
@@ -17,17 +20,66 @@ impl<P: AsRef<[Parameter]>> RefResponse<P> {
         RefResponse {
             node_id,
             flags,
+            version: GETSET_VERSION_1,
+            sequence: 0,
             op,
             params,
         }
     }
 
+    pub fn flags(&self) -> GetSetFlags {
+        self.flags
+    }
+
+    pub fn set_flags(&mut self, flags: GetSetFlags) {
+        self.flags = flags;
+    }
+
     pub fn op(&self) -> GetSetOp {
         self.op
     }
 
+    /// Frame layout version, v1 unless set otherwise
+    pub fn version(&self) -> GetSetVersion {
+        self.version
+    }
+
+    /// Responses should use the version of the request they answer
+    pub fn set_version(&mut self, version: GetSetVersion) {
+        self.version = version;
+    }
+
+    /// Sequence number, only carried by v2 frames
+    pub fn sequence(&self) -> GetSetSequence {
+        self.sequence
+    }
+
+    /// Responders echo the sequence number of the request
+    pub fn set_sequence(&mut self, sequence: GetSetSequence) {
+        self.sequence = sequence;
+    }
+
     pub fn wire_size(&self) -> usize {
-        GetSetFrame::<&[u8]>::buffer_len(self.payload_wire_size())
+        GetSetFrame::<&[u8]>::frame_len(self.version, self.flags, self.payload_wire_size())
+    }
+
+    /// Number of leading parameters that fit in a frame of at most
+    /// `max_len` bytes, the rest belong on the following pages
+    pub fn page_len(&self, max_len: usize) -> usize {
+        let mut size = GetSetFrame::<&[u8]>::frame_len(
+            self.version,
+            self.flags,
+            ParameterListPacket::<&[u8]>::buffer_len(0),
+        );
+        self.params
+            .as_ref()
+            .iter()
+            .take(MAX_PARAMS_PER_OP)
+            .take_while(|p| {
+                size += p.wire_size();
+                size <= max_len
+            })
+            .count()
     }
 
     fn payload_wire_size(&self) -> usize {
@@ -46,7 +98,8 @@ impl<P: AsRef<[Parameter]>> RefResponse<P> {
         frame.set_preamble(PREAMBLE_WORD);
         frame.set_node_id(self.node_id);
         frame.set_flags(self.flags);
-        frame.set_version(1);
+        frame.set_version(self.version);
+        frame.set_sequence(self.sequence);
         frame.set_op(self.op);
         frame.set_payload_type(GetSetPayloadType::ParameterListPacket);
         frame.set_payload_size(self.payload_wire_size() as u16);
@@ -55,10 +108,22 @@ impl<P: AsRef<[Parameter]>> RefResponse<P> {
         for (index, param) in self.params.as_ref().iter().enumerate() {
             p.set_parameter_at(index, *param)?;
         }
+        frame.fill_checksum();
         Ok(())
     }
 }
 
+impl<P: AsRef<[Parameter]> + Default> Default for RefResponse<P> {
+    fn default() -> Self {
+        RefResponse::new(
+            NODE_ID_ANONYMOUS,
+            GetSetFlags::default(),
+            GetSetOp::default(),
+            P::default(),
+        )
+    }
+}
+
 impl<P: AsRef<[Parameter]>> fmt::Display for RefResponse<P> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(
@@ -131,7 +196,7 @@ mod tests {
         assert_eq!(resp.emit(&mut frame), Ok(()));
         assert_eq!(frame.check_len(), Ok(()));
         assert_eq!(frame.check_preamble(), Ok(()));
-        assert_eq!(frame.op(), GetSetOp::Set);
+        assert_eq!(frame.op(), Ok(GetSetOp::Set));
         assert_eq!(frame.payload_size(), resp.payload_wire_size() as u16);
         let packet = ParameterListPacket::new_checked(frame.payload_mut()).unwrap();
         assert_eq!(packet.check_len(), Ok(()));
@@ -139,4 +204,25 @@ mod tests {
         assert_eq!(packet.parameter_at(0), Ok(p_a));
         assert_eq!(packet.parameter_at(1), Ok(p_b));
     }
+
+    #[test]
+    fn page_len() {
+        let p = Parameter::new_with_value(
+            ParameterId::new(0x0A),
+            ParameterFlags(0),
+            ParameterValue::U32(1234),
+        );
+        let params = [p; MAX_PARAMS_PER_OP + 1];
+        let resp = RefResponse::new(0, 0, GetSetOp::ListAll, &params[..3]);
+        let one = RefResponse::new(0, 0, GetSetOp::ListAll, &params[..1]).wire_size();
+        let two = RefResponse::new(0, 0, GetSetOp::ListAll, &params[..2]).wire_size();
+        assert_eq!(resp.page_len(0), 0);
+        assert_eq!(resp.page_len(one), 1);
+        assert_eq!(resp.page_len(two - 1), 1);
+        assert_eq!(resp.page_len(two), 2);
+        assert_eq!(resp.page_len(usize::MAX), 3);
+
+        let resp = RefResponse::new(0, 0, GetSetOp::ListAll, &params[..]);
+        assert_eq!(resp.page_len(usize::MAX), MAX_PARAMS_PER_OP);
+    }
 }
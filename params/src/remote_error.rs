@@ -0,0 +1,244 @@
+use crate::{
+    Error, ErrorPacket, GetSetFlags, GetSetFrame, GetSetNodeId, GetSetOp, GetSetPayloadType,
+    GetSetSequence, GetSetVersion, GETSET_VERSION_1, PREAMBLE_WORD,
+};
+use core::convert::TryFrom;
+use core::fmt;
+
+/// Reason a node rejected a request
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum ErrorCode {
+    /// The request could not be decoded or its payload doesn't match the op
+    Malformed = 0,
+    Capacity = 1,
+    TypeMismatch = 2,
+    InvalidValue = 3,
+    Duplicate = 4,
+    PermissionDenied = 5,
+    NotFound = 6,
+    OutOfRange = 7,
+    /// The node's network stack failed while handling the request
+    Network = 8,
+    /// The node doesn't speak the request's frame version
+    UnsupportedVersion = 9,
+    /// The request must be signed with a key the node knows
+    Unauthenticated = 10,
+    /// The request was signed with a counter the node already accepted
+    Replayed = 11,
+    /// The request must be encrypted with the node's key
+    Undecryptable = 12,
+}
+
+impl ErrorCode {
+    pub(crate) fn as_u8(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl TryFrom<u8> for ErrorCode {
+    type Error = Error;
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            0 => Ok(ErrorCode::Malformed),
+            1 => Ok(ErrorCode::Capacity),
+            2 => Ok(ErrorCode::TypeMismatch),
+            3 => Ok(ErrorCode::InvalidValue),
+            4 => Ok(ErrorCode::Duplicate),
+            5 => Ok(ErrorCode::PermissionDenied),
+            6 => Ok(ErrorCode::NotFound),
+            7 => Ok(ErrorCode::OutOfRange),
+            8 => Ok(ErrorCode::Network),
+            9 => Ok(ErrorCode::UnsupportedVersion),
+            10 => Ok(ErrorCode::Unauthenticated),
+            11 => Ok(ErrorCode::Replayed),
+            12 => Ok(ErrorCode::Undecryptable),
+            _ => Err(Error::WireInvalidErrorCode),
+        }
+    }
+}
+
+impl From<Error> for ErrorCode {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::WireTruncated
+            | Error::WireIndexOutOfBounds
+            | Error::WirePreamble
+            | Error::WireChecksum
+            | Error::WireInvalidPayloadType
+            | Error::WireInvalidOp
+            | Error::WireInvalidValueType
+            | Error::WireInvalidSetStatus
+            | Error::WireInvalidErrorCode
+            | Error::WireInvalidCompareAndSet => ErrorCode::Malformed,
+            Error::WireUnsupportedVersion => ErrorCode::UnsupportedVersion,
+            Error::AuthRequired | Error::AuthFailed => ErrorCode::Unauthenticated,
+            Error::AuthReplay => ErrorCode::Replayed,
+            Error::EncryptionRequired | Error::DecryptFailed => ErrorCode::Undecryptable,
+            Error::ParseValue => ErrorCode::InvalidValue,
+            Error::Capacity => ErrorCode::Capacity,
+            Error::ValueTypeMismatch => ErrorCode::TypeMismatch,
+            Error::Remote(e) => e.code(),
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", *self)
+    }
+}
+
+/// Error reply from a node, carries the op of the offending request
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct RemoteError {
+    code: ErrorCode,
+    op: u8,
+}
+
+impl RemoteError {
+    /// The op is kept as received, it may not be a valid op
+    pub fn new(code: ErrorCode, op: u8) -> Self {
+        RemoteError { code, op }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    pub fn raw_op(&self) -> u8 {
+        self.op
+    }
+
+    pub fn op(&self) -> Result<GetSetOp, Error> {
+        GetSetOp::try_from(self.op)
+    }
+
+    /// Size of the emitted frame, the flags determine whether
+    /// it carries a CRC32 trailer
+    pub fn wire_size(&self, version: GetSetVersion, flags: GetSetFlags) -> usize {
+        GetSetFrame::<&[u8]>::frame_len(
+            self.reply_version(version),
+            flags,
+            ErrorPacket::<&[u8]>::buffer_len(),
+        )
+    }
+
+    /// A node can't answer a version it doesn't speak in kind,
+    /// those errors use the v1 layout every client can decode
+    fn reply_version(&self, version: GetSetVersion) -> GetSetVersion {
+        if self.code == ErrorCode::UnsupportedVersion {
+            GETSET_VERSION_1
+        } else {
+            version
+        }
+    }
+
+    pub fn parse<T: AsRef<[u8]> + ?Sized>(packet: &ErrorPacket<&T>) -> Result<Self, Error> {
+        packet.check_len()?;
+        Ok(RemoteError::new(packet.code()?, packet.op()))
+    }
+
+    /// Emit an Error op frame answering a request of the given version
+    /// and sequence number, which it echoes like a Response does.
+    /// UnsupportedVersion errors always use the v1 layout.
+    pub fn emit<T: AsRef<[u8]> + AsMut<[u8]>>(
+        &self,
+        node_id: GetSetNodeId,
+        flags: GetSetFlags,
+        version: GetSetVersion,
+        sequence: GetSetSequence,
+        frame: &mut GetSetFrame<T>,
+    ) -> Result<(), Error> {
+        frame.set_preamble(PREAMBLE_WORD);
+        frame.set_node_id(node_id);
+        frame.set_flags(flags);
+        frame.set_version(self.reply_version(version));
+        frame.set_sequence(sequence);
+        frame.set_op(GetSetOp::Error);
+        frame.set_payload_type(GetSetPayloadType::ErrorPacket);
+        frame.set_payload_size(ErrorPacket::<&[u8]>::buffer_len() as u16);
+        let mut p = ErrorPacket::new_checked(frame.payload_mut())?;
+        p.set_code(self.code);
+        p.set_op(self.op);
+        frame.fill_checksum();
+        Ok(())
+    }
+}
+
+impl fmt::Display for RemoteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RemoteError {{ code: {} op: ", self.code)?;
+        match self.op() {
+            Ok(op) => write!(f, "{} }}", op),
+            Err(_) => write!(f, "Invalid({}) }}", self.op),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Response, GETSET_VERSION_2};
+    use pretty_assertions::assert_eq;
+
+    static FRAME_BYTES: [u8; 19] = [
+        0xAB, 0xCD, 0xEF, 0xFF, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03, 0x04,
+        0x02, 0x00, 0x00, 0x02,
+    ];
+
+    #[test]
+    fn codes() {
+        for v in 0..=12 {
+            assert_eq!(ErrorCode::try_from(v).map(|c| c.as_u8()), Ok(v));
+        }
+        assert_eq!(ErrorCode::try_from(13), Err(Error::WireInvalidErrorCode));
+        assert_eq!(ErrorCode::from(Error::WireTruncated), ErrorCode::Malformed);
+        assert_eq!(
+            ErrorCode::from(Error::ValueTypeMismatch),
+            ErrorCode::TypeMismatch
+        );
+    }
+
+    #[test]
+    fn emit() {
+        let err = RemoteError::new(ErrorCode::Malformed, GetSetOp::Set.as_u8());
+        let mut bytes = [0xFF; 19];
+        assert_eq!(err.wire_size(GETSET_VERSION_1, 0), bytes.len());
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..]);
+        assert_eq!(err.emit(1, 0, GETSET_VERSION_1, 0, &mut frame), Ok(()));
+        assert_eq!(&bytes[..], &FRAME_BYTES[..]);
+    }
+
+    #[test]
+    fn emit_v2() {
+        let err = RemoteError::new(ErrorCode::NotFound, GetSetOp::Get.as_u8());
+        let mut bytes = [0xFF; 64];
+        let size = err.wire_size(GETSET_VERSION_2, 0);
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..size]);
+        assert_eq!(err.emit(1, 0, GETSET_VERSION_2, 42, &mut frame), Ok(()));
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(frame.version(), GETSET_VERSION_2);
+        assert_eq!(frame.sequence(), 42);
+        assert_eq!(Response::parse(&frame), Err(Error::Remote(err)));
+
+        // Unsupported versions are answered as v1
+        let err = RemoteError::new(ErrorCode::UnsupportedVersion, GetSetOp::Get.as_u8());
+        let size = err.wire_size(GETSET_VERSION_2, 0);
+        assert_eq!(size, FRAME_BYTES.len());
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..size]);
+        assert_eq!(err.emit(1, 0, GETSET_VERSION_2, 42, &mut frame), Ok(()));
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(frame.version(), GETSET_VERSION_1);
+        assert_eq!(frame.sequence(), 0);
+    }
+
+    #[test]
+    fn parse() {
+        let frame = GetSetFrame::new_checked(&FRAME_BYTES[..]).unwrap();
+        assert_eq!(frame.op(), Ok(GetSetOp::Error));
+        let err = RemoteError::new(ErrorCode::Malformed, GetSetOp::Set.as_u8());
+        assert_eq!(err.op(), Ok(GetSetOp::Set));
+        assert_eq!(Response::parse(&frame), Err(Error::Remote(err)));
+    }
+}
@@ -1,20 +1,28 @@
 use crate::{
-    Error, GetSetFlags, GetSetFrame, GetSetNodeId, GetSetOp, GetSetPayloadType, MaxParamsPerOp,
-    Parameter, ParameterId, ParameterIdListPacket, ParameterListPacket, PREAMBLE_WORD,
+    Error, GetSetFlags, GetSetFrame, GetSetNodeId, GetSetOp, GetSetPayloadType, GetSetSequence,
+    GetSetVersion, MaxParamsPerOp, PagePacket, Parameter, ParameterFlags, ParameterId,
+    ParameterIdListPacket, ParameterListPacket, ParameterValue, ParameterValueBytes, PingPacket,
+    SincePacket, GETSET_VERSION_1, NODE_ID_ANONYMOUS, PREAMBLE_WORD,
 };
 use core::fmt;
 use heapless::Vec;
 
 // TODO - revist this impl, could be much more memory conscious
 // wrap a slice of refs instead?
-#[derive(Clone, PartialEq, Debug, Default)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct Request {
     node_id: GetSetNodeId,
     flags: GetSetFlags,
+    version: GetSetVersion,
+    sequence: GetSetSequence,
     op: GetSetOp,
     payload_type: GetSetPayloadType,
     ids: Vec<ParameterId, MaxParamsPerOp>,
     params: Vec<Parameter, MaxParamsPerOp>,
+    offset: u16,
+    since_ms: u64,
+    start_id: ParameterId,
+    ping_data: ParameterValueBytes,
 }
 
 impl Request {
@@ -27,17 +35,92 @@ impl Request {
         Request {
             node_id,
             flags,
+            version: GETSET_VERSION_1,
+            sequence: 0,
             op,
             payload_type,
             ids: Vec::new(),
             params: Vec::new(),
+            offset: 0,
+            since_ms: 0,
+            start_id: ParameterId::default(),
+            ping_data: ParameterValueBytes::new(),
         }
     }
 
+    pub fn flags(&self) -> GetSetFlags {
+        self.flags
+    }
+
+    pub fn set_flags(&mut self, flags: GetSetFlags) {
+        self.flags = flags;
+    }
+
     pub fn op(&self) -> GetSetOp {
         self.op
     }
 
+    /// Frame layout version, v1 unless set otherwise
+    pub fn version(&self) -> GetSetVersion {
+        self.version
+    }
+
+    pub fn set_version(&mut self, version: GetSetVersion) {
+        self.version = version;
+    }
+
+    /// Sequence number, only carried by v2 frames
+    pub fn sequence(&self) -> GetSetSequence {
+        self.sequence
+    }
+
+    /// Has no effect on the wire unless the version is v2
+    pub fn set_sequence(&mut self, sequence: GetSetSequence) {
+        self.sequence = sequence;
+    }
+
+    /// Index of the first parameter of the requested page
+    pub fn offset(&self) -> u16 {
+        self.offset
+    }
+
+    /// Has no effect on the wire unless the payload is a `PagePacket`
+    pub fn set_offset(&mut self, offset: u16) {
+        self.offset = offset;
+    }
+
+    /// Node time after which changed parameters are wanted
+    pub fn since_ms(&self) -> u64 {
+        self.since_ms
+    }
+
+    /// Has no effect on the wire unless the payload is a `SincePacket`
+    pub fn set_since_ms(&mut self, since_ms: u64) {
+        self.since_ms = since_ms;
+    }
+
+    /// Lowest ID of the requested page of changed parameters
+    pub fn start_id(&self) -> ParameterId {
+        self.start_id
+    }
+
+    /// Has no effect on the wire unless the payload is a `SincePacket`
+    pub fn set_start_id(&mut self, start_id: ParameterId) {
+        self.start_id = start_id;
+    }
+
+    /// Opaque data a Ping has echoed
+    pub fn ping_data(&self) -> &[u8] {
+        self.ping_data.as_slice()
+    }
+
+    /// Has no effect on the wire unless the payload is a `PingPacket`,
+    /// holds up to MAX_BYTES_LEN bytes
+    pub fn set_ping_data(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.ping_data = ParameterValueBytes::from_slice(data)?;
+        Ok(())
+    }
+
     pub fn push_id(&mut self, id: ParameterId) -> Result<(), Error> {
         self.ids.push(id).map_err(|_| Error::Capacity)?;
         Ok(())
@@ -48,6 +131,28 @@ impl Request {
         Ok(())
     }
 
+    /// Push an entry of a CompareAndSet, the expected then the new value
+    pub fn push_compare_and_set(
+        &mut self,
+        id: ParameterId,
+        expected: ParameterValue,
+        value: ParameterValue,
+    ) -> Result<(), Error> {
+        if self.params.len() + 2 > self.params.capacity() {
+            return Err(Error::Capacity);
+        }
+        self.push_parameter(Parameter::new_with_value(
+            id,
+            ParameterFlags::default(),
+            expected,
+        ))?;
+        self.push_parameter(Parameter::new_with_value(
+            id,
+            ParameterFlags::default(),
+            value,
+        ))
+    }
+
     pub fn pop_id(&mut self) -> Option<ParameterId> {
         self.ids.pop()
     }
@@ -70,7 +175,7 @@ impl Request {
     }
 
     pub fn wire_size(&self) -> usize {
-        GetSetFrame::<&[u8]>::buffer_len(self.payload_wire_size())
+        GetSetFrame::<&[u8]>::frame_len(self.version, self.flags, self.payload_wire_size())
     }
 
     fn payload_wire_size(&self) -> usize {
@@ -82,36 +187,73 @@ impl Request {
             GetSetPayloadType::ParameterListPacket => ParameterListPacket::<&[u8]>::buffer_len(
                 self.params.iter().map(|p| p.wire_size()).sum(),
             ),
+            GetSetPayloadType::PagePacket => PagePacket::<&[u8]>::buffer_len(),
+            GetSetPayloadType::SincePacket => SincePacket::<&[u8]>::buffer_len(),
+            GetSetPayloadType::PingPacket => PingPacket::<&[u8]>::buffer_len(self.ping_data.len()),
+            // Only used by responses
+            GetSetPayloadType::SetResultListPacket
+            | GetSetPayloadType::ErrorPacket
+            | GetSetPayloadType::CapabilitiesPacket
+            | GetSetPayloadType::CompareAndSetResultPacket
+            | GetSetPayloadType::DescriptionListPacket
+            | GetSetPayloadType::ChangedListPacket
+            | GetSetPayloadType::AnnouncePacket => 0,
         }
     }
 
     pub fn parse<T: AsRef<[u8]> + ?Sized>(frame: &GetSetFrame<&T>) -> Result<Self, Error> {
         frame.check_len()?;
         frame.check_preamble()?;
+        frame.check_version()?;
+        frame.check_payload_len()?;
+        frame.check_checksum()?;
         let node_id = frame.node_id();
         let flags = frame.flags();
-        let _ver = frame.version();
-        let op = frame.op();
-        let payload_type = frame.payload_type();
+        let op = frame.op()?;
+        let payload_type = frame.payload_type()?;
+        let mut r = Request::new(node_id, flags, op, payload_type);
+        r.set_version(frame.version());
+        r.set_sequence(frame.sequence());
 
         match payload_type {
-            GetSetPayloadType::None => Ok(Request::new(node_id, flags, op, payload_type)),
+            GetSetPayloadType::None => Ok(r),
             GetSetPayloadType::ParameterIdListPacket => {
-                let mut r = Request::new(node_id, flags, op, payload_type);
-                let p = ParameterIdListPacket::new_checked(frame.payload())?;
-                for index in 0..usize::from(p.count()) {
-                    r.push_id(p.id_at(index)?)?
+                let p = ParameterIdListPacket::new_checked(frame.checked_payload()?)?;
+                for id in p.iter() {
+                    r.push_id(id?)?
                 }
                 Ok(r)
             }
             GetSetPayloadType::ParameterListPacket => {
-                let mut r = Request::new(node_id, flags, op, payload_type);
-                let p = ParameterListPacket::new_checked(frame.payload())?;
-                for index in 0..usize::from(p.count()) {
-                    r.push_parameter(p.parameter_at(index)?)?
+                let p = ParameterListPacket::new_checked(frame.checked_payload()?)?;
+                for param in p.iter() {
+                    r.push_parameter(param?)?
                 }
                 Ok(r)
             }
+            GetSetPayloadType::PagePacket => {
+                let p = PagePacket::new_checked(frame.checked_payload()?)?;
+                r.set_offset(p.offset());
+                Ok(r)
+            }
+            GetSetPayloadType::SincePacket => {
+                let p = SincePacket::new_checked(frame.checked_payload()?)?;
+                r.set_since_ms(p.since_ms());
+                r.set_start_id(p.start_id());
+                Ok(r)
+            }
+            GetSetPayloadType::PingPacket => {
+                let p = PingPacket::new_checked(frame.checked_payload()?)?;
+                r.set_ping_data(p.data())?;
+                Ok(r)
+            }
+            GetSetPayloadType::SetResultListPacket
+            | GetSetPayloadType::ErrorPacket
+            | GetSetPayloadType::CapabilitiesPacket
+            | GetSetPayloadType::CompareAndSetResultPacket
+            | GetSetPayloadType::DescriptionListPacket
+            | GetSetPayloadType::ChangedListPacket
+            | GetSetPayloadType::AnnouncePacket => Err(Error::WireInvalidPayloadType),
         }
     }
 
@@ -122,7 +264,8 @@ impl Request {
         frame.set_preamble(PREAMBLE_WORD);
         frame.set_node_id(self.node_id);
         frame.set_flags(self.flags);
-        frame.set_version(1);
+        frame.set_version(self.version);
+        frame.set_sequence(self.sequence);
         frame.set_op(self.op);
         frame.set_payload_type(self.payload_type);
         frame.set_payload_size(self.payload_wire_size() as u16);
@@ -144,7 +287,45 @@ impl Request {
                 }
                 Ok(())
             }
-        }
+            GetSetPayloadType::PagePacket => {
+                let mut p = PagePacket::new_unchecked(frame.payload_mut());
+                p.set_offset(self.offset);
+                Ok(())
+            }
+            GetSetPayloadType::SincePacket => {
+                let mut p = SincePacket::new_unchecked(frame.payload_mut());
+                p.set_since_ms(self.since_ms);
+                p.set_start_id(self.start_id);
+                Ok(())
+            }
+            GetSetPayloadType::PingPacket => {
+                let mut p = PingPacket::new_unchecked(frame.payload_mut());
+                p.set_uptime(0);
+                p.set_time_ms(0);
+                p.set_data(self.ping_data());
+                Ok(())
+            }
+            GetSetPayloadType::SetResultListPacket
+            | GetSetPayloadType::ErrorPacket
+            | GetSetPayloadType::CapabilitiesPacket
+            | GetSetPayloadType::CompareAndSetResultPacket
+            | GetSetPayloadType::DescriptionListPacket
+            | GetSetPayloadType::ChangedListPacket
+            | GetSetPayloadType::AnnouncePacket => Err(Error::WireInvalidPayloadType),
+        }?;
+        frame.fill_checksum();
+        Ok(())
+    }
+}
+
+impl Default for Request {
+    fn default() -> Self {
+        Request::new(
+            NODE_ID_ANONYMOUS,
+            GetSetFlags::default(),
+            GetSetOp::default(),
+            GetSetPayloadType::default(),
+        )
     }
 }
 
@@ -164,7 +345,10 @@ impl fmt::Display for Request {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{GetSetPayloadType, ParameterFlags, ParameterPacket, ParameterValue};
+    use crate::{
+        GetSetPayloadType, ParameterFlags, ParameterId, ParameterPacket, ParameterValue,
+        RefRequest, GETSET_VERSION_2,
+    };
     use core::convert::TryInto;
     use core::mem;
     use pretty_assertions::assert_eq;
@@ -288,7 +472,7 @@ mod tests {
         assert_eq!(req.emit(&mut frame), Ok(()));
         assert_eq!(frame.check_len(), Ok(()));
         assert_eq!(frame.check_preamble(), Ok(()));
-        assert_eq!(frame.op(), GetSetOp::ListAll);
+        assert_eq!(frame.op(), Ok(GetSetOp::ListAll));
         assert_eq!(frame.payload_size(), req.payload_wire_size() as u16);
 
         let mut req = Request::new(0, 0, GetSetOp::Set, GetSetPayloadType::ParameterListPacket);
@@ -311,7 +495,7 @@ mod tests {
         assert_eq!(req.emit(&mut frame), Ok(()));
         assert_eq!(frame.check_len(), Ok(()));
         assert_eq!(frame.check_preamble(), Ok(()));
-        assert_eq!(frame.op(), GetSetOp::Set);
+        assert_eq!(frame.op(), Ok(GetSetOp::Set));
         assert_eq!(frame.payload_size(), req.payload_wire_size() as u16);
         let packet = ParameterListPacket::new_checked(frame.payload_mut()).unwrap();
         assert_eq!(packet.check_len(), Ok(()));
@@ -335,7 +519,7 @@ mod tests {
         assert_eq!(req.emit(&mut frame), Ok(()));
         assert_eq!(frame.check_len(), Ok(()));
         assert_eq!(frame.check_preamble(), Ok(()));
-        assert_eq!(frame.op(), GetSetOp::Get);
+        assert_eq!(frame.op(), Ok(GetSetOp::Get));
         assert_eq!(frame.payload_size(), req.payload_wire_size() as u16);
         let packet = ParameterIdListPacket::new_checked(frame.payload_mut()).unwrap();
         assert_eq!(packet.check_len(), Ok(()));
@@ -352,14 +536,14 @@ mod tests {
         assert_eq!(f.node_id(), 0x01);
         assert_eq!(f.flags(), 0);
         assert_eq!(f.version(), 1);
-        assert_eq!(f.op(), GetSetOp::Set);
-        assert_eq!(f.payload_type(), GetSetPayloadType::ParameterListPacket);
+        assert_eq!(f.op(), Ok(GetSetOp::Set));
+        assert_eq!(f.payload_type(), Ok(GetSetPayloadType::ParameterListPacket));
         assert_eq!(f.payload_size(), PAYLOAD_BYTES.len().try_into().unwrap());
         assert_eq!(f.payload(), &PAYLOAD_BYTES[..]);
         let p = ParameterListPacket::new_checked(f.payload()).unwrap();
         assert_eq!(p.count(), PARAMS.len().try_into().unwrap());
-        for index in 0..PARAMS.len() {
-            assert_eq!(p.parameter_at(index), Ok(PARAMS[index]));
+        for (index, param) in PARAMS.iter().enumerate() {
+            assert_eq!(p.parameter_at(index), Ok(*param));
         }
 
         let req = Request::parse(&f).unwrap();
@@ -368,4 +552,218 @@ mod tests {
             assert_eq!(*p, PARAMS[index]);
         }
     }
+
+    #[test]
+    fn parse_invalid() {
+        let mut bytes = FRAME_BYTES;
+        bytes[13] = 0xEE;
+        let f = GetSetFrame::new_checked(&bytes[..]).unwrap();
+        assert_eq!(Request::parse(&f), Err(Error::WireInvalidOp));
+
+        let mut bytes = FRAME_BYTES;
+        bytes[14] = 0xEE;
+        let f = GetSetFrame::new_checked(&bytes[..]).unwrap();
+        assert_eq!(Request::parse(&f), Err(Error::WireInvalidPayloadType));
+
+        let f = GetSetFrame::new_unchecked(&FRAME_BYTES[..FRAME_BYTES.len() - 1]);
+        assert_eq!(Request::parse(&f), Err(Error::WireTruncated));
+    }
+
+    #[test]
+    fn round_trip_v2() {
+        let mut req = Request::new(
+            1,
+            0,
+            GetSetOp::Get,
+            GetSetPayloadType::ParameterIdListPacket,
+        );
+        req.set_version(GETSET_VERSION_2);
+        req.set_sequence(0x1234);
+        assert_eq!(req.push_id(ParameterId::new(0x0A)), Ok(()));
+        let mut bytes = [0xFF; 32];
+        let size = req.wire_size();
+        assert_eq!(size, 21 + 5);
+        assert_eq!(
+            req.emit(&mut GetSetFrame::new_unchecked(&mut bytes[..size])),
+            Ok(())
+        );
+        let f = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(f.version(), GETSET_VERSION_2);
+        assert_eq!(f.sequence(), 0x1234);
+        assert_eq!(Request::parse(&f), Ok(req));
+    }
+
+    #[test]
+    fn round_trip_subscribe() {
+        let mut req = Request::new(
+            1,
+            0,
+            GetSetOp::Subscribe,
+            GetSetPayloadType::ParameterIdListPacket,
+        );
+        assert_eq!(req.push_id(ParameterId::new(0x0A)), Ok(()));
+        assert_eq!(req.push_id(ParameterId::new(0x0B)), Ok(()));
+        let mut bytes = [0xFF; 32];
+        let size = req.wire_size();
+        assert_eq!(size, 17 + 1 + 8);
+        assert_eq!(
+            req.emit(&mut GetSetFrame::new_unchecked(&mut bytes[..size])),
+            Ok(())
+        );
+        let f = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(f.op(), Ok(GetSetOp::Subscribe));
+        assert_eq!(Request::parse(&f), Ok(req));
+
+        // The count claims more IDs than the payload holds
+        bytes[17] = 3;
+        let f = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(Request::parse(&f), Err(Error::WireTruncated));
+        assert_eq!(RefRequest::parse(&f).err(), Some(Error::WireTruncated));
+    }
+
+    #[test]
+    fn round_trip_unsubscribe() {
+        let mut req = Request::new(
+            1,
+            0,
+            GetSetOp::Unsubscribe,
+            GetSetPayloadType::ParameterIdListPacket,
+        );
+        assert_eq!(req.push_id(ParameterId::new(0x0A)), Ok(()));
+        let mut bytes = [0xFF; 32];
+        let size = req.wire_size();
+        assert_eq!(
+            req.emit(&mut GetSetFrame::new_unchecked(&mut bytes[..size])),
+            Ok(())
+        );
+        let f = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(Request::parse(&f), Ok(req));
+
+        // Without IDs every subscription is dropped
+        let req = Request::new(1, 0, GetSetOp::Unsubscribe, GetSetPayloadType::None);
+        let size = req.wire_size();
+        assert_eq!(size, 17);
+        assert_eq!(
+            req.emit(&mut GetSetFrame::new_unchecked(&mut bytes[..size])),
+            Ok(())
+        );
+        let f = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(Request::parse(&f), Ok(req));
+
+        // Truncated payload
+        let mut bytes = [0xFF; 32];
+        let mut req = Request::new(
+            1,
+            0,
+            GetSetOp::Unsubscribe,
+            GetSetPayloadType::ParameterIdListPacket,
+        );
+        assert_eq!(req.push_id(ParameterId::new(0x0A)), Ok(()));
+        let size = req.wire_size();
+        assert_eq!(
+            req.emit(&mut GetSetFrame::new_unchecked(&mut bytes[..size])),
+            Ok(())
+        );
+        let f = GetSetFrame::new_unchecked(&bytes[..size - 1]);
+        assert_eq!(Request::parse(&f), Err(Error::WireTruncated));
+    }
+
+    #[test]
+    fn round_trip_invoke() {
+        let mut req = Request::new(
+            1,
+            0,
+            GetSetOp::Invoke,
+            GetSetPayloadType::ParameterListPacket,
+        );
+        assert_eq!(
+            req.push_parameter(Parameter::new_with_value(
+                ParameterId::new(402),
+                ParameterFlags(0),
+                ParameterValue::U32(3),
+            )),
+            Ok(())
+        );
+        let mut bytes = [0xFF; 64];
+        let size = req.wire_size();
+        assert_eq!(
+            size,
+            17 + ParameterListPacket::<&[u8]>::header_len()
+                + ParameterPacket::<&[u8]>::header_len()
+                + mem::size_of::<u32>()
+        );
+        assert_eq!(
+            req.emit(&mut GetSetFrame::new_unchecked(&mut bytes[..size])),
+            Ok(())
+        );
+        let f = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(f.op(), Ok(GetSetOp::Invoke));
+        assert_eq!(Request::parse(&f), Ok(req));
+        let ref_req = RefRequest::parse(&f).unwrap();
+        let mut parameters = ref_req.parameters();
+        let p = parameters.next().unwrap().unwrap();
+        assert_eq!(p.id(), ParameterId::new(402));
+        assert_eq!(p.value(), ParameterValue::U32(3));
+        assert!(parameters.next().is_none());
+
+        // The argument is cut short
+        let f = GetSetFrame::new_unchecked(&bytes[..size - 1]);
+        assert_eq!(Request::parse(&f), Err(Error::WireTruncated));
+        assert_eq!(RefRequest::parse(&f).err(), Some(Error::WireTruncated));
+    }
+
+    #[test]
+    fn round_trip_page() {
+        let mut req = Request::new(1, 0, GetSetOp::ListAll, GetSetPayloadType::PagePacket);
+        req.set_offset(12);
+        let mut bytes = [0xFF; 32];
+        let size = req.wire_size();
+        assert_eq!(size, 17 + 2);
+        assert_eq!(
+            req.emit(&mut GetSetFrame::new_unchecked(&mut bytes[..size])),
+            Ok(())
+        );
+        let f = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(f.payload(), &[12, 0][..]);
+        assert_eq!(Request::parse(&f), Ok(req));
+    }
+
+    #[test]
+    fn round_trip_since() {
+        let mut req = Request::new(1, 0, GetSetOp::GetChanged, GetSetPayloadType::SincePacket);
+        req.set_since_ms(5000);
+        req.set_start_id(ParameterId::new(2));
+        let mut bytes = [0xFF; 32];
+        let size = req.wire_size();
+        assert_eq!(size, 17 + 12);
+        assert_eq!(
+            req.emit(&mut GetSetFrame::new_unchecked(&mut bytes[..size])),
+            Ok(())
+        );
+        let f = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(Request::parse(&f), Ok(req));
+        let ref_req = RefRequest::parse(&f).unwrap();
+        assert_eq!(ref_req.since_ms(), 5000);
+        assert_eq!(ref_req.start_id(), ParameterId::new(2));
+        assert_eq!(ref_req.offset(), 0);
+    }
+
+    #[test]
+    fn round_trip_ping() {
+        let mut req = Request::new(1, 0, GetSetOp::Ping, GetSetPayloadType::PingPacket);
+        assert_eq!(req.set_ping_data(&[1, 2, 3, 4]), Ok(()));
+        assert_eq!(req.set_ping_data(&[0; 33]), Err(Error::Capacity));
+        assert_eq!(req.ping_data(), &[1, 2, 3, 4][..]);
+        let mut bytes = [0xFF; 64];
+        let size = req.wire_size();
+        assert_eq!(size, 17 + 13 + 4);
+        assert_eq!(
+            req.emit(&mut GetSetFrame::new_unchecked(&mut bytes[..size])),
+            Ok(())
+        );
+        let f = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(Request::parse(&f), Ok(req));
+        let ref_req = RefRequest::parse(&f).unwrap();
+        assert_eq!(ref_req.ping_data(), &[1, 2, 3, 4][..]);
+    }
 }
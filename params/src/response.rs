@@ -1,34 +1,91 @@
 // TODO - make this use the RefResponse impl, it's a dup
 
 use crate::{
-    Error, GetSetFlags, GetSetFrame, GetSetNodeId, GetSetOp, GetSetPayloadType, MaxParamsPerOp,
-    Parameter, ParameterListPacket, PREAMBLE_WORD,
+    Error, ErrorPacket, GetSetFlags, GetSetFrame, GetSetNodeId, GetSetOp, GetSetPayloadType,
+    GetSetSequence, GetSetVersion, MaxParamsPerOp, Parameter, ParameterListPacket,
+    ParameterValueBytes, PingPacket, RemoteError, SetResult, SetResultListPacket, TimePacket,
+    GETSET_FLAG_ATOMIC, GETSET_FLAG_MORE, GETSET_VERSION_1, NODE_ID_ANONYMOUS, PREAMBLE_WORD,
 };
 use core::fmt;
 use heapless::Vec;
 
-#[derive(Clone, PartialEq, Debug, Default)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct Response {
     node_id: GetSetNodeId,
     flags: GetSetFlags,
+    version: GetSetVersion,
+    sequence: GetSetSequence,
     op: GetSetOp,
+    payload_type: GetSetPayloadType,
     params: Vec<Parameter, MaxParamsPerOp>,
+    results: Vec<SetResult, MaxParamsPerOp>,
+    node_time_ms: u64,
+    uptime: u32,
+    ping_data: ParameterValueBytes,
 }
 
 impl Response {
     pub fn new(node_id: GetSetNodeId, flags: GetSetFlags, op: GetSetOp) -> Self {
+        Self::new_with_payload_type(node_id, flags, op, GetSetPayloadType::ParameterListPacket)
+    }
+
+    pub fn new_with_payload_type(
+        node_id: GetSetNodeId,
+        flags: GetSetFlags,
+        op: GetSetOp,
+        payload_type: GetSetPayloadType,
+    ) -> Self {
         Response {
             node_id,
             flags,
+            version: GETSET_VERSION_1,
+            sequence: 0,
             op,
+            payload_type,
             params: Vec::new(),
+            results: Vec::new(),
+            node_time_ms: 0,
+            uptime: 0,
+            ping_data: ParameterValueBytes::new(),
         }
     }
 
+    pub fn flags(&self) -> GetSetFlags {
+        self.flags
+    }
+
+    pub fn set_flags(&mut self, flags: GetSetFlags) {
+        self.flags = flags;
+    }
+
     pub fn op(&self) -> GetSetOp {
         self.op
     }
 
+    pub fn payload_type(&self) -> GetSetPayloadType {
+        self.payload_type
+    }
+
+    /// Frame layout version, v1 unless set otherwise
+    pub fn version(&self) -> GetSetVersion {
+        self.version
+    }
+
+    /// Responses should use the version of the request they answer
+    pub fn set_version(&mut self, version: GetSetVersion) {
+        self.version = version;
+    }
+
+    /// Sequence number, only carried by v2 frames
+    pub fn sequence(&self) -> GetSetSequence {
+        self.sequence
+    }
+
+    /// Responders echo the sequence number of the request
+    pub fn set_sequence(&mut self, sequence: GetSetSequence) {
+        self.sequence = sequence;
+    }
+
     pub fn push(&mut self, param: Parameter) -> Result<(), Error> {
         self.params.push(param).map_err(|_| Error::Capacity)?;
         Ok(())
@@ -42,35 +99,168 @@ impl Response {
         &self.params
     }
 
+    pub fn push_result(&mut self, result: SetResult) -> Result<(), Error> {
+        self.results.push(result).map_err(|_| Error::Capacity)?;
+        Ok(())
+    }
+
+    pub fn results(&self) -> &[SetResult] {
+        &self.results
+    }
+
+    /// Whether an atomic Set was committed or rolled back,
+    /// None if the response isn't for an atomic Set
+    pub fn committed(&self) -> Option<bool> {
+        if self.op == GetSetOp::Set && self.flags & GETSET_FLAG_ATOMIC != 0 {
+            Some(self.results.iter().all(|r| r.status().is_success()))
+        } else {
+            None
+        }
+    }
+
+    /// Node time a GetChanged or Ping was answered at, zero for other
+    /// responses
+    pub fn node_time_ms(&self) -> u64 {
+        self.node_time_ms
+    }
+
+    pub fn set_node_time_ms(&mut self, time_ms: u64) {
+        self.node_time_ms = time_ms;
+    }
+
+    /// Node uptime in seconds a Ping was answered at, zero for other
+    /// responses
+    pub fn uptime(&self) -> u32 {
+        self.uptime
+    }
+
+    pub fn set_uptime(&mut self, uptime: u32) {
+        self.uptime = uptime;
+    }
+
+    /// Opaque data a Ping echoed
+    pub fn ping_data(&self) -> &[u8] {
+        self.ping_data.as_slice()
+    }
+
+    /// Holds up to MAX_BYTES_LEN bytes
+    pub fn set_ping_data(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.ping_data = ParameterValueBytes::from_slice(data)?;
+        Ok(())
+    }
+
+    /// A paged ListAll or Get has more parameters than this page holds
+    pub fn more(&self) -> bool {
+        self.flags & GETSET_FLAG_MORE != 0
+    }
+
     pub fn clear(&mut self) {
         self.params.clear();
+        self.results.clear();
     }
 
     pub fn wire_size(&self) -> usize {
-        GetSetFrame::<&[u8]>::buffer_len(self.payload_wire_size())
+        GetSetFrame::<&[u8]>::frame_len(self.version, self.flags, self.payload_wire_size())
     }
 
     fn payload_wire_size(&self) -> usize {
+        match self.payload_type {
+            GetSetPayloadType::SetResultListPacket => self.results_wire_size(),
+            GetSetPayloadType::CompareAndSetResultPacket => {
+                self.results_wire_size() + self.params_wire_size()
+            }
+            GetSetPayloadType::ChangedListPacket => {
+                TimePacket::<&[u8]>::buffer_len() + self.params_wire_size()
+            }
+            GetSetPayloadType::PingPacket => PingPacket::<&[u8]>::buffer_len(self.ping_data.len()),
+            _ => self.params_wire_size(),
+        }
+    }
+
+    fn results_wire_size(&self) -> usize {
+        SetResultListPacket::<&[u8]>::buffer_len(self.results.iter().map(|r| r.wire_size()).sum())
+    }
+
+    fn params_wire_size(&self) -> usize {
         ParameterListPacket::<&[u8]>::buffer_len(self.params.iter().map(|p| p.wire_size()).sum())
     }
 
+    fn emit_results(&self, payload: &mut [u8]) -> Result<(), Error> {
+        let mut p = SetResultListPacket::new_unchecked(payload);
+        p.set_count(self.results.len() as _);
+        for (index, result) in self.results.iter().enumerate() {
+            p.set_result_at(index, *result)?;
+        }
+        Ok(())
+    }
+
     pub fn parse<T: AsRef<[u8]> + ?Sized>(frame: &GetSetFrame<&T>) -> Result<Self, Error> {
         frame.check_len()?;
         frame.check_preamble()?;
+        frame.check_version()?;
+        frame.check_payload_len()?;
+        frame.check_checksum()?;
         let node_id = frame.node_id();
         let flags = frame.flags();
-        let _ver = frame.version();
-        let op = frame.op();
-        let payload_type = frame.payload_type();
-        if payload_type == GetSetPayloadType::ParameterListPacket {
-            let mut r = Response::new(node_id, flags, op);
-            let p = ParameterListPacket::new_checked(frame.payload())?;
-            for index in 0..usize::from(p.count()) {
-                r.push(p.parameter_at(index)?)?
+        let op = frame.op()?;
+        let payload_type = frame.payload_type()?;
+        let mut r = Response::new_with_payload_type(node_id, flags, op, payload_type);
+        r.set_version(frame.version());
+        r.set_sequence(frame.sequence());
+        match payload_type {
+            GetSetPayloadType::ParameterListPacket => {
+                let p = ParameterListPacket::new_checked(frame.checked_payload()?)?;
+                for param in p.iter() {
+                    r.push(param?)?
+                }
+                Ok(r)
             }
-            Ok(r)
-        } else {
-            Err(Error::WireInvalidPayloadType)
+            GetSetPayloadType::ErrorPacket => {
+                let p = ErrorPacket::new_checked(frame.checked_payload()?)?;
+                Err(Error::Remote(RemoteError::parse(&p)?))
+            }
+            GetSetPayloadType::SetResultListPacket => {
+                let p = SetResultListPacket::new_checked(frame.checked_payload()?)?;
+                for result in p.iter() {
+                    r.push_result(result?)?
+                }
+                Ok(r)
+            }
+            GetSetPayloadType::CompareAndSetResultPacket => {
+                let payload = frame.checked_payload()?;
+                let p = SetResultListPacket::new_checked(payload)?;
+                for result in p.iter() {
+                    r.push_result(result?)?
+                }
+                let payload = payload
+                    .get(r.results_wire_size()..)
+                    .ok_or(Error::WireTruncated)?;
+                let p = ParameterListPacket::new_checked(payload)?;
+                for param in p.iter() {
+                    r.push(param?)?
+                }
+                Ok(r)
+            }
+            GetSetPayloadType::ChangedListPacket => {
+                let payload = frame.checked_payload()?;
+                let p = TimePacket::new_checked(payload)?;
+                r.set_node_time_ms(p.time_ms());
+                let p = ParameterListPacket::new_checked(
+                    &payload[TimePacket::<&[u8]>::buffer_len()..],
+                )?;
+                for param in p.iter() {
+                    r.push(param?)?
+                }
+                Ok(r)
+            }
+            GetSetPayloadType::PingPacket => {
+                let p = PingPacket::new_checked(frame.checked_payload()?)?;
+                r.set_uptime(p.uptime());
+                r.set_node_time_ms(p.time_ms());
+                r.set_ping_data(p.data())?;
+                Ok(r)
+            }
+            _ => Err(Error::WireInvalidPayloadType),
         }
     }
 
@@ -81,25 +271,78 @@ impl Response {
         frame.set_preamble(PREAMBLE_WORD);
         frame.set_node_id(self.node_id);
         frame.set_flags(self.flags);
-        frame.set_version(1);
+        frame.set_version(self.version);
+        frame.set_sequence(self.sequence);
         frame.set_op(self.op);
-        frame.set_payload_type(GetSetPayloadType::ParameterListPacket);
+        frame.set_payload_type(self.payload_type);
         frame.set_payload_size(self.payload_wire_size() as u16);
-        let mut p = ParameterListPacket::new_unchecked(frame.payload_mut());
-        p.set_count(self.params.len() as _);
-        for (index, param) in self.params.iter().enumerate() {
-            p.set_parameter_at(index, *param)?;
-        }
+        match self.payload_type {
+            GetSetPayloadType::ParameterListPacket => {
+                let mut p = ParameterListPacket::new_unchecked(frame.payload_mut());
+                p.set_count(self.params.len() as _);
+                for (index, param) in self.params.iter().enumerate() {
+                    p.set_parameter_at(index, *param)?;
+                }
+                Ok(())
+            }
+            GetSetPayloadType::SetResultListPacket => self.emit_results(frame.payload_mut()),
+            GetSetPayloadType::CompareAndSetResultPacket => {
+                let payload = frame.payload_mut();
+                self.emit_results(payload)?;
+                let mut p =
+                    ParameterListPacket::new_unchecked(&mut payload[self.results_wire_size()..]);
+                p.set_count(self.params.len() as _);
+                for (index, param) in self.params.iter().enumerate() {
+                    p.set_parameter_at(index, *param)?;
+                }
+                Ok(())
+            }
+            GetSetPayloadType::ChangedListPacket => {
+                let payload = frame.payload_mut();
+                let mut p = TimePacket::new_unchecked(&mut payload[..]);
+                p.set_time_ms(self.node_time_ms);
+                let mut p = ParameterListPacket::new_unchecked(
+                    &mut payload[TimePacket::<&[u8]>::buffer_len()..],
+                );
+                p.set_count(self.params.len() as _);
+                for (index, param) in self.params.iter().enumerate() {
+                    p.set_parameter_at(index, *param)?;
+                }
+                Ok(())
+            }
+            GetSetPayloadType::PingPacket => {
+                let mut p = PingPacket::new_unchecked(frame.payload_mut());
+                p.set_uptime(self.uptime);
+                p.set_time_ms(self.node_time_ms);
+                p.set_data(self.ping_data());
+                Ok(())
+            }
+            _ => Err(Error::WireInvalidPayloadType),
+        }?;
+        frame.fill_checksum();
         Ok(())
     }
 }
 
+impl Default for Response {
+    fn default() -> Self {
+        Response::new(
+            NODE_ID_ANONYMOUS,
+            GetSetFlags::default(),
+            GetSetOp::default(),
+        )
+    }
+}
+
 impl fmt::Display for Response {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "Response {{ nid: {} op: {} }}", self.node_id, self.op())?;
         for p in &self.params {
             writeln!(f, "{}", p)?;
         }
+        for r in &self.results {
+            writeln!(f, "{}", r)?;
+        }
         Ok(())
     }
 }
@@ -107,7 +350,9 @@ impl fmt::Display for Response {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{GetSetPayloadType, ParameterFlags, ParameterId, ParameterPacket, ParameterValue};
+    use crate::{
+        ParameterFlags, ParameterId, ParameterPacket, ParameterValue, SetStatus, GETSET_VERSION_2,
+    };
     use core::convert::TryInto;
     use core::mem;
     use pretty_assertions::assert_eq;
@@ -220,7 +465,7 @@ mod tests {
         assert_eq!(resp.emit(&mut frame), Ok(()));
         assert_eq!(frame.check_len(), Ok(()));
         assert_eq!(frame.check_preamble(), Ok(()));
-        assert_eq!(frame.op(), GetSetOp::Set);
+        assert_eq!(frame.op(), Ok(GetSetOp::Set));
         assert_eq!(frame.payload_size(), resp.payload_wire_size() as u16);
         let packet = ParameterListPacket::new_checked(frame.payload_mut()).unwrap();
         assert_eq!(packet.check_len(), Ok(()));
@@ -236,14 +481,14 @@ mod tests {
         assert_eq!(f.node_id(), 0x01);
         assert_eq!(f.flags(), 0);
         assert_eq!(f.version(), 1);
-        assert_eq!(f.op(), GetSetOp::Get);
-        assert_eq!(f.payload_type(), GetSetPayloadType::ParameterListPacket);
+        assert_eq!(f.op(), Ok(GetSetOp::Get));
+        assert_eq!(f.payload_type(), Ok(GetSetPayloadType::ParameterListPacket));
         assert_eq!(f.payload_size(), PAYLOAD_BYTES.len().try_into().unwrap());
         assert_eq!(f.payload(), &PAYLOAD_BYTES[..]);
         let p = ParameterListPacket::new_checked(f.payload()).unwrap();
         assert_eq!(p.count(), PARAMS.len().try_into().unwrap());
-        for index in 0..PARAMS.len() {
-            assert_eq!(p.parameter_at(index), Ok(PARAMS[index]));
+        for (index, param) in PARAMS.iter().enumerate() {
+            assert_eq!(p.parameter_at(index), Ok(*param));
         }
 
         let resp = Response::parse(&f).unwrap();
@@ -252,4 +497,273 @@ mod tests {
             assert_eq!(*p, PARAMS[index]);
         }
     }
+
+    #[test]
+    fn set_results_round_trip() {
+        let mut resp = Response::new_with_payload_type(
+            0,
+            0,
+            GetSetOp::Set,
+            GetSetPayloadType::SetResultListPacket,
+        );
+        let results = [
+            SetResult::new(ParameterId::new(0x0A), SetStatus::Ok),
+            SetResult::new(ParameterId::new(0x0B), SetStatus::NotFound),
+            SetResult::new(ParameterId::new(0x0C), SetStatus::TypeMismatch),
+        ];
+        for r in results.iter() {
+            assert_eq!(resp.push_result(*r), Ok(()));
+        }
+        assert_eq!(
+            resp.wire_size(),
+            GetSetFrame::<&[u8]>::header_len()
+                + SetResultListPacket::<&[u8]>::header_len()
+                + 3 * (mem::size_of::<u32>() + mem::size_of::<u8>())
+        );
+
+        let mut bytes = [0xFF; 64];
+        let size = resp.wire_size();
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..size]);
+        assert_eq!(resp.emit(&mut frame), Ok(()));
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(
+            frame.payload_type(),
+            Ok(GetSetPayloadType::SetResultListPacket)
+        );
+        let parsed = Response::parse(&frame).unwrap();
+        assert_eq!(parsed.results(), &results[..]);
+        assert_eq!(parsed, resp);
+    }
+
+    #[test]
+    fn compare_and_set_round_trip() {
+        let mut resp = Response::new_with_payload_type(
+            0,
+            0,
+            GetSetOp::CompareAndSet,
+            GetSetPayloadType::CompareAndSetResultPacket,
+        );
+        let results = [
+            SetResult::new(ParameterId::new(0x0A), SetStatus::Ok),
+            SetResult::new(ParameterId::new(0x0B), SetStatus::Mismatch),
+        ];
+        for r in results.iter() {
+            assert_eq!(resp.push_result(*r), Ok(()));
+        }
+        let current = Parameter::new_with_value(
+            ParameterId::new(0x0B),
+            ParameterFlags(0),
+            ParameterValue::U8(3),
+        );
+        assert_eq!(resp.push(current), Ok(()));
+        assert_eq!(
+            resp.wire_size(),
+            GetSetFrame::<&[u8]>::header_len()
+                + SetResultListPacket::<&[u8]>::header_len()
+                + 2 * (mem::size_of::<u32>() + mem::size_of::<u8>())
+                + ParameterListPacket::<&[u8]>::header_len()
+                + current.wire_size()
+        );
+
+        let mut bytes = [0xFF; 64];
+        let size = resp.wire_size();
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..size]);
+        assert_eq!(resp.emit(&mut frame), Ok(()));
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        let parsed = Response::parse(&frame).unwrap();
+        assert_eq!(parsed.results(), &results[..]);
+        assert_eq!(parsed.parameters(), &[current]);
+        assert_eq!(parsed, resp);
+
+        let frame = GetSetFrame::new_unchecked(&bytes[..size - 1]);
+        assert!(Response::parse(&frame).is_err());
+    }
+
+    #[test]
+    fn changed_round_trip() {
+        let mut resp = Response::new_with_payload_type(
+            0,
+            0,
+            GetSetOp::GetChanged,
+            GetSetPayloadType::ChangedListPacket,
+        );
+        resp.set_node_time_ms(5000);
+        let mut changed = Parameter::new_with_value(
+            ParameterId::new(0x0B),
+            ParameterFlags(0),
+            ParameterValue::U8(3),
+        );
+        changed.set_local_time_ms(4000);
+        assert_eq!(resp.push(changed), Ok(()));
+        assert_eq!(
+            resp.wire_size(),
+            GetSetFrame::<&[u8]>::header_len()
+                + mem::size_of::<u64>()
+                + ParameterListPacket::<&[u8]>::header_len()
+                + changed.wire_size()
+        );
+
+        let mut bytes = [0xFF; 64];
+        let size = resp.wire_size();
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..size]);
+        assert_eq!(resp.emit(&mut frame), Ok(()));
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        let parsed = Response::parse(&frame).unwrap();
+        assert_eq!(parsed.node_time_ms(), 5000);
+        assert_eq!(parsed.parameters(), &[changed]);
+        assert_eq!(parsed, resp);
+
+        let frame = GetSetFrame::new_unchecked(&bytes[..size - 1]);
+        assert!(Response::parse(&frame).is_err());
+    }
+
+    #[test]
+    fn ping_round_trip() {
+        let mut resp =
+            Response::new_with_payload_type(0, 0, GetSetOp::Ping, GetSetPayloadType::PingPacket);
+        resp.set_uptime(300);
+        resp.set_node_time_ms(300_250);
+        assert_eq!(resp.set_ping_data(&[0xAA; 8]), Ok(()));
+
+        let mut bytes = [0xFF; 64];
+        let size = resp.wire_size();
+        assert_eq!(size, GetSetFrame::<&[u8]>::header_len() + 13 + 8);
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..size]);
+        assert_eq!(resp.emit(&mut frame), Ok(()));
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        let parsed = Response::parse(&frame).unwrap();
+        assert_eq!(parsed.uptime(), 300);
+        assert_eq!(parsed.node_time_ms(), 300_250);
+        assert_eq!(parsed.ping_data(), &[0xAA; 8][..]);
+        assert_eq!(parsed, resp);
+    }
+
+    #[test]
+    fn atomic_committed() {
+        let mut resp = Response::new_with_payload_type(
+            0,
+            0,
+            GetSetOp::Set,
+            GetSetPayloadType::SetResultListPacket,
+        );
+        resp.push_result(SetResult::new(ParameterId::new(0x0A), SetStatus::Ok))
+            .unwrap();
+        assert_eq!(resp.committed(), None);
+
+        resp.set_flags(GETSET_FLAG_ATOMIC);
+        let mut bytes = [0xFF; 64];
+        let size = resp.wire_size();
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..size]);
+        assert_eq!(resp.emit(&mut frame), Ok(()));
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(Response::parse(&frame).unwrap().committed(), Some(true));
+
+        resp.push_result(SetResult::new(
+            ParameterId::new(0x0B),
+            SetStatus::OutOfRange,
+        ))
+        .unwrap();
+        resp.push_result(SetResult::new(
+            ParameterId::new(0x0C),
+            SetStatus::RolledBack,
+        ))
+        .unwrap();
+        assert_eq!(resp.committed(), Some(false));
+    }
+
+    #[test]
+    fn subscribe_round_trip() {
+        // Pushed updates are v2 Subscribe responses with sequence 0
+        let mut resp = Response::new(0, 0, GetSetOp::Subscribe);
+        resp.set_version(GETSET_VERSION_2);
+        assert_eq!(resp.push(PARAMS[0]), Ok(()));
+        assert_eq!(resp.push(PARAMS[1]), Ok(()));
+
+        let mut bytes = [0xFF; 64];
+        let size = resp.wire_size();
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..size]);
+        assert_eq!(resp.emit(&mut frame), Ok(()));
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(frame.op(), Ok(GetSetOp::Subscribe));
+        assert_eq!(frame.sequence(), 0);
+        let parsed = Response::parse(&frame).unwrap();
+        assert_eq!(parsed.parameters(), &PARAMS[..2]);
+        assert_eq!(parsed, resp);
+
+        let frame = GetSetFrame::new_unchecked(&bytes[..size - 1]);
+        assert_eq!(Response::parse(&frame), Err(Error::WireTruncated));
+
+        // The count claims more parameters than the payload holds
+        bytes[21] += 1;
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert!(Response::parse(&frame).is_err());
+    }
+
+    #[test]
+    fn unsubscribe_round_trip() {
+        let mut resp = Response::new(0, 0, GetSetOp::Unsubscribe);
+        resp.set_version(GETSET_VERSION_2);
+        resp.set_sequence(7);
+
+        let mut bytes = [0xFF; 32];
+        let size = resp.wire_size();
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..size]);
+        assert_eq!(resp.emit(&mut frame), Ok(()));
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        let parsed = Response::parse(&frame).unwrap();
+        assert!(parsed.parameters().is_empty());
+        assert_eq!(parsed, resp);
+
+        let frame = GetSetFrame::new_unchecked(&bytes[..size - 1]);
+        assert_eq!(Response::parse(&frame), Err(Error::WireTruncated));
+    }
+
+    #[test]
+    fn invoke_round_trip() {
+        // A single parameter holding the command's result
+        let mut resp = Response::new(0, 0, GetSetOp::Invoke);
+        resp.set_version(GETSET_VERSION_2);
+        resp.set_sequence(3);
+        let result = Parameter::new_with_value(
+            ParameterId::new(402),
+            ParameterFlags(0),
+            ParameterValue::U32(0b10),
+        );
+        assert_eq!(resp.push(result), Ok(()));
+
+        let mut bytes = [0xFF; 64];
+        let size = resp.wire_size();
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..size]);
+        assert_eq!(resp.emit(&mut frame), Ok(()));
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(frame.op(), Ok(GetSetOp::Invoke));
+        let parsed = Response::parse(&frame).unwrap();
+        assert_eq!(parsed.parameters(), &[result][..]);
+        assert_eq!(parsed, resp);
+
+        let frame = GetSetFrame::new_unchecked(&bytes[..size - 1]);
+        assert_eq!(Response::parse(&frame), Err(Error::WireTruncated));
+    }
+
+    #[test]
+    fn sequence_round_trip() {
+        let mut resp = Response::new(0, 0, GetSetOp::ListAll);
+        resp.set_sequence(0xABCD);
+        let mut bytes = [0xFF; 32];
+
+        // v1 frames have no room for the sequence number
+        let size = resp.wire_size();
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..size]);
+        assert_eq!(resp.emit(&mut frame), Ok(()));
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(Response::parse(&frame).unwrap().sequence(), 0);
+
+        resp.set_version(GETSET_VERSION_2);
+        let size = resp.wire_size();
+        let mut frame = GetSetFrame::new_unchecked(&mut bytes[..size]);
+        assert_eq!(resp.emit(&mut frame), Ok(()));
+        let frame = GetSetFrame::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(frame.sequence(), 0xABCD);
+        assert_eq!(Response::parse(&frame), Ok(resp));
+    }
 }
@@ -0,0 +1,148 @@
+use crate::{Error, ParameterId};
+use core::convert::TryFrom;
+use core::fmt;
+
+/// Outcome of setting a single parameter
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Status {
+    /// The value was applied as given
+    Ok = 0,
+    /// No parameter with the ID exists
+    NotFound = 1,
+    /// The parameter is read-only or constant
+    PermissionDenied = 2,
+    /// The value type doesn't match the parameter's type
+    TypeMismatch = 3,
+    /// The value is outside the parameter's valid range
+    OutOfRange = 4,
+    /// The value was applied after being clamped to the valid range
+    Clamped = 5,
+    /// The value is valid but wasn't applied, another parameter in the
+    /// atomic Set failed
+    RolledBack = 6,
+    /// The current value doesn't match the expected value of a
+    /// CompareAndSet, it's returned in the response
+    Mismatch = 7,
+    /// The node failed to apply the value for a reason unrelated to it
+    Internal = 8,
+}
+
+impl Default for Status {
+    fn default() -> Self {
+        Status::Ok
+    }
+}
+
+impl Status {
+    pub(crate) fn as_u8(&self) -> u8 {
+        *self as u8
+    }
+
+    /// The value was applied, possibly clamped
+    pub fn is_success(&self) -> bool {
+        match self {
+            Status::Ok | Status::Clamped => true,
+            _ => false,
+        }
+    }
+
+    pub fn wire_size(&self) -> usize {
+        1
+    }
+}
+
+impl TryFrom<u8> for Status {
+    type Error = Error;
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            0 => Ok(Status::Ok),
+            1 => Ok(Status::NotFound),
+            2 => Ok(Status::PermissionDenied),
+            3 => Ok(Status::TypeMismatch),
+            4 => Ok(Status::OutOfRange),
+            5 => Ok(Status::Clamped),
+            6 => Ok(Status::RolledBack),
+            7 => Ok(Status::Mismatch),
+            8 => Ok(Status::Internal),
+            _ => Err(Error::WireInvalidSetStatus),
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", *self)
+    }
+}
+
+/// Status of a Set on the parameter with the given ID
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct SetResult {
+    id: ParameterId,
+    status: Status,
+}
+
+impl SetResult {
+    pub const fn new(id: ParameterId, status: Status) -> Self {
+        SetResult { id, status }
+    }
+
+    pub fn id(&self) -> ParameterId {
+        self.id
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn wire_size(&self) -> usize {
+        self.id.wire_size() + self.status.wire_size()
+    }
+}
+
+impl fmt::Display for SetResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SetResult {{ id: {} status: {} }}",
+            self.id(),
+            self.status()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn status_wire_values() {
+        let statuses = [
+            Status::Ok,
+            Status::NotFound,
+            Status::PermissionDenied,
+            Status::TypeMismatch,
+            Status::OutOfRange,
+            Status::Clamped,
+            Status::RolledBack,
+            Status::Mismatch,
+            Status::Internal,
+        ];
+        for s in statuses.iter() {
+            assert_eq!(Status::try_from(s.as_u8()), Ok(*s));
+        }
+        assert_eq!(Status::try_from(9), Err(Error::WireInvalidSetStatus));
+    }
+
+    #[test]
+    fn success() {
+        assert!(Status::Ok.is_success());
+        assert!(Status::Clamped.is_success());
+        assert!(!Status::NotFound.is_success());
+        assert!(!Status::OutOfRange.is_success());
+        assert!(!Status::RolledBack.is_success());
+        assert!(!Status::Internal.is_success());
+    }
+}
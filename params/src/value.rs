@@ -1,7 +1,8 @@
 // TODO - refactor this impl
-// reduce the types, add a str/string type?
+// reduce the types?
 
-use crate::Error;
+use crate::{Error, ParameterValueArray, ParameterValueBytes, ParameterValueStr};
+use core::convert::TryFrom;
 use core::fmt;
 use core::str;
 
@@ -13,11 +14,17 @@ pub enum Value {
     Bool(bool),
     U8(u8),
     I8(i8),
+    U16(u16),
+    I16(i16),
     U32(u32),
     I32(i32),
     U64(u64),
     I64(i64),
     F32(f32),
+    F64(f64),
+    Str(ParameterValueStr),
+    Bytes(ParameterValueBytes),
+    Array(ParameterValueArray),
 }
 
 impl Default for Value {
@@ -31,61 +38,210 @@ impl Value {
         TypeId::from(*self)
     }
 
-    pub fn as_bool(&self) -> bool {
+    /// Size of the value field on the wire
+    pub fn wire_size(&self) -> usize {
         match *self {
-            Value::Bool(v) => v,
-            _ => panic!("Value type mismatch"),
+            Value::Str(v) => v.wire_size(),
+            Value::Bytes(v) => v.wire_size(),
+            Value::Array(v) => v.wire_size(),
+            _ => self.type_id().wire_size(),
         }
     }
 
-    pub fn as_u8(&self) -> u8 {
+    pub fn try_as_bool(&self) -> Result<bool, Error> {
         match *self {
-            Value::U8(v) => v,
-            _ => panic!("Value type mismatch"),
+            Value::Bool(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
         }
     }
 
-    pub fn as_i8(&self) -> i8 {
+    pub fn try_as_u8(&self) -> Result<u8, Error> {
         match *self {
-            Value::I8(v) => v,
-            _ => panic!("Value type mismatch"),
+            Value::U8(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
         }
     }
 
-    pub fn as_u32(&self) -> u32 {
+    pub fn try_as_i8(&self) -> Result<i8, Error> {
         match *self {
-            Value::U32(v) => v,
-            _ => panic!("Value type mismatch"),
+            Value::I8(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
         }
     }
 
-    pub fn as_i32(&self) -> i32 {
+    pub fn try_as_u16(&self) -> Result<u16, Error> {
         match *self {
-            Value::I32(v) => v,
-            _ => panic!("Value type mismatch"),
+            Value::U16(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
         }
     }
 
-    pub fn as_u64(&self) -> u64 {
+    pub fn try_as_i16(&self) -> Result<i16, Error> {
         match *self {
-            Value::U64(v) => v,
-            _ => panic!("Value type mismatch"),
+            Value::I16(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
         }
     }
 
-    pub fn as_i64(&self) -> i64 {
+    pub fn try_as_u32(&self) -> Result<u32, Error> {
         match *self {
-            Value::I64(v) => v,
-            _ => panic!("Value type mismatch"),
+            Value::U32(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
         }
     }
 
-    pub fn as_f32(&self) -> f32 {
+    pub fn try_as_i32(&self) -> Result<i32, Error> {
+        match *self {
+            Value::I32(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
+        }
+    }
+
+    pub fn try_as_u64(&self) -> Result<u64, Error> {
+        match *self {
+            Value::U64(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
+        }
+    }
+
+    pub fn try_as_i64(&self) -> Result<i64, Error> {
+        match *self {
+            Value::I64(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
+        }
+    }
+
+    pub fn try_as_f32(&self) -> Result<f32, Error> {
+        match *self {
+            Value::F32(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
+        }
+    }
+
+    pub fn try_as_f64(&self) -> Result<f64, Error> {
+        match *self {
+            Value::F64(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
+        }
+    }
+
+    pub fn try_as_str(&self) -> Result<&str, Error> {
+        match self {
+            Value::Str(v) => Ok(v.as_str()),
+            _ => Err(Error::ValueTypeMismatch),
+        }
+    }
+
+    pub fn try_as_bytes(&self) -> Result<&[u8], Error> {
+        match self {
+            Value::Bytes(v) => Ok(v.as_slice()),
+            _ => Err(Error::ValueTypeMismatch),
+        }
+    }
+
+    pub fn try_as_array(&self) -> Result<&ParameterValueArray, Error> {
+        match self {
+            Value::Array(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
+        }
+    }
+
+    /// Lossless widening of any integer or float type that fits in an f64
+    pub fn to_f64(&self) -> Result<f64, Error> {
         match *self {
-            Value::F32(v) => v,
-            _ => panic!("Value type mismatch"),
+            Value::U8(v) => Ok(v.into()),
+            Value::I8(v) => Ok(v.into()),
+            Value::U16(v) => Ok(v.into()),
+            Value::I16(v) => Ok(v.into()),
+            Value::U32(v) => Ok(v.into()),
+            Value::I32(v) => Ok(v.into()),
+            Value::F32(v) => Ok(v.into()),
+            Value::F64(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
         }
     }
+
+    /// Lossless widening of any signed or unsigned integer type that fits in an i64
+    pub fn to_i64(&self) -> Result<i64, Error> {
+        match *self {
+            Value::U8(v) => Ok(v.into()),
+            Value::I8(v) => Ok(v.into()),
+            Value::U16(v) => Ok(v.into()),
+            Value::I16(v) => Ok(v.into()),
+            Value::U32(v) => Ok(v.into()),
+            Value::I32(v) => Ok(v.into()),
+            Value::I64(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
+        }
+    }
+
+    /// Lossless widening of any unsigned integer type
+    pub fn to_u64(&self) -> Result<u64, Error> {
+        match *self {
+            Value::U8(v) => Ok(v.into()),
+            Value::U16(v) => Ok(v.into()),
+            Value::U32(v) => Ok(v.into()),
+            Value::U64(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
+        }
+    }
+
+    // The as_* accessors panic on a type mismatch, see the try_as_* variants
+    pub fn as_bool(&self) -> bool {
+        self.try_as_bool().expect("Value type mismatch")
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        self.try_as_u8().expect("Value type mismatch")
+    }
+
+    pub fn as_i8(&self) -> i8 {
+        self.try_as_i8().expect("Value type mismatch")
+    }
+
+    pub fn as_u16(&self) -> u16 {
+        self.try_as_u16().expect("Value type mismatch")
+    }
+
+    pub fn as_i16(&self) -> i16 {
+        self.try_as_i16().expect("Value type mismatch")
+    }
+
+    pub fn as_u32(&self) -> u32 {
+        self.try_as_u32().expect("Value type mismatch")
+    }
+
+    pub fn as_i32(&self) -> i32 {
+        self.try_as_i32().expect("Value type mismatch")
+    }
+
+    pub fn as_u64(&self) -> u64 {
+        self.try_as_u64().expect("Value type mismatch")
+    }
+
+    pub fn as_i64(&self) -> i64 {
+        self.try_as_i64().expect("Value type mismatch")
+    }
+
+    pub fn as_f32(&self) -> f32 {
+        self.try_as_f32().expect("Value type mismatch")
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        self.try_as_f64().expect("Value type mismatch")
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.try_as_str().expect("Value type mismatch")
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.try_as_bytes().expect("Value type mismatch")
+    }
+
+    pub fn as_array(&self) -> &ParameterValueArray {
+        self.try_as_array().expect("Value type mismatch")
+    }
 }
 
 impl From<bool> for Value {
@@ -106,6 +262,18 @@ impl From<i8> for Value {
     }
 }
 
+impl From<u16> for Value {
+    fn from(v: u16) -> Self {
+        Value::U16(v)
+    }
+}
+
+impl From<i16> for Value {
+    fn from(v: i16) -> Self {
+        Value::I16(v)
+    }
+}
+
 impl From<u32> for Value {
     fn from(v: u32) -> Self {
         Value::U32(v)
@@ -136,6 +304,87 @@ impl From<f32> for Value {
     }
 }
 
+impl From<f64> for Value {
+    fn from(v: f64) -> Self {
+        Value::F64(v)
+    }
+}
+
+impl From<ParameterValueStr> for Value {
+    fn from(v: ParameterValueStr) -> Self {
+        Value::Str(v)
+    }
+}
+
+impl From<ParameterValueBytes> for Value {
+    fn from(v: ParameterValueBytes) -> Self {
+        Value::Bytes(v)
+    }
+}
+
+impl From<ParameterValueArray> for Value {
+    fn from(v: ParameterValueArray) -> Self {
+        Value::Array(v)
+    }
+}
+
+macro_rules! impl_try_from_value {
+    ($t:ty, $f:ident) => {
+        impl TryFrom<Value> for $t {
+            type Error = Error;
+
+            fn try_from(v: Value) -> Result<Self, Self::Error> {
+                v.$f()
+            }
+        }
+    };
+}
+
+impl_try_from_value!(bool, try_as_bool);
+impl_try_from_value!(u8, try_as_u8);
+impl_try_from_value!(i8, try_as_i8);
+impl_try_from_value!(u16, try_as_u16);
+impl_try_from_value!(i16, try_as_i16);
+impl_try_from_value!(u32, try_as_u32);
+impl_try_from_value!(i32, try_as_i32);
+impl_try_from_value!(u64, try_as_u64);
+impl_try_from_value!(i64, try_as_i64);
+impl_try_from_value!(f32, try_as_f32);
+impl_try_from_value!(f64, try_as_f64);
+
+impl TryFrom<Value> for ParameterValueStr {
+    type Error = Error;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        match v {
+            Value::Str(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
+        }
+    }
+}
+
+impl TryFrom<Value> for ParameterValueBytes {
+    type Error = Error;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        match v {
+            Value::Bytes(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
+        }
+    }
+}
+
+impl TryFrom<Value> for ParameterValueArray {
+    type Error = Error;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        match v {
+            Value::Array(v) => Ok(v),
+            _ => Err(Error::ValueTypeMismatch),
+        }
+    }
+}
+
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -144,11 +393,17 @@ impl fmt::Display for Value {
             Value::Bool(v) => write!(f, "Bool({})", v),
             Value::U8(v) => write!(f, "U8({})", v),
             Value::I8(v) => write!(f, "I8({})", v),
+            Value::U16(v) => write!(f, "U16({})", v),
+            Value::I16(v) => write!(f, "I16({})", v),
             Value::U32(v) => write!(f, "U32({})", v),
             Value::I32(v) => write!(f, "I32({})", v),
             Value::U64(v) => write!(f, "U64({})", v),
             Value::I64(v) => write!(f, "I64({})", v),
             Value::F32(v) => write!(f, "F32({})", v),
+            Value::F64(v) => write!(f, "F64({})", v),
+            Value::Str(v) => write!(f, "Str(\"{}\")", v),
+            Value::Bytes(v) => write!(f, "Bytes({})", v),
+            Value::Array(v) => write!(f, "Array({})", v),
         }
     }
 }
@@ -165,6 +420,12 @@ pub enum TypeId {
     U64 = 7,
     I64 = 8,
     F32 = 9,
+    Str = 10,
+    Bytes = 11,
+    U16 = 12,
+    I16 = 13,
+    F64 = 14,
+    Array = 15,
 }
 
 impl str::FromStr for TypeId {
@@ -191,6 +452,18 @@ impl str::FromStr for TypeId {
             Ok(TypeId::I64)
         } else if s == "F32" || s == "f32" {
             Ok(TypeId::F32)
+        } else if s == "Str" || s == "str" {
+            Ok(TypeId::Str)
+        } else if s == "Bytes" || s == "bytes" {
+            Ok(TypeId::Bytes)
+        } else if s == "U16" || s == "u16" {
+            Ok(TypeId::U16)
+        } else if s == "I16" || s == "i16" {
+            Ok(TypeId::I16)
+        } else if s == "F64" || s == "f64" {
+            Ok(TypeId::F64)
+        } else if s == "Array" || s == "array" {
+            Ok(TypeId::Array)
         } else {
             Err(Error::ParseValue)
         }
@@ -202,7 +475,9 @@ impl TypeId {
         *self as u8
     }
 
-    // Size of the value field on the ire
+    /// Size of the value field on the wire, for variable length
+    /// types this is the size of the length prefix (element type and
+    /// count for arrays)
     pub fn wire_size(&self) -> usize {
         match *self {
             TypeId::None => 0,
@@ -215,24 +490,38 @@ impl TypeId {
             TypeId::U64 => 8,
             TypeId::I64 => 8,
             TypeId::F32 => 4,
+            TypeId::Str => 1,
+            TypeId::Bytes => 1,
+            TypeId::U16 => 2,
+            TypeId::I16 => 2,
+            TypeId::F64 => 8,
+            TypeId::Array => 2,
         }
     }
 }
 
-impl From<u8> for TypeId {
-    fn from(v: u8) -> TypeId {
+impl TryFrom<u8> for TypeId {
+    type Error = Error;
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
         match v {
-            0 => TypeId::None,
-            1 => TypeId::Notification,
-            2 => TypeId::Bool,
-            3 => TypeId::U8,
-            4 => TypeId::I8,
-            5 => TypeId::U32,
-            6 => TypeId::I32,
-            7 => TypeId::U64,
-            8 => TypeId::I64,
-            9 => TypeId::F32,
-            _ => TypeId::None,
+            0 => Ok(TypeId::None),
+            1 => Ok(TypeId::Notification),
+            2 => Ok(TypeId::Bool),
+            3 => Ok(TypeId::U8),
+            4 => Ok(TypeId::I8),
+            5 => Ok(TypeId::U32),
+            6 => Ok(TypeId::I32),
+            7 => Ok(TypeId::U64),
+            8 => Ok(TypeId::I64),
+            9 => Ok(TypeId::F32),
+            10 => Ok(TypeId::Str),
+            11 => Ok(TypeId::Bytes),
+            12 => Ok(TypeId::U16),
+            13 => Ok(TypeId::I16),
+            14 => Ok(TypeId::F64),
+            15 => Ok(TypeId::Array),
+            _ => Err(Error::WireInvalidValueType),
         }
     }
 }
@@ -245,11 +534,17 @@ impl From<Value> for TypeId {
             Value::Bool(_) => TypeId::Bool,
             Value::U8(_) => TypeId::U8,
             Value::I8(_) => TypeId::I8,
+            Value::U16(_) => TypeId::U16,
+            Value::I16(_) => TypeId::I16,
             Value::U32(_) => TypeId::U32,
             Value::I32(_) => TypeId::I32,
             Value::U64(_) => TypeId::U64,
             Value::I64(_) => TypeId::I64,
             Value::F32(_) => TypeId::F32,
+            Value::F64(_) => TypeId::F64,
+            Value::Str(_) => TypeId::Str,
+            Value::Bytes(_) => TypeId::Bytes,
+            Value::Array(_) => TypeId::Array,
         }
     }
 }
@@ -259,6 +554,7 @@ mod tests {
     use super::*;
     use approx::*;
     use core::mem;
+    use core::str::FromStr;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -280,6 +576,14 @@ mod tests {
             TypeId::from(Value::I8(-123)).wire_size(),
             mem::size_of::<i8>()
         );
+        assert_eq!(
+            TypeId::from(Value::U16(123)).wire_size(),
+            mem::size_of::<u16>()
+        );
+        assert_eq!(
+            TypeId::from(Value::I16(-123)).wire_size(),
+            mem::size_of::<i16>()
+        );
         assert_eq!(
             TypeId::from(Value::U32(123)).wire_size(),
             mem::size_of::<u32>()
@@ -300,6 +604,22 @@ mod tests {
             TypeId::from(Value::F32(-1.234)).wire_size(),
             mem::size_of::<f32>()
         );
+        assert_eq!(
+            TypeId::from(Value::F64(-1.234)).wire_size(),
+            mem::size_of::<f64>()
+        );
+        assert_eq!(TypeId::from(Value::Str(Default::default())).wire_size(), 1);
+        let val = Value::Str(ParameterValueStr::from_str("abcd").unwrap());
+        assert_eq!(val.wire_size(), 1 + 4);
+        assert_eq!(
+            TypeId::from(Value::Bytes(Default::default())).wire_size(),
+            1
+        );
+        let val = Value::Bytes(ParameterValueBytes::from_slice(&[1, 2, 3]).unwrap());
+        assert_eq!(val.wire_size(), 1 + 3);
+        let val = Value::Array(ParameterValueArray::parse(TypeId::F32, "1, 2, 3").unwrap());
+        assert_eq!(val.type_id().wire_size(), 2);
+        assert_eq!(val.wire_size(), 2 + 3 * 4);
     }
 
     #[test]
@@ -313,6 +633,12 @@ mod tests {
         let val = Value::I8(-123);
         assert_eq!(-123, val.as_i8());
 
+        let val = Value::U16(12345);
+        assert_eq!(12345, val.as_u16());
+
+        let val = Value::I16(-123);
+        assert_eq!(-123, val.as_i16());
+
         let val = Value::U32(12345);
         assert_eq!(12345, val.as_u32());
 
@@ -327,48 +653,134 @@ mod tests {
 
         let val = Value::F32(-1.23);
         assert_relative_eq!(-1.23, val.as_f32());
+
+        let val = Value::F64(-1.23);
+        assert_relative_eq!(-1.23, val.as_f64());
+
+        let val = Value::Str(ParameterValueStr::from_str("abcd").unwrap());
+        assert_eq!("abcd", val.as_str());
+
+        let val = Value::Bytes(ParameterValueBytes::from_slice(&[1, 2, 3]).unwrap());
+        assert_eq!(&[1, 2, 3], val.as_bytes());
+
+        let arr = ParameterValueArray::parse(TypeId::U8, "1, 2").unwrap();
+        let val = Value::Array(arr);
+        assert_eq!(&arr, val.as_array());
+    }
+
+    #[test]
+    fn try_inner_types() {
+        let val = Value::U16(12345);
+        assert_eq!(val.try_as_u16(), Ok(12345));
+        assert_eq!(val.try_as_u32(), Err(Error::ValueTypeMismatch));
+        assert_eq!(val.try_as_str(), Err(Error::ValueTypeMismatch));
+        assert_eq!(u16::try_from(val), Ok(12345));
+        assert_eq!(bool::try_from(val), Err(Error::ValueTypeMismatch));
+
+        let val = Value::Bool(true);
+        assert_eq!(val.try_as_bool(), Ok(true));
+        assert_eq!(bool::try_from(val), Ok(true));
+        assert_eq!(val.try_as_u8(), Err(Error::ValueTypeMismatch));
+
+        let s = ParameterValueStr::from_str("abcd").unwrap();
+        let val = Value::Str(s);
+        assert_eq!(val.try_as_str(), Ok("abcd"));
+        assert_eq!(ParameterValueStr::try_from(val), Ok(s));
+        assert_eq!(val.try_as_bytes(), Err(Error::ValueTypeMismatch));
+
+        let b = ParameterValueBytes::from_slice(&[1, 2]).unwrap();
+        assert_eq!(Value::Bytes(b).try_as_bytes(), Ok(&[1_u8, 2][..]));
+        assert_eq!(ParameterValueBytes::try_from(Value::Bytes(b)), Ok(b));
+
+        assert_eq!(Value::None.try_as_array(), Err(Error::ValueTypeMismatch));
+    }
+
+    #[test]
+    fn widening() {
+        assert_eq!(Value::U8(200).to_i64(), Ok(200));
+        assert_eq!(Value::I16(-123).to_i64(), Ok(-123));
+        assert_eq!(Value::U32(u32::MAX).to_i64(), Ok(i64::from(u32::MAX)));
+        assert_eq!(Value::U64(1).to_i64(), Err(Error::ValueTypeMismatch));
+        assert_eq!(Value::F32(1.0).to_i64(), Err(Error::ValueTypeMismatch));
+
+        assert_eq!(Value::U16(7).to_u64(), Ok(7));
+        assert_eq!(Value::I8(-1).to_u64(), Err(Error::ValueTypeMismatch));
+
+        assert_relative_eq!(Value::F32(-1.5).to_f64().unwrap(), -1.5);
+        assert_relative_eq!(Value::I32(-1234).to_f64().unwrap(), -1234.0);
+        assert_eq!(Value::I64(1).to_f64(), Err(Error::ValueTypeMismatch));
+        assert_eq!(Value::Bool(true).to_f64(), Err(Error::ValueTypeMismatch));
     }
 
     #[test]
     fn type_ids() {
         let val = Value::None;
         assert_eq!(val.type_id(), TypeId::None);
-        assert_eq!(TypeId::from(val.type_id().as_u8()), TypeId::None);
+        assert_eq!(TypeId::try_from(val.type_id().as_u8()), Ok(TypeId::None));
 
         let val = Value::Notification;
         assert_eq!(val.type_id(), TypeId::Notification);
-        assert_eq!(TypeId::from(val.type_id().as_u8()), TypeId::Notification);
+        assert_eq!(
+            TypeId::try_from(val.type_id().as_u8()),
+            Ok(TypeId::Notification)
+        );
 
         let val = Value::Bool(true);
         assert_eq!(val.type_id(), TypeId::Bool);
-        assert_eq!(TypeId::from(val.type_id().as_u8()), TypeId::Bool);
+        assert_eq!(TypeId::try_from(val.type_id().as_u8()), Ok(TypeId::Bool));
 
         let val = Value::U8(123);
         assert_eq!(val.type_id(), TypeId::U8);
-        assert_eq!(TypeId::from(val.type_id().as_u8()), TypeId::U8);
+        assert_eq!(TypeId::try_from(val.type_id().as_u8()), Ok(TypeId::U8));
 
         let val = Value::I8(-123);
         assert_eq!(val.type_id(), TypeId::I8);
-        assert_eq!(TypeId::from(val.type_id().as_u8()), TypeId::I8);
+        assert_eq!(TypeId::try_from(val.type_id().as_u8()), Ok(TypeId::I8));
+
+        let val = Value::U16(12345);
+        assert_eq!(val.type_id(), TypeId::U16);
+        assert_eq!(TypeId::try_from(val.type_id().as_u8()), Ok(TypeId::U16));
+
+        let val = Value::I16(-123);
+        assert_eq!(val.type_id(), TypeId::I16);
+        assert_eq!(TypeId::try_from(val.type_id().as_u8()), Ok(TypeId::I16));
 
         let val = Value::U32(12345);
         assert_eq!(val.type_id(), TypeId::U32);
-        assert_eq!(TypeId::from(val.type_id().as_u8()), TypeId::U32);
+        assert_eq!(TypeId::try_from(val.type_id().as_u8()), Ok(TypeId::U32));
 
         let val = Value::I32(-123);
         assert_eq!(val.type_id(), TypeId::I32);
-        assert_eq!(TypeId::from(val.type_id().as_u8()), TypeId::I32);
+        assert_eq!(TypeId::try_from(val.type_id().as_u8()), Ok(TypeId::I32));
 
         let val = Value::U64(12345);
         assert_eq!(val.type_id(), TypeId::U64);
-        assert_eq!(TypeId::from(val.type_id().as_u8()), TypeId::U64);
+        assert_eq!(TypeId::try_from(val.type_id().as_u8()), Ok(TypeId::U64));
 
         let val = Value::I64(-123);
         assert_eq!(val.type_id(), TypeId::I64);
-        assert_eq!(TypeId::from(val.type_id().as_u8()), TypeId::I64);
+        assert_eq!(TypeId::try_from(val.type_id().as_u8()), Ok(TypeId::I64));
 
         let val = Value::F32(-1.23);
         assert_eq!(val.type_id(), TypeId::F32);
-        assert_eq!(TypeId::from(val.type_id().as_u8()), TypeId::F32);
+        assert_eq!(TypeId::try_from(val.type_id().as_u8()), Ok(TypeId::F32));
+
+        let val = Value::F64(-1.23);
+        assert_eq!(val.type_id(), TypeId::F64);
+        assert_eq!(TypeId::try_from(val.type_id().as_u8()), Ok(TypeId::F64));
+
+        let val = Value::Str(ParameterValueStr::from_str("abcd").unwrap());
+        assert_eq!(val.type_id(), TypeId::Str);
+        assert_eq!(TypeId::try_from(val.type_id().as_u8()), Ok(TypeId::Str));
+
+        let val = Value::Bytes(ParameterValueBytes::from_slice(&[1, 2, 3]).unwrap());
+        assert_eq!(val.type_id(), TypeId::Bytes);
+        assert_eq!(TypeId::try_from(val.type_id().as_u8()), Ok(TypeId::Bytes));
+
+        let val = Value::Array(ParameterValueArray::new(TypeId::U8).unwrap());
+        assert_eq!(val.type_id(), TypeId::Array);
+        assert_eq!(TypeId::try_from(val.type_id().as_u8()), Ok(TypeId::Array));
+
+        assert_eq!(TypeId::try_from(0xFF), Err(Error::WireInvalidValueType));
     }
 }
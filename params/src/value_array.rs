@@ -0,0 +1,364 @@
+use crate::{Error, ParameterValue, ParameterValueTypeId};
+use byteorder::{ByteOrder, LittleEndian};
+use core::cmp::Ordering;
+use core::fmt;
+use core::str::FromStr;
+
+/// Maximum number of bytes used to store the elements of an `Array`,
+/// the element capacity depends on the element type
+pub const MAX_ARRAY_WIRE_SIZE: usize = 32;
+
+/// Bounded, fixed-capacity array of numeric scalars
+///
+/// Elements are stored little endian, the same as their wire encoding
+#[derive(Copy, Clone)]
+pub struct Array {
+    elem: ParameterValueTypeId,
+    len: u8,
+    buf: [u8; MAX_ARRAY_WIRE_SIZE],
+}
+
+impl Array {
+    pub fn new(elem: ParameterValueTypeId) -> Result<Self, Error> {
+        if Self::is_element_type(elem) {
+            Ok(Array {
+                elem,
+                len: 0,
+                buf: [0; MAX_ARRAY_WIRE_SIZE],
+            })
+        } else {
+            Err(Error::ValueTypeMismatch)
+        }
+    }
+
+    /// Used by generated code, `buf` holds `len` little endian encoded
+    /// elements and the remainder must be zero
+    pub const fn from_raw_parts(
+        elem: ParameterValueTypeId,
+        len: u8,
+        buf: [u8; MAX_ARRAY_WIRE_SIZE],
+    ) -> Self {
+        Array { elem, len, buf }
+    }
+
+    /// Build from `len` little endian encoded elements
+    pub fn from_le_bytes(
+        elem: ParameterValueTypeId,
+        len: usize,
+        bytes: &[u8],
+    ) -> Result<Self, Error> {
+        let mut a = Array::new(elem)?;
+        if len > a.capacity() {
+            return Err(Error::Capacity);
+        }
+        let size = len * elem.wire_size();
+        let src = bytes.get(..size).ok_or(Error::WireTruncated)?;
+        a.buf[..size].copy_from_slice(src);
+        a.len = len as u8;
+        Ok(a)
+    }
+
+    /// Parse a comma separated list of elements, optionally surrounded by
+    /// brackets (`1, 2, 3`, `[1.5, 2.5]`)
+    pub fn parse(elem: ParameterValueTypeId, s: &str) -> Result<Self, Error> {
+        let mut a = Array::new(elem)?;
+        let s = s.trim();
+        let s = if s.len() >= 2 && s.starts_with('[') && s.ends_with(']') {
+            &s[1..s.len() - 1]
+        } else {
+            s
+        };
+        for e in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            a.push(parse_element(elem, e)?)?;
+        }
+        Ok(a)
+    }
+
+    /// Parse an array type description such as `f32[4]` into the element
+    /// type and length
+    pub fn parse_type(s: &str) -> Result<(ParameterValueTypeId, usize), Error> {
+        let s = s.trim();
+        let open = s.find('[').ok_or(Error::ParseValue)?;
+        if !s.ends_with(']') {
+            return Err(Error::ParseValue);
+        }
+        let len =
+            usize::from_str(s[open + 1..s.len() - 1].trim()).map_err(|_| Error::ParseValue)?;
+        let elem = ParameterValueTypeId::from_str(&s[..open])?;
+        let a = Array::new(elem)?;
+        if len > a.capacity() {
+            Err(Error::Capacity)
+        } else {
+            Ok((elem, len))
+        }
+    }
+
+    /// Fixed size numeric scalars can be array elements
+    pub fn is_element_type(elem: ParameterValueTypeId) -> bool {
+        match elem {
+            ParameterValueTypeId::Bool
+            | ParameterValueTypeId::U8
+            | ParameterValueTypeId::I8
+            | ParameterValueTypeId::U16
+            | ParameterValueTypeId::I16
+            | ParameterValueTypeId::U32
+            | ParameterValueTypeId::I32
+            | ParameterValueTypeId::U64
+            | ParameterValueTypeId::I64
+            | ParameterValueTypeId::F32
+            | ParameterValueTypeId::F64 => true,
+            _ => false,
+        }
+    }
+
+    pub fn element_type_id(&self) -> ParameterValueTypeId {
+        self.elem
+    }
+
+    pub fn capacity(&self) -> usize {
+        MAX_ARRAY_WIRE_SIZE / self.elem.wire_size()
+    }
+
+    pub fn len(&self) -> usize {
+        usize::from(self.len).min(self.capacity())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&mut self, value: ParameterValue) -> Result<(), Error> {
+        let index = self.len();
+        if value.type_id() != self.elem {
+            Err(Error::ValueTypeMismatch)
+        } else if index >= self.capacity() {
+            Err(Error::Capacity)
+        } else {
+            self.len += 1;
+            self.set(index, value)
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<ParameterValue> {
+        if index >= self.len() {
+            return None;
+        }
+        let size = self.elem.wire_size();
+        Some(read_element(
+            self.elem,
+            &self.buf[index * size..(index + 1) * size],
+        ))
+    }
+
+    pub fn set(&mut self, index: usize, value: ParameterValue) -> Result<(), Error> {
+        if value.type_id() != self.elem {
+            return Err(Error::ValueTypeMismatch);
+        }
+        if index >= self.len() {
+            return Err(Error::WireIndexOutOfBounds);
+        }
+        let size = self.elem.wire_size();
+        write_element(value, &mut self.buf[index * size..(index + 1) * size]);
+        Ok(())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ParameterValue> + '_ {
+        (0..self.len()).filter_map(move |index| self.get(index))
+    }
+
+    /// The little endian encoded elements
+    pub fn as_le_bytes(&self) -> &[u8] {
+        &self.buf[..self.len() * self.elem.wire_size()]
+    }
+
+    /// Element type and count followed by the elements
+    pub fn wire_size(&self) -> usize {
+        2 + self.as_le_bytes().len()
+    }
+}
+
+fn parse_element(elem: ParameterValueTypeId, s: &str) -> Result<ParameterValue, Error> {
+    fn p<T: FromStr>(s: &str) -> Result<T, Error> {
+        T::from_str(s).map_err(|_| Error::ParseValue)
+    }
+    Ok(match elem {
+        ParameterValueTypeId::Bool => ParameterValue::Bool(p(s)?),
+        ParameterValueTypeId::U8 => ParameterValue::U8(p(s)?),
+        ParameterValueTypeId::I8 => ParameterValue::I8(p(s)?),
+        ParameterValueTypeId::U16 => ParameterValue::U16(p(s)?),
+        ParameterValueTypeId::I16 => ParameterValue::I16(p(s)?),
+        ParameterValueTypeId::U32 => ParameterValue::U32(p(s)?),
+        ParameterValueTypeId::I32 => ParameterValue::I32(p(s)?),
+        ParameterValueTypeId::U64 => ParameterValue::U64(p(s)?),
+        ParameterValueTypeId::I64 => ParameterValue::I64(p(s)?),
+        ParameterValueTypeId::F32 => ParameterValue::F32(p(s)?),
+        ParameterValueTypeId::F64 => ParameterValue::F64(p(s)?),
+        _ => return Err(Error::ValueTypeMismatch),
+    })
+}
+
+fn read_element(elem: ParameterValueTypeId, data: &[u8]) -> ParameterValue {
+    match elem {
+        ParameterValueTypeId::Bool => ParameterValue::Bool(data[0] != 0),
+        ParameterValueTypeId::U8 => ParameterValue::U8(data[0]),
+        ParameterValueTypeId::I8 => ParameterValue::I8(data[0] as _),
+        ParameterValueTypeId::U16 => ParameterValue::U16(LittleEndian::read_u16(data)),
+        ParameterValueTypeId::I16 => ParameterValue::I16(LittleEndian::read_i16(data)),
+        ParameterValueTypeId::U32 => ParameterValue::U32(LittleEndian::read_u32(data)),
+        ParameterValueTypeId::I32 => ParameterValue::I32(LittleEndian::read_i32(data)),
+        ParameterValueTypeId::U64 => ParameterValue::U64(LittleEndian::read_u64(data)),
+        ParameterValueTypeId::I64 => ParameterValue::I64(LittleEndian::read_i64(data)),
+        ParameterValueTypeId::F32 => ParameterValue::F32(LittleEndian::read_f32(data)),
+        ParameterValueTypeId::F64 => ParameterValue::F64(LittleEndian::read_f64(data)),
+        _ => ParameterValue::None,
+    }
+}
+
+fn write_element(value: ParameterValue, data: &mut [u8]) {
+    match value {
+        ParameterValue::Bool(v) => data[0] = v as u8,
+        ParameterValue::U8(v) => data[0] = v,
+        ParameterValue::I8(v) => data[0] = v as _,
+        ParameterValue::U16(v) => LittleEndian::write_u16(data, v),
+        ParameterValue::I16(v) => LittleEndian::write_i16(data, v),
+        ParameterValue::U32(v) => LittleEndian::write_u32(data, v),
+        ParameterValue::I32(v) => LittleEndian::write_i32(data, v),
+        ParameterValue::U64(v) => LittleEndian::write_u64(data, v),
+        ParameterValue::I64(v) => LittleEndian::write_i64(data, v),
+        ParameterValue::F32(v) => LittleEndian::write_f32(data, v),
+        ParameterValue::F64(v) => LittleEndian::write_f64(data, v),
+        _ => (),
+    }
+}
+
+impl PartialEq for Array {
+    fn eq(&self, other: &Self) -> bool {
+        self.elem == other.elem && self.iter().eq(other.iter())
+    }
+}
+
+impl PartialOrd for Array {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.elem.partial_cmp(&other.elem) {
+            Some(Ordering::Equal) => self.iter().partial_cmp(other.iter()),
+            ord => ord,
+        }
+    }
+}
+
+impl fmt::Debug for Array {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl fmt::Display for Array {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[")?;
+        for (index, v) in self.iter().enumerate() {
+            if index != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", v)?;
+        }
+        write!(f, "]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn element_types() {
+        assert!(Array::new(ParameterValueTypeId::F32).is_ok());
+        assert_eq!(
+            Array::new(ParameterValueTypeId::Str),
+            Err(Error::ValueTypeMismatch)
+        );
+        assert_eq!(
+            Array::new(ParameterValueTypeId::Array),
+            Err(Error::ValueTypeMismatch)
+        );
+    }
+
+    #[test]
+    fn capacity() {
+        let mut a = Array::new(ParameterValueTypeId::F32).unwrap();
+        assert_eq!(a.capacity(), MAX_ARRAY_WIRE_SIZE / 4);
+        for i in 0..a.capacity() {
+            assert_eq!(a.push(ParameterValue::F32(i as f32)), Ok(()));
+        }
+        assert_eq!(a.push(ParameterValue::F32(1.0)), Err(Error::Capacity));
+
+        let a = Array::new(ParameterValueTypeId::U8).unwrap();
+        assert_eq!(a.capacity(), MAX_ARRAY_WIRE_SIZE);
+    }
+
+    #[test]
+    fn push_get_set() {
+        let mut a = Array::new(ParameterValueTypeId::I16).unwrap();
+        assert!(a.is_empty());
+        assert_eq!(a.push(ParameterValue::I16(-1)), Ok(()));
+        assert_eq!(a.push(ParameterValue::I16(2)), Ok(()));
+        assert_eq!(a.push(ParameterValue::U8(2)), Err(Error::ValueTypeMismatch));
+        assert_eq!(a.len(), 2);
+        assert_eq!(a.wire_size(), 2 + 2 * 2);
+        assert_eq!(a.get(0), Some(ParameterValue::I16(-1)));
+        assert_eq!(a.get(1), Some(ParameterValue::I16(2)));
+        assert_eq!(a.get(2), None);
+        assert_eq!(a.set(1, ParameterValue::I16(3)), Ok(()));
+        assert_eq!(a.get(1), Some(ParameterValue::I16(3)));
+        assert_eq!(
+            a.set(2, ParameterValue::I16(3)),
+            Err(Error::WireIndexOutOfBounds)
+        );
+        assert_eq!(a.as_le_bytes(), &[0xFF, 0xFF, 0x03, 0x00]);
+    }
+
+    #[test]
+    fn le_bytes_round_trip() {
+        let a = Array::parse(ParameterValueTypeId::U32, "1, 2, 3").unwrap();
+        let b = Array::from_le_bytes(ParameterValueTypeId::U32, a.len(), a.as_le_bytes()).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(
+            Array::from_le_bytes(ParameterValueTypeId::U32, 4, a.as_le_bytes()),
+            Err(Error::WireTruncated)
+        );
+    }
+
+    #[test]
+    fn parse() {
+        let a = Array::parse(ParameterValueTypeId::F32, "[1.5, -2.0,3]").unwrap();
+        assert_eq!(a.len(), 3);
+        match a.get(0) {
+            Some(ParameterValue::F32(v)) => assert_relative_eq!(v, 1.5),
+            _ => panic!("Unexpected value"),
+        }
+        assert_eq!(
+            Array::parse(ParameterValueTypeId::U8, "1, x"),
+            Err(Error::ParseValue)
+        );
+        assert!(Array::parse(ParameterValueTypeId::U8, "")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn parse_type() {
+        assert_eq!(
+            Array::parse_type("f32[4]"),
+            Ok((ParameterValueTypeId::F32, 4))
+        );
+        assert_eq!(
+            Array::parse_type("u8[32]"),
+            Ok((ParameterValueTypeId::U8, 32))
+        );
+        assert_eq!(Array::parse_type("u8[33]"), Err(Error::Capacity));
+        assert_eq!(Array::parse_type("f32"), Err(Error::ParseValue));
+        assert_eq!(Array::parse_type("f32[x]"), Err(Error::ParseValue));
+        assert_eq!(Array::parse_type("str[2]"), Err(Error::ValueTypeMismatch));
+    }
+}
@@ -0,0 +1,215 @@
+use crate::Error;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str;
+
+/// Maximum number of bytes a `Bytes` can hold
+pub const MAX_BYTES_LEN: usize = 32;
+
+/// Bounded, fixed-capacity opaque byte blob value
+#[derive(Copy, Clone)]
+pub struct Bytes {
+    len: u8,
+    buf: [u8; MAX_BYTES_LEN],
+}
+
+impl Bytes {
+    pub const fn new() -> Self {
+        Bytes {
+            len: 0,
+            buf: [0; MAX_BYTES_LEN],
+        }
+    }
+
+    /// Used by generated code, the remainder after `buf[..len]` must be zero
+    pub const fn from_raw_parts(len: u8, buf: [u8; MAX_BYTES_LEN]) -> Self {
+        Bytes { len, buf }
+    }
+
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() > MAX_BYTES_LEN {
+            return Err(Error::Capacity);
+        }
+        let mut b = Bytes::new();
+        b.buf[..bytes.len()].copy_from_slice(bytes);
+        b.len = bytes.len() as u8;
+        Ok(b)
+    }
+
+    /// Parse a hex string, an optional `0x` prefix and `:`/`-` separators
+    /// are accepted (`0x0A0B`, `0a:0b`)
+    pub fn from_hex(s: &str) -> Result<Self, Error> {
+        let s = if s.starts_with("0x") || s.starts_with("0X") {
+            &s[2..]
+        } else {
+            s
+        };
+        let mut b = Bytes::new();
+        let mut high: Option<u8> = None;
+        for c in s.chars().filter(|c| *c != ':' && *c != '-') {
+            let nibble = c.to_digit(16).ok_or(Error::ParseValue)? as u8;
+            match high.take() {
+                None => high = Some(nibble),
+                Some(h) => b.push((h << 4) | nibble)?,
+            }
+        }
+        if high.is_some() {
+            Err(Error::ParseValue)
+        } else {
+            Ok(b)
+        }
+    }
+
+    pub fn push(&mut self, byte: u8) -> Result<(), Error> {
+        let len = self.len();
+        if len >= MAX_BYTES_LEN {
+            Err(Error::Capacity)
+        } else {
+            self.buf[len] = byte;
+            self.len += 1;
+            Ok(())
+        }
+    }
+
+    pub const fn capacity() -> usize {
+        MAX_BYTES_LEN
+    }
+
+    pub fn len(&self) -> usize {
+        usize::from(self.len).min(MAX_BYTES_LEN)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf[..self.len()]
+    }
+
+    /// Length prefix followed by the bytes
+    pub fn wire_size(&self) -> usize {
+        1 + self.len()
+    }
+}
+
+impl Default for Bytes {
+    fn default() -> Self {
+        Bytes::new()
+    }
+}
+
+impl str::FromStr for Bytes {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Bytes::from_hex(s)
+    }
+}
+
+impl PartialEq for Bytes {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for Bytes {}
+
+impl PartialOrd for Bytes {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Bytes {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl Hash for Bytes {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl fmt::Debug for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Bytes({})", self)
+    }
+}
+
+impl fmt::LowerHex for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for b in self.as_slice() {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{:x}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn from_hex() {
+        let b = Bytes::from_hex("0x0A0bFF").unwrap();
+        assert_eq!(b.as_slice(), &[0x0A, 0x0B, 0xFF]);
+        assert_eq!(b.wire_size(), 1 + 3);
+
+        let b = Bytes::from_hex("02:00:05:06:07:08").unwrap();
+        assert_eq!(b.as_slice(), &[0x02, 0x00, 0x05, 0x06, 0x07, 0x08]);
+
+        let b = Bytes::from_hex("").unwrap();
+        assert!(b.is_empty());
+        assert_eq!(b, Bytes::default());
+
+        assert_eq!(Bytes::from_hex("0xABC"), Err(Error::ParseValue));
+        assert_eq!(Bytes::from_hex("0xZZ"), Err(Error::ParseValue));
+    }
+
+    #[test]
+    fn capacity() {
+        let max = [0xAB; MAX_BYTES_LEN];
+        let b = Bytes::from_slice(&max[..]).unwrap();
+        assert_eq!(b.len(), Bytes::capacity());
+
+        let too_big = [0xAB; MAX_BYTES_LEN + 1];
+        assert_eq!(Bytes::from_slice(&too_big[..]), Err(Error::Capacity));
+
+        let mut b = b;
+        assert_eq!(b.push(0), Err(Error::Capacity));
+    }
+
+    #[test]
+    fn display() {
+        struct Buf([u8; 16], usize);
+        impl Write for Buf {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0[self.1..self.1 + s.len()].copy_from_slice(s.as_bytes());
+                self.1 += s.len();
+                Ok(())
+            }
+        }
+        let mut buf = Buf([0; 16], 0);
+        let b = Bytes::from_slice(&[0x0A, 0xFF]).unwrap();
+        write!(buf, "{}", b).unwrap();
+        assert_eq!(&buf.0[..buf.1], b"0x0aff");
+    }
+}
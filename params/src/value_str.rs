@@ -0,0 +1,167 @@
+use crate::Error;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str;
+
+/// Maximum number of UTF-8 bytes a `Str` can hold
+pub const MAX_STR_LEN: usize = 32;
+
+/// Bounded, fixed-capacity UTF-8 string value
+#[derive(Copy, Clone)]
+pub struct Str {
+    len: u8,
+    buf: [u8; MAX_STR_LEN],
+}
+
+impl Str {
+    pub const fn new() -> Self {
+        Str {
+            len: 0,
+            buf: [0; MAX_STR_LEN],
+        }
+    }
+
+    /// Used by generated code, `buf[..len]` must be valid UTF-8 and
+    /// the remainder zero
+    pub const fn from_raw_parts(len: u8, buf: [u8; MAX_STR_LEN]) -> Self {
+        Str { len, buf }
+    }
+
+    pub fn from_utf8(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() > MAX_STR_LEN {
+            return Err(Error::Capacity);
+        }
+        str::from_utf8(bytes).map_err(|_| Error::ParseValue)?;
+        let mut s = Str::new();
+        s.buf[..bytes.len()].copy_from_slice(bytes);
+        s.len = bytes.len() as u8;
+        Ok(s)
+    }
+
+    pub const fn capacity() -> usize {
+        MAX_STR_LEN
+    }
+
+    pub fn len(&self) -> usize {
+        usize::from(self.len).min(MAX_STR_LEN)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len()]
+    }
+
+    pub fn as_str(&self) -> &str {
+        str::from_utf8(self.as_bytes()).unwrap_or_default()
+    }
+
+    /// Length prefix followed by the UTF-8 bytes
+    pub fn wire_size(&self) -> usize {
+        1 + self.len()
+    }
+}
+
+impl Default for Str {
+    fn default() -> Self {
+        Str::new()
+    }
+}
+
+impl str::FromStr for Str {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Str::from_utf8(s.as_bytes())
+    }
+}
+
+impl PartialEq for Str {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for Str {}
+
+impl PartialOrd for Str {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Str {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_bytes().cmp(other.as_bytes())
+    }
+}
+
+impl Hash for Str {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state)
+    }
+}
+
+impl AsRef<str> for Str {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for Str {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Str").field(&self.as_str()).finish()
+    }
+}
+
+impl fmt::Display for Str {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn from_str() {
+        let s = Str::from_str("hello").unwrap();
+        assert_eq!(s.len(), 5);
+        assert_eq!(s.as_str(), "hello");
+        assert_eq!(s.as_bytes(), b"hello");
+        assert_eq!(s.wire_size(), 1 + 5);
+
+        let s = Str::from_str("").unwrap();
+        assert!(s.is_empty());
+        assert_eq!(s, Str::default());
+    }
+
+    #[test]
+    fn capacity() {
+        let max = [b'a'; MAX_STR_LEN];
+        let s = Str::from_utf8(&max[..]).unwrap();
+        assert_eq!(s.len(), Str::capacity());
+
+        let too_big = [b'a'; MAX_STR_LEN + 1];
+        assert_eq!(Str::from_utf8(&too_big[..]), Err(Error::Capacity));
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        assert_eq!(Str::from_utf8(&[0xC3, 0x28]), Err(Error::ParseValue));
+    }
+
+    #[test]
+    fn raw_parts() {
+        let mut buf = [0; MAX_STR_LEN];
+        buf[..3].copy_from_slice(b"abc");
+        let s = Str::from_raw_parts(3, buf);
+        assert_eq!(s, Str::from_str("abc").unwrap());
+    }
+}
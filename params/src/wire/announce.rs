@@ -0,0 +1,194 @@
+use crate::{Error, GetSetVersion};
+use byteorder::{ByteOrder, LittleEndian};
+use core::str;
+
+#[derive(Debug, Clone)]
+pub struct Packet<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+mod field {
+    use crate::wire::field::*;
+
+    pub const MIN_VERSION: usize = 0;
+    pub const MAX_VERSION: usize = 1;
+    /// CRC32 of the paramdb the node was built with
+    pub const PARAMDB_HASH: Field = 2..6;
+    /// IPv4 address and port of the node's TCP endpoint
+    pub const TCP_IP: Field = 6..10;
+    pub const TCP_PORT: Field = 10..12;
+    pub const FW_VERSION_LEN: usize = 12;
+    /// Not nul terminated
+    pub const FW_VERSION: Rest = 13..;
+}
+
+impl<T: AsRef<[u8]>> Packet<T> {
+    pub fn new_unchecked(buffer: T) -> Packet<T> {
+        Packet { buffer }
+    }
+
+    pub fn new_checked(buffer: T) -> Result<Packet<T>, Error> {
+        let packet = Self::new_unchecked(buffer);
+        packet.check_len()?;
+        Ok(packet)
+    }
+
+    /// The header and the firmware version it declares must be present
+    pub fn check_len(&self) -> Result<(), Error> {
+        let len = self.buffer.as_ref().len();
+        if len < field::FW_VERSION.start || len < Self::buffer_len(self.fw_version_len()) {
+            Err(Error::WireTruncated)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    pub fn buffer_len(fw_version_len: usize) -> usize {
+        field::FW_VERSION.start + fw_version_len
+    }
+
+    #[inline]
+    pub fn min_version(&self) -> GetSetVersion {
+        let data = self.buffer.as_ref();
+        data[field::MIN_VERSION]
+    }
+
+    #[inline]
+    pub fn max_version(&self) -> GetSetVersion {
+        let data = self.buffer.as_ref();
+        data[field::MAX_VERSION]
+    }
+
+    #[inline]
+    pub fn paramdb_hash(&self) -> u32 {
+        let data = self.buffer.as_ref();
+        LittleEndian::read_u32(&data[field::PARAMDB_HASH])
+    }
+
+    #[inline]
+    pub fn tcp_ip(&self) -> [u8; 4] {
+        let data = self.buffer.as_ref();
+        let mut ip = [0; 4];
+        ip.copy_from_slice(&data[field::TCP_IP]);
+        ip
+    }
+
+    #[inline]
+    pub fn tcp_port(&self) -> u16 {
+        let data = self.buffer.as_ref();
+        LittleEndian::read_u16(&data[field::TCP_PORT])
+    }
+
+    #[inline]
+    pub fn fw_version_len(&self) -> usize {
+        let data = self.buffer.as_ref();
+        usize::from(data[field::FW_VERSION_LEN])
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Packet<&'a T> {
+    #[inline]
+    pub fn fw_version(&self) -> Result<&'a str, Error> {
+        let data = self.buffer.as_ref();
+        let end = field::FW_VERSION.start + self.fw_version_len();
+        let bytes = data
+            .get(field::FW_VERSION.start..end)
+            .ok_or(Error::WireTruncated)?;
+        str::from_utf8(bytes).map_err(|_| Error::ParseValue)
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> Packet<T> {
+    #[inline]
+    pub fn set_min_version(&mut self, value: GetSetVersion) {
+        let data = self.buffer.as_mut();
+        data[field::MIN_VERSION] = value;
+    }
+
+    #[inline]
+    pub fn set_max_version(&mut self, value: GetSetVersion) {
+        let data = self.buffer.as_mut();
+        data[field::MAX_VERSION] = value;
+    }
+
+    #[inline]
+    pub fn set_paramdb_hash(&mut self, value: u32) {
+        let data = self.buffer.as_mut();
+        LittleEndian::write_u32(&mut data[field::PARAMDB_HASH], value);
+    }
+
+    #[inline]
+    pub fn set_tcp_ip(&mut self, value: [u8; 4]) {
+        let data = self.buffer.as_mut();
+        data[field::TCP_IP].copy_from_slice(&value);
+    }
+
+    #[inline]
+    pub fn set_tcp_port(&mut self, value: u16) {
+        let data = self.buffer.as_mut();
+        LittleEndian::write_u16(&mut data[field::TCP_PORT], value);
+    }
+
+    /// Sets the length then the firmware version, the buffer
+    /// must hold buffer_len(value.len()) bytes
+    #[inline]
+    pub fn set_fw_version(&mut self, value: &str) {
+        let data = self.buffer.as_mut();
+        data[field::FW_VERSION_LEN] = value.len() as u8;
+        let end = field::FW_VERSION.start + value.len();
+        data[field::FW_VERSION.start..end].copy_from_slice(value.as_bytes());
+    }
+}
+
+impl<T: AsRef<[u8]>> AsRef<[u8]> for Packet<T> {
+    fn as_ref(&self) -> &[u8] {
+        self.buffer.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    static BYTES: [u8; 18] = [
+        0x01, 0x02, 0x78, 0x56, 0x34, 0x12, 0xC0, 0xA8, 0x01, 0x27, 0x95, 0x26, 0x05, 0x30, 0x2E,
+        0x31, 0x2E, 0x30,
+    ];
+
+    #[test]
+    fn construct() {
+        let mut bytes = [0xFF; 18];
+        let mut p = Packet::new_unchecked(&mut bytes[..]);
+        p.set_min_version(1);
+        p.set_max_version(2);
+        p.set_paramdb_hash(0x1234_5678);
+        p.set_tcp_ip([192, 168, 1, 39]);
+        p.set_tcp_port(9877);
+        p.set_fw_version("0.1.0");
+        assert_eq!(&p.into_inner()[..], &BYTES[..]);
+    }
+
+    #[test]
+    fn deconstruct() {
+        let p = Packet::new_checked(&BYTES[..]).unwrap();
+        assert_eq!(p.min_version(), 1);
+        assert_eq!(p.max_version(), 2);
+        assert_eq!(p.paramdb_hash(), 0x1234_5678);
+        assert_eq!(p.tcp_ip(), [192, 168, 1, 39]);
+        assert_eq!(p.tcp_port(), 9877);
+        assert_eq!(p.fw_version(), Ok("0.1.0"));
+        assert_eq!(
+            Packet::new_checked(&BYTES[..17]).err(),
+            Some(Error::WireTruncated)
+        );
+        assert_eq!(
+            Packet::new_checked(&BYTES[..12]).err(),
+            Some(Error::WireTruncated)
+        );
+    }
+}
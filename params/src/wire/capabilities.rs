@@ -0,0 +1,138 @@
+use crate::{Error, GetSetVersion};
+use byteorder::{ByteOrder, LittleEndian};
+
+#[derive(Debug, Clone)]
+pub struct Packet<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+mod field {
+    use crate::wire::field::*;
+
+    pub const MIN_VERSION: usize = 0;
+    pub const MAX_VERSION: usize = 1;
+    /// Bit N is set when the op with value N is supported
+    pub const OPS: Field = 2..6;
+    /// Epoch the node's auth tags cover, picked at random every boot
+    pub const AUTH_EPOCH: Field = 6..14;
+}
+
+impl<T: AsRef<[u8]>> Packet<T> {
+    pub fn new_unchecked(buffer: T) -> Packet<T> {
+        Packet { buffer }
+    }
+
+    pub fn new_checked(buffer: T) -> Result<Packet<T>, Error> {
+        let packet = Self::new_unchecked(buffer);
+        packet.check_len()?;
+        Ok(packet)
+    }
+
+    pub fn check_len(&self) -> Result<(), Error> {
+        let len = self.buffer.as_ref().len();
+        if len < Self::buffer_len() {
+            Err(Error::WireTruncated)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    /// The packet is fixed size
+    pub fn buffer_len() -> usize {
+        field::AUTH_EPOCH.end
+    }
+
+    #[inline]
+    pub fn min_version(&self) -> GetSetVersion {
+        let data = self.buffer.as_ref();
+        data[field::MIN_VERSION]
+    }
+
+    #[inline]
+    pub fn max_version(&self) -> GetSetVersion {
+        let data = self.buffer.as_ref();
+        data[field::MAX_VERSION]
+    }
+
+    #[inline]
+    pub fn ops(&self) -> u32 {
+        let data = self.buffer.as_ref();
+        LittleEndian::read_u32(&data[field::OPS])
+    }
+
+    #[inline]
+    pub fn auth_epoch(&self) -> u64 {
+        let data = self.buffer.as_ref();
+        LittleEndian::read_u64(&data[field::AUTH_EPOCH])
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> Packet<T> {
+    #[inline]
+    pub fn set_min_version(&mut self, value: GetSetVersion) {
+        let data = self.buffer.as_mut();
+        data[field::MIN_VERSION] = value;
+    }
+
+    #[inline]
+    pub fn set_max_version(&mut self, value: GetSetVersion) {
+        let data = self.buffer.as_mut();
+        data[field::MAX_VERSION] = value;
+    }
+
+    #[inline]
+    pub fn set_ops(&mut self, value: u32) {
+        let data = self.buffer.as_mut();
+        LittleEndian::write_u32(&mut data[field::OPS], value);
+    }
+
+    #[inline]
+    pub fn set_auth_epoch(&mut self, value: u64) {
+        let data = self.buffer.as_mut();
+        LittleEndian::write_u64(&mut data[field::AUTH_EPOCH], value);
+    }
+}
+
+impl<T: AsRef<[u8]>> AsRef<[u8]> for Packet<T> {
+    fn as_ref(&self) -> &[u8] {
+        self.buffer.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    static BYTES: [u8; 14] = [
+        0x01, 0x02, 0x17, 0x00, 0x00, 0x00, 0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01,
+    ];
+
+    #[test]
+    fn construct() {
+        let mut bytes = [0xFF; 14];
+        let mut p = Packet::new_checked(&mut bytes[..]).unwrap();
+        p.set_min_version(1);
+        p.set_max_version(2);
+        p.set_ops(0x17);
+        p.set_auth_epoch(0x0102_0304_0506_0708);
+        assert_eq!(&p.into_inner()[..], &BYTES[..]);
+    }
+
+    #[test]
+    fn deconstruct() {
+        let p = Packet::new_checked(&BYTES[..]).unwrap();
+        assert_eq!(p.min_version(), 1);
+        assert_eq!(p.max_version(), 2);
+        assert_eq!(p.ops(), 0x17);
+        assert_eq!(p.auth_epoch(), 0x0102_0304_0506_0708);
+        assert_eq!(
+            Packet::new_checked(&BYTES[..13]).err(),
+            Some(Error::WireTruncated)
+        );
+    }
+}
@@ -0,0 +1,28 @@
+/// CRC-32 (IEEE 802.3, reflected 0xEDB88320), computed bitwise since
+/// building a lookup table in a const fn needs a newer toolchain than
+/// the firmware is pinned to
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, b| {
+        (0..8).fold(crc ^ u32::from(*b), |crc, _| {
+            if crc & 1 != 0 {
+                (crc >> 1) ^ POLY
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+const POLY: u32 = 0xEDB8_8320;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn check_value() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+}
@@ -0,0 +1,259 @@
+use crate::value::TypeId;
+use crate::{Error, ParameterFlags, ParameterId};
+use byteorder::{ByteOrder, LittleEndian};
+use core::convert::TryFrom;
+use core::str;
+
+#[derive(Debug, Clone)]
+pub struct Packet<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+mod field {
+    use crate::wire::field::*;
+
+    pub const ID: Field = 0..4;
+    pub const FLAGS: Field = 4..8;
+    pub const VALUE_TYPE_ID: usize = 8;
+    /// Non-zero when MIN and MAX hold a range
+    pub const HAS_RANGE: usize = 9;
+    pub const MIN: Field = 10..18;
+    pub const MAX: Field = 18..26;
+    pub const NAME_LEN: usize = 26;
+    pub const DESC_LEN: usize = 27;
+    /// Zero when the parameter has no units
+    pub const UNITS_LEN: usize = 28;
+    /// Name, description then units, not nul terminated
+    pub const STRINGS: Rest = 29..;
+}
+
+impl<T: AsRef<[u8]>> Packet<T> {
+    pub fn new_unchecked(buffer: T) -> Packet<T> {
+        Packet { buffer }
+    }
+
+    pub fn new_checked(buffer: T) -> Result<Packet<T>, Error> {
+        let packet = Self::new_unchecked(buffer);
+        packet.check_len()?;
+        Ok(packet)
+    }
+
+    /// The header and the strings it declares must be present
+    pub fn check_len(&self) -> Result<(), Error> {
+        let len = self.buffer.as_ref().len();
+        if len < field::STRINGS.start || len < Self::buffer_len(self.strings_len()) {
+            Err(Error::WireTruncated)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    pub fn header_len() -> usize {
+        field::STRINGS.start
+    }
+
+    pub fn buffer_len(strings_len: usize) -> usize {
+        field::STRINGS.start + strings_len
+    }
+
+    /// Combined length of the name, description and units
+    #[inline]
+    pub fn strings_len(&self) -> usize {
+        let data = self.buffer.as_ref();
+        usize::from(data[field::NAME_LEN])
+            + usize::from(data[field::DESC_LEN])
+            + usize::from(data[field::UNITS_LEN])
+    }
+
+    #[inline]
+    pub fn id(&self) -> ParameterId {
+        let data = self.buffer.as_ref();
+        ParameterId::from(LittleEndian::read_u32(&data[field::ID]))
+    }
+
+    #[inline]
+    pub fn flags(&self) -> ParameterFlags {
+        let data = self.buffer.as_ref();
+        ParameterFlags::from(LittleEndian::read_u32(&data[field::FLAGS]))
+    }
+
+    #[inline]
+    pub fn value_type_id(&self) -> Result<TypeId, Error> {
+        let data = self.buffer.as_ref();
+        TypeId::try_from(data[field::VALUE_TYPE_ID])
+    }
+
+    #[inline]
+    pub fn range(&self) -> Option<(f64, f64)> {
+        let data = self.buffer.as_ref();
+        if data[field::HAS_RANGE] == 0 {
+            None
+        } else {
+            Some((
+                LittleEndian::read_f64(&data[field::MIN]),
+                LittleEndian::read_f64(&data[field::MAX]),
+            ))
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Packet<&'a T> {
+    #[inline]
+    pub fn name(&self) -> Result<&'a str, Error> {
+        let start = field::STRINGS.start;
+        self.string(start, field::NAME_LEN)
+    }
+
+    #[inline]
+    pub fn desc(&self) -> Result<&'a str, Error> {
+        let data = self.buffer.as_ref();
+        let start = field::STRINGS.start + usize::from(data[field::NAME_LEN]);
+        self.string(start, field::DESC_LEN)
+    }
+
+    /// None when the parameter has no units
+    #[inline]
+    pub fn units(&self) -> Result<Option<&'a str>, Error> {
+        let data = self.buffer.as_ref();
+        let start = field::STRINGS.start
+            + usize::from(data[field::NAME_LEN])
+            + usize::from(data[field::DESC_LEN]);
+        let units = self.string(start, field::UNITS_LEN)?;
+        Ok(if units.is_empty() { None } else { Some(units) })
+    }
+
+    fn string(&self, start: usize, len_field: usize) -> Result<&'a str, Error> {
+        let data = self.buffer.as_ref();
+        let end = start + usize::from(data[len_field]);
+        let bytes = data.get(start..end).ok_or(Error::WireTruncated)?;
+        str::from_utf8(bytes).map_err(|_| Error::ParseValue)
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> Packet<T> {
+    #[inline]
+    pub fn set_id(&mut self, value: ParameterId) {
+        let data = self.buffer.as_mut();
+        LittleEndian::write_u32(&mut data[field::ID], value.into())
+    }
+
+    #[inline]
+    pub fn set_flags(&mut self, value: ParameterFlags) {
+        let data = self.buffer.as_mut();
+        LittleEndian::write_u32(&mut data[field::FLAGS], value.into())
+    }
+
+    #[inline]
+    pub fn set_value_type_id(&mut self, value: TypeId) {
+        let data = self.buffer.as_mut();
+        data[field::VALUE_TYPE_ID] = value.as_u8();
+    }
+
+    #[inline]
+    pub fn set_range(&mut self, value: Option<(f64, f64)>) {
+        let data = self.buffer.as_mut();
+        let (has_range, (min, max)) = match value {
+            Some(range) => (1, range),
+            None => (0, (0.0, 0.0)),
+        };
+        data[field::HAS_RANGE] = has_range;
+        LittleEndian::write_f64(&mut data[field::MIN], min);
+        LittleEndian::write_f64(&mut data[field::MAX], max);
+    }
+
+    /// Each string must be at most 255 bytes, units may be empty
+    #[inline]
+    pub fn set_strings(&mut self, name: &str, desc: &str, units: &str) -> Result<(), Error> {
+        let data = self.buffer.as_mut();
+        let mut offset = field::STRINGS.start;
+        for (len_field, s) in [
+            (field::NAME_LEN, name),
+            (field::DESC_LEN, desc),
+            (field::UNITS_LEN, units),
+        ]
+        .iter()
+        {
+            let len = u8::try_from(s.len()).map_err(|_| Error::Capacity)?;
+            data.get_mut(offset..offset + s.len())
+                .ok_or(Error::WireTruncated)?
+                .copy_from_slice(s.as_bytes());
+            data[*len_field] = len;
+            offset += s.len();
+        }
+        Ok(())
+    }
+}
+
+impl<T: AsRef<[u8]>> AsRef<[u8]> for Packet<T> {
+    fn as_ref(&self) -> &[u8] {
+        self.buffer.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    static BYTES: [u8; 36] = [
+        0x0A, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x05, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x59, 0x40, 0x02, 0x04, 0x01, 0x69,
+        0x64, 0x44, 0x65, 0x73, 0x63, 0x73,
+    ];
+
+    #[test]
+    fn header_len() {
+        assert_eq!(Packet::<&[u8]>::header_len(), 29);
+        assert_eq!(Packet::<&[u8]>::buffer_len(7), 29 + 7);
+    }
+
+    #[test]
+    fn construct() {
+        let mut bytes = [0xFF; 36];
+        let mut p = Packet::new_unchecked(&mut bytes[..]);
+        p.set_id(ParameterId::new(0x0A));
+        p.set_flags(ParameterFlags(1));
+        p.set_value_type_id(TypeId::U32);
+        p.set_range(Some((0.0, 100.0)));
+        assert_eq!(p.set_strings("id", "Desc", "s"), Ok(()));
+        assert_eq!(&p.into_inner()[..], &BYTES[..]);
+    }
+
+    #[test]
+    fn deconstruct() {
+        let p = Packet::new_checked(&BYTES[..]).unwrap();
+        assert_eq!(p.id(), ParameterId::new(0x0A));
+        assert_eq!(p.flags(), ParameterFlags(1));
+        assert_eq!(p.value_type_id(), Ok(TypeId::U32));
+        assert_eq!(p.range(), Some((0.0, 100.0)));
+        assert_eq!(p.strings_len(), 7);
+        assert_eq!(p.name(), Ok("id"));
+        assert_eq!(p.desc(), Ok("Desc"));
+        assert_eq!(p.units(), Ok(Some("s")));
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            Packet::new_checked(&BYTES[..BYTES.len() - 1]).err(),
+            Some(Error::WireTruncated)
+        );
+
+        let mut bytes = BYTES;
+        bytes[29] = 0xFF;
+        let p = Packet::new_checked(&bytes[..]).unwrap();
+        assert_eq!(p.name(), Err(Error::ParseValue));
+
+        let mut bytes = [0; 29];
+        let mut p = Packet::new_unchecked(&mut bytes[..]);
+        assert_eq!(p.set_strings("id", "", ""), Err(Error::WireTruncated));
+        p.set_range(None);
+        assert_eq!(p.range(), None);
+        let p = Packet::new_checked(&bytes[..]).unwrap();
+        assert_eq!(p.units(), Ok(None));
+    }
+}
@@ -0,0 +1,195 @@
+use crate::{Description, DescriptionPacket, Error, MAX_PARAMS_PER_OP};
+
+#[derive(Debug, Clone)]
+pub struct Packet<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+mod field {
+    use crate::wire::field::*;
+
+    pub const COUNT: usize = 0;
+    pub const DESCRIPTIONS: Rest = 1..;
+}
+
+impl<T: AsRef<[u8]>> Packet<T> {
+    pub fn new_unchecked(buffer: T) -> Packet<T> {
+        Packet { buffer }
+    }
+
+    pub fn new_checked(buffer: T) -> Result<Packet<T>, Error> {
+        let packet = Self::new_unchecked(buffer);
+        packet.check_len()?;
+        Ok(packet)
+    }
+
+    pub fn check_len(&self) -> Result<(), Error> {
+        let len = self.buffer.as_ref().len();
+        if len < field::DESCRIPTIONS.start {
+            Err(Error::WireTruncated)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    pub fn header_len() -> usize {
+        field::DESCRIPTIONS.start
+    }
+
+    pub fn buffer_len(payload_len: usize) -> usize {
+        field::DESCRIPTIONS.start + payload_len
+    }
+
+    #[inline]
+    pub fn count(&self) -> u8 {
+        let data = self.buffer.as_ref();
+        data[field::COUNT]
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Packet<&'a T> {
+    /// Borrowing iterator over the descriptions, walks the buffer once
+    #[inline]
+    pub fn iter(&self) -> Iter<'a> {
+        let data = self.buffer.as_ref();
+        Iter {
+            data,
+            offset: field::DESCRIPTIONS.start,
+            remaining: usize::from(self.count()),
+        }
+    }
+}
+
+/// Iterator over the descriptions of a `DescriptionListPacket`,
+/// stops after the first error
+#[derive(Debug, Clone, Default)]
+pub struct Iter<'a> {
+    data: &'a [u8],
+    offset: usize,
+    remaining: usize,
+}
+
+impl<'a> Iter<'a> {
+    fn next_description(&mut self) -> Result<Description<'a>, Error> {
+        let rest = self.data.get(self.offset..).ok_or(Error::WireTruncated)?;
+        let packet = DescriptionPacket::new_checked(rest)?;
+        let description = Description::parse(&packet)?;
+        self.offset += DescriptionPacket::<&[u8]>::buffer_len(packet.strings_len());
+        self.remaining -= 1;
+        Ok(description)
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Result<Description<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let result = self.next_description();
+        if result.is_err() {
+            self.remaining = 0;
+        }
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> Packet<T> {
+    #[inline]
+    pub fn set_count(&mut self, value: u8) {
+        let data = self.buffer.as_mut();
+        data[field::COUNT] = value;
+    }
+
+    #[inline]
+    pub fn set_description_at(&mut self, index: usize, value: &Description) -> Result<(), Error> {
+        if index >= MAX_PARAMS_PER_OP {
+            return Err(Error::WireIndexOutOfBounds);
+        }
+        let data = self.buffer.as_mut();
+        let mut offset = field::DESCRIPTIONS.start;
+        for _ in 0..index {
+            let p = DescriptionPacket::new_checked(&data[offset..])?;
+            offset += DescriptionPacket::<&[u8]>::buffer_len(p.strings_len());
+        }
+
+        // Cursor now at index
+        let mut p = DescriptionPacket::new_unchecked(&mut data[offset..]);
+        value.emit(&mut p)
+    }
+}
+
+impl<T: AsRef<[u8]>> AsRef<[u8]> for Packet<T> {
+    fn as_ref(&self) -> &[u8] {
+        self.buffer.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParameterFlags, ParameterId, ParameterValueTypeId};
+    use pretty_assertions::assert_eq;
+
+    static DESCRIPTIONS: [Description; 2] = [
+        Description::new(
+            ParameterId::new(0x0A),
+            ParameterFlags(0),
+            ParameterValueTypeId::U32,
+            "interval",
+            "Interval, seconds",
+        )
+        .with_units("s")
+        .with_range(1.0, 60.0),
+        Description::new(
+            ParameterId::new(0x0B),
+            ParameterFlags(1),
+            ParameterValueTypeId::Str,
+            "version",
+            "Version",
+        ),
+    ];
+
+    fn emit(bytes: &mut [u8]) -> usize {
+        let size = Packet::<&[u8]>::buffer_len(DESCRIPTIONS.iter().map(|d| d.wire_size()).sum());
+        let mut p = Packet::new_unchecked(&mut bytes[..size]);
+        p.set_count(DESCRIPTIONS.len() as u8);
+        for (index, d) in DESCRIPTIONS.iter().enumerate() {
+            assert_eq!(p.set_description_at(index, d), Ok(()));
+        }
+        size
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut bytes = [0xFF; 128];
+        let size = emit(&mut bytes);
+        assert_eq!(size, 1 + 29 + 8 + 17 + 1 + 29 + 7 + 7);
+        let p = Packet::new_checked(&bytes[..size]).unwrap();
+        assert_eq!(p.count(), 2);
+        let mut iter = p.iter();
+        assert_eq!(iter.next(), Some(Ok(DESCRIPTIONS[0])));
+        assert_eq!(iter.next(), Some(Ok(DESCRIPTIONS[1])));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn iter_truncated() {
+        let mut bytes = [0xFF; 128];
+        let size = emit(&mut bytes);
+        let p = Packet::new_checked(&bytes[..size - 1]).unwrap();
+        let mut iter = p.iter();
+        assert_eq!(iter.next(), Some(Ok(DESCRIPTIONS[0])));
+        assert_eq!(iter.next(), Some(Err(Error::WireTruncated)));
+        assert_eq!(iter.next(), None);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{Error, ErrorCode};
+use core::convert::TryFrom;
+
+#[derive(Debug, Clone)]
+pub struct Packet<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+mod field {
+    pub const CODE: usize = 0;
+    pub const OP: usize = 1;
+}
+
+impl<T: AsRef<[u8]>> Packet<T> {
+    pub fn new_unchecked(buffer: T) -> Packet<T> {
+        Packet { buffer }
+    }
+
+    pub fn new_checked(buffer: T) -> Result<Packet<T>, Error> {
+        let packet = Self::new_unchecked(buffer);
+        packet.check_len()?;
+        Ok(packet)
+    }
+
+    pub fn check_len(&self) -> Result<(), Error> {
+        let len = self.buffer.as_ref().len();
+        if len < Self::buffer_len() {
+            Err(Error::WireTruncated)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    /// The packet is fixed size
+    pub fn buffer_len() -> usize {
+        field::OP + 1
+    }
+
+    #[inline]
+    pub fn code(&self) -> Result<ErrorCode, Error> {
+        let data = self.buffer.as_ref();
+        ErrorCode::try_from(data[field::CODE])
+    }
+
+    /// Op of the offending request, as received
+    #[inline]
+    pub fn op(&self) -> u8 {
+        let data = self.buffer.as_ref();
+        data[field::OP]
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> Packet<T> {
+    #[inline]
+    pub fn set_code(&mut self, value: ErrorCode) {
+        let data = self.buffer.as_mut();
+        data[field::CODE] = value.as_u8();
+    }
+
+    #[inline]
+    pub fn set_op(&mut self, value: u8) {
+        let data = self.buffer.as_mut();
+        data[field::OP] = value;
+    }
+}
+
+impl<T: AsRef<[u8]>> AsRef<[u8]> for Packet<T> {
+    fn as_ref(&self) -> &[u8] {
+        self.buffer.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    static BYTES: [u8; 2] = [0x06, 0x01];
+
+    #[test]
+    fn construct() {
+        let mut bytes = [0xFF; 2];
+        let mut p = Packet::new_checked(&mut bytes[..]).unwrap();
+        p.set_code(ErrorCode::NotFound);
+        p.set_op(1);
+        assert_eq!(&p.into_inner()[..], &BYTES[..]);
+    }
+
+    #[test]
+    fn deconstruct() {
+        let p = Packet::new_checked(&BYTES[..]).unwrap();
+        assert_eq!(p.code(), Ok(ErrorCode::NotFound));
+        assert_eq!(p.op(), 1);
+        assert_eq!(
+            Packet::new_checked(&BYTES[..1]).err(),
+            Some(Error::WireTruncated)
+        );
+        assert_eq!(
+            Packet::new_checked(&[0xEE, 0x01][..]).unwrap().code(),
+            Err(Error::WireInvalidErrorCode)
+        );
+    }
+}
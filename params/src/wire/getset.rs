@@ -1,7 +1,10 @@
 use crate::{
-    Error, GetSetFlags, GetSetNodeId, GetSetOp, GetSetPayloadType, GetSetVersion, PREAMBLE_WORD,
+    wire::crc32::crc32, Error, GetSetFlags, GetSetNodeId, GetSetOp, GetSetPayloadType,
+    GetSetSequence, GetSetVersion, GETSET_FLAG_AUTH, GETSET_FLAG_CRC32, GETSET_FLAG_ENCRYPTED,
+    GETSET_MAX_VERSION, GETSET_MIN_VERSION, GETSET_VERSION_2, PREAMBLE_WORD,
 };
 use byteorder::{ByteOrder, LittleEndian};
+use core::convert::TryFrom;
 use core::fmt;
 
 #[derive(Debug, Clone)]
@@ -20,6 +23,25 @@ mod field {
     pub const PAYLOAD_TYPE: usize = 14;
     pub const PAYLOAD_SIZE: Field = 15..17;
     pub const PAYLOAD: Rest = 17..;
+
+    /// Version 2 extends the header, the payload follows it
+    pub const V2_SEQUENCE: Field = 17..21;
+    pub const V2_PAYLOAD: Rest = 21..;
+
+    /// Follows the payload when the encrypted flag is set, the nonce
+    /// then the Poly1305 tag
+    pub const CIPHER_NONCE: Field = 0..12;
+    pub const CIPHER_TAG: Field = 12..28;
+    pub const CIPHER_SIZE: usize = 28;
+
+    /// Follows the payload and cipher trailer when the auth flag is set,
+    /// the counter then the HMAC-SHA256 tag
+    pub const AUTH_COUNTER: Field = 0..8;
+    pub const AUTH_TAG: Field = 8..40;
+    pub const AUTH_SIZE: usize = 40;
+
+    /// Last field of the frame when the CRC32 flag is set
+    pub const CRC32_SIZE: usize = 4;
 }
 
 impl<T: AsRef<[u8]>> Frame<T> {
@@ -31,18 +53,51 @@ impl<T: AsRef<[u8]>> Frame<T> {
         let packet = Self::new_unchecked(buffer);
         packet.check_len()?;
         packet.check_preamble()?;
+        packet.check_version()?;
+        packet.check_payload_len()?;
+        packet.check_checksum()?;
         Ok(packet)
     }
 
+    /// The buffer must hold the header of the frame's version
     pub fn check_len(&self) -> Result<(), Error> {
         let len = self.buffer.as_ref().len();
-        if len < field::PAYLOAD.start {
+        if len < field::PAYLOAD.start || len < Self::header_len_for(self.version()) {
             Err(Error::WireTruncated)
         } else {
             Ok(())
         }
     }
 
+    /// The buffer must hold the whole payload and the CRC32 trailer if present,
+    /// expects the header to be present
+    pub fn check_payload_len(&self) -> Result<(), Error> {
+        let len = self.buffer.as_ref().len();
+        if len < self.cipher_start() + Self::trailer_len(self.flags()) {
+            Err(Error::WireTruncated)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn check_version(&self) -> Result<(), Error> {
+        let version = self.version();
+        if !(GETSET_MIN_VERSION..=GETSET_MAX_VERSION).contains(&version) {
+            Err(Error::WireUnsupportedVersion)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Verify the CRC32 trailer, frames without one always pass,
+    /// expects the payload to be present
+    pub fn check_checksum(&self) -> Result<(), Error> {
+        match self.checksum() {
+            Some(crc) if crc != self.compute_checksum() => Err(Error::WireChecksum),
+            _ => Ok(()),
+        }
+    }
+
     pub fn check_preamble(&self) -> Result<(), Error> {
         if self.preamble() != PREAMBLE_WORD {
             Err(Error::WirePreamble)
@@ -55,6 +110,7 @@ impl<T: AsRef<[u8]>> Frame<T> {
         self.buffer
     }
 
+    /// Length of the v1 header, the fields common to every version
     pub fn header_len() -> usize {
         field::PAYLOAD.start
     }
@@ -63,6 +119,149 @@ impl<T: AsRef<[u8]>> Frame<T> {
         field::PAYLOAD.start + payload_len
     }
 
+    /// Length of the header in the given version's layout,
+    /// unknown versions are treated as v1
+    pub fn header_len_for(version: GetSetVersion) -> usize {
+        if version == GETSET_VERSION_2 {
+            field::V2_PAYLOAD.start
+        } else {
+            field::PAYLOAD.start
+        }
+    }
+
+    pub fn buffer_len_for(version: GetSetVersion, payload_len: usize) -> usize {
+        Self::header_len_for(version) + payload_len
+    }
+
+    /// Length of the trailers for the given flags
+    pub fn trailer_len(flags: GetSetFlags) -> usize {
+        Self::cipher_len(flags) + Self::auth_len(flags) + Self::crc32_len(flags)
+    }
+
+    fn cipher_len(flags: GetSetFlags) -> usize {
+        if flags & GETSET_FLAG_ENCRYPTED != 0 {
+            field::CIPHER_SIZE
+        } else {
+            0
+        }
+    }
+
+    fn auth_len(flags: GetSetFlags) -> usize {
+        if flags & GETSET_FLAG_AUTH != 0 {
+            field::AUTH_SIZE
+        } else {
+            0
+        }
+    }
+
+    fn crc32_len(flags: GetSetFlags) -> usize {
+        if flags & GETSET_FLAG_CRC32 != 0 {
+            field::CRC32_SIZE
+        } else {
+            0
+        }
+    }
+
+    /// Length of the whole frame in the given layout, including the trailer
+    pub fn frame_len(version: GetSetVersion, flags: GetSetFlags, payload_len: usize) -> usize {
+        Self::buffer_len_for(version, payload_len) + Self::trailer_len(flags)
+    }
+
+    #[inline]
+    fn payload_start(&self) -> usize {
+        Self::header_len_for(self.version())
+    }
+
+    #[inline]
+    fn cipher_start(&self) -> usize {
+        self.payload_start() + usize::from(self.payload_size())
+    }
+
+    #[inline]
+    fn auth_start(&self) -> usize {
+        self.cipher_start() + Self::cipher_len(self.flags())
+    }
+
+    #[inline]
+    fn checksum_start(&self) -> usize {
+        self.auth_start() + Self::auth_len(self.flags())
+    }
+
+    /// The cipher nonce, if the payload is encrypted
+    #[inline]
+    pub fn cipher_nonce(&self) -> Option<&[u8]> {
+        if Self::cipher_len(self.flags()) == 0 {
+            return None;
+        }
+        let data = self.buffer.as_ref();
+        let start = self.cipher_start();
+        let trailer = &data[start..start + field::CIPHER_SIZE];
+        Some(&trailer[field::CIPHER_NONCE])
+    }
+
+    /// The cipher tag, if the payload is encrypted
+    #[inline]
+    pub fn cipher_tag(&self) -> Option<&[u8]> {
+        if Self::cipher_len(self.flags()) == 0 {
+            return None;
+        }
+        let data = self.buffer.as_ref();
+        let start = self.cipher_start();
+        let trailer = &data[start..start + field::CIPHER_SIZE];
+        Some(&trailer[field::CIPHER_TAG])
+    }
+
+    /// The auth counter, if the frame carries an auth trailer
+    #[inline]
+    pub fn auth_counter(&self) -> Option<u64> {
+        if Self::auth_len(self.flags()) == 0 {
+            return None;
+        }
+        let data = self.buffer.as_ref();
+        let start = self.auth_start();
+        let trailer = &data[start..start + field::AUTH_SIZE];
+        Some(LittleEndian::read_u64(&trailer[field::AUTH_COUNTER]))
+    }
+
+    /// The auth tag, if the frame carries an auth trailer
+    #[inline]
+    pub fn auth_tag(&self) -> Option<&[u8]> {
+        if Self::auth_len(self.flags()) == 0 {
+            return None;
+        }
+        let data = self.buffer.as_ref();
+        let start = self.auth_start();
+        let trailer = &data[start..start + field::AUTH_SIZE];
+        Some(&trailer[field::AUTH_TAG])
+    }
+
+    /// The bytes covered by the auth tag, the header, payload,
+    /// cipher trailer and counter
+    #[inline]
+    pub fn auth_data(&self) -> &[u8] {
+        let data = self.buffer.as_ref();
+        &data[..self.auth_start() + field::AUTH_COUNTER.end]
+    }
+
+    /// The CRC32 trailer, if the frame carries one
+    #[inline]
+    pub fn checksum(&self) -> Option<u32> {
+        if Self::crc32_len(self.flags()) == 0 {
+            return None;
+        }
+        let data = self.buffer.as_ref();
+        let start = self.checksum_start();
+        Some(LittleEndian::read_u32(
+            &data[start..start + field::CRC32_SIZE],
+        ))
+    }
+
+    /// CRC32 of everything before the CRC32 trailer
+    pub fn compute_checksum(&self) -> u32 {
+        let data = self.buffer.as_ref();
+        crc32(&data[..self.checksum_start()])
+    }
+
     #[inline]
     pub fn preamble(&self) -> u32 {
         let data = self.buffer.as_ref();
@@ -88,15 +287,21 @@ impl<T: AsRef<[u8]>> Frame<T> {
     }
 
     #[inline]
-    pub fn op(&self) -> GetSetOp {
+    pub fn op(&self) -> Result<GetSetOp, Error> {
+        GetSetOp::try_from(self.raw_op())
+    }
+
+    /// The op field as received, it may not be a valid op
+    #[inline]
+    pub fn raw_op(&self) -> u8 {
         let data = self.buffer.as_ref();
-        GetSetOp::from(data[field::OP])
+        data[field::OP]
     }
 
     #[inline]
-    pub fn payload_type(&self) -> GetSetPayloadType {
+    pub fn payload_type(&self) -> Result<GetSetPayloadType, Error> {
         let data = self.buffer.as_ref();
-        GetSetPayloadType::from(data[field::PAYLOAD_TYPE])
+        GetSetPayloadType::try_from(data[field::PAYLOAD_TYPE])
     }
 
     #[inline]
@@ -104,13 +309,34 @@ impl<T: AsRef<[u8]>> Frame<T> {
         let data = self.buffer.as_ref();
         LittleEndian::read_u16(&data[field::PAYLOAD_SIZE])
     }
+
+    /// Sequence number, v1 frames don't carry one and read as zero
+    #[inline]
+    pub fn sequence(&self) -> GetSetSequence {
+        let data = self.buffer.as_ref();
+        if self.version() == GETSET_VERSION_2 {
+            LittleEndian::read_u32(&data[field::V2_SEQUENCE])
+        } else {
+            0
+        }
+    }
 }
 
 impl<'a, T: AsRef<[u8]> + ?Sized> Frame<&'a T> {
     #[inline]
     pub fn payload(&self) -> &'a [u8] {
+        let start = self.payload_start();
+        let data = self.buffer.as_ref();
+        &data[start..]
+    }
+
+    /// The payload bounded by the payload size field
+    #[inline]
+    pub fn checked_payload(&self) -> Result<&'a [u8], Error> {
+        let start = self.payload_start();
         let data = self.buffer.as_ref();
-        &data[field::PAYLOAD]
+        let size = usize::from(self.payload_size());
+        data.get(start..start + size).ok_or(Error::WireTruncated)
     }
 }
 
@@ -157,10 +383,61 @@ impl<T: AsRef<[u8]> + AsMut<[u8]>> Frame<T> {
         LittleEndian::write_u16(&mut data[field::PAYLOAD_SIZE], value);
     }
 
+    /// No-op on v1 frames, the version must be set first
+    #[inline]
+    pub fn set_sequence(&mut self, value: GetSetSequence) {
+        if self.version() == GETSET_VERSION_2 {
+            let data = self.buffer.as_mut();
+            LittleEndian::write_u32(&mut data[field::V2_SEQUENCE], value);
+        }
+    }
+
+    /// The version must be set first, it determines where the payload starts
     #[inline]
     pub fn payload_mut(&mut self) -> &mut [u8] {
+        let start = self.payload_start();
+        let data = self.buffer.as_mut();
+        &mut data[start..]
+    }
+
+    /// Split into the header, payload and cipher trailer, the encrypted flag
+    /// and payload size must be set first
+    pub(crate) fn cipher_parts_mut(&mut self) -> (&[u8], &mut [u8], &mut [u8]) {
+        let payload_start = self.payload_start();
+        let cipher_start = self.cipher_start();
+        let data = self.buffer.as_mut();
+        let (header, rest) = data.split_at_mut(payload_start);
+        let (payload, rest) = rest.split_at_mut(cipher_start - payload_start);
+        (header, payload, &mut rest[..field::CIPHER_SIZE])
+    }
+
+    /// Write the auth counter, the auth flag and payload size must be set first
+    #[inline]
+    pub fn set_auth_counter(&mut self, value: u64) {
+        let start = self.auth_start();
+        let data = self.buffer.as_mut();
+        let trailer = &mut data[start..start + field::AUTH_SIZE];
+        LittleEndian::write_u64(&mut trailer[field::AUTH_COUNTER], value);
+    }
+
+    /// The auth flag and payload size must be set first
+    #[inline]
+    pub fn auth_tag_mut(&mut self) -> &mut [u8] {
+        let start = self.auth_start();
         let data = self.buffer.as_mut();
-        &mut data[field::PAYLOAD]
+        let trailer = &mut data[start..start + field::AUTH_SIZE];
+        &mut trailer[field::AUTH_TAG]
+    }
+
+    /// Write the CRC32 trailer if the flag is set, no-op otherwise,
+    /// the header and payload must be filled in first
+    pub fn fill_checksum(&mut self) {
+        if Self::crc32_len(self.flags()) != 0 {
+            let crc = self.compute_checksum();
+            let start = self.checksum_start();
+            let data = self.buffer.as_mut();
+            LittleEndian::write_u32(&mut data[start..start + field::CRC32_SIZE], crc);
+        }
     }
 }
 
@@ -174,15 +451,23 @@ impl<T: AsRef<[u8]>> fmt::Display for Frame<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "GetSetFrame {{ pa: 0x{:X} nid: 0x{:X} f: 0x{:X} v: {} op: {} pt: {} ps: {}}}",
+            "GetSetFrame {{ pa: 0x{:X} nid: 0x{:X} f: 0x{:X} v: {} op: ",
             self.preamble(),
             self.node_id(),
             self.flags(),
             self.version(),
-            self.op(),
-            self.payload_type(),
-            self.payload_size(),
-        )
+        )?;
+        let data = self.buffer.as_ref();
+        match self.op() {
+            Ok(op) => write!(f, "{}", op)?,
+            Err(_) => write!(f, "Invalid({})", self.raw_op())?,
+        }
+        write!(f, " pt: ")?;
+        match self.payload_type() {
+            Ok(pt) => write!(f, "{}", pt)?,
+            Err(_) => write!(f, "Invalid({})", data[field::PAYLOAD_TYPE])?,
+        }
+        write!(f, " ps: {}}}", self.payload_size())
     }
 }
 
@@ -243,9 +528,118 @@ mod tests {
         assert_eq!(f.node_id(), 0x01);
         assert_eq!(f.flags(), 0);
         assert_eq!(f.version(), 1);
-        assert_eq!(f.op(), GetSetOp::Get);
-        assert_eq!(f.payload_type(), GetSetPayloadType::ParameterIdListPacket);
+        assert_eq!(f.op(), Ok(GetSetOp::Get));
+        assert_eq!(
+            f.payload_type(),
+            Ok(GetSetPayloadType::ParameterIdListPacket)
+        );
         assert_eq!(f.payload_size(), PAYLOAD_BYTES.len().try_into().unwrap());
         assert_eq!(f.payload(), &PAYLOAD_BYTES[..]);
     }
+
+    #[test]
+    fn deconstruct_invalid() {
+        let mut bytes = FRAME_BYTES;
+        bytes[13] = 0xEE;
+        bytes[14] = 0xEE;
+        let f = Frame::new_checked(&bytes[..]).unwrap();
+        assert_eq!(f.op(), Err(Error::WireInvalidOp));
+        assert_eq!(f.payload_type(), Err(Error::WireInvalidPayloadType));
+    }
+
+    #[test]
+    fn payload_truncated() {
+        assert_eq!(
+            Frame::new_checked(&FRAME_BYTES[..45]).err(),
+            Some(Error::WireTruncated)
+        );
+        let f = Frame::new_unchecked(&FRAME_BYTES[..45]);
+        assert_eq!(f.checked_payload(), Err(Error::WireTruncated));
+    }
+
+    #[test]
+    fn v2_header() {
+        assert_eq!(Frame::<&[u8]>::header_len_for(1), 17);
+        assert_eq!(Frame::<&[u8]>::header_len_for(2), 21);
+        assert_eq!(Frame::<&[u8]>::buffer_len_for(2, 29), 21 + 29);
+
+        let mut bytes = [0xFF; 50];
+        let mut f = Frame::new_unchecked(&mut bytes[..]);
+        f.set_preamble(PREAMBLE_WORD);
+        f.set_node_id(0x01);
+        f.set_flags(0);
+        f.set_version(2);
+        f.set_sequence(0x1234);
+        f.set_op(GetSetOp::Get);
+        f.set_payload_type(GetSetPayloadType::ParameterIdListPacket);
+        f.set_payload_size(PAYLOAD_BYTES.len().try_into().unwrap());
+        f.payload_mut().copy_from_slice(&PAYLOAD_BYTES[..]);
+        assert_eq!(&bytes[..12], &FRAME_BYTES[..12]);
+        assert_eq!(bytes[12], 2);
+        assert_eq!(&bytes[13..17], &FRAME_BYTES[13..17]);
+        assert_eq!(&bytes[17..21], &[0x34, 0x12, 0x00, 0x00]);
+
+        let f = Frame::new_checked(&bytes[..]).unwrap();
+        assert_eq!(f.version(), 2);
+        assert_eq!(f.sequence(), 0x1234);
+        assert_eq!(f.payload(), &PAYLOAD_BYTES[..]);
+        assert_eq!(
+            Frame::new_checked(&bytes[..49]).err(),
+            Some(Error::WireTruncated)
+        );
+        assert_eq!(
+            Frame::new_checked(&bytes[..20]).err(),
+            Some(Error::WireTruncated)
+        );
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let f = Frame::new_checked(&FRAME_BYTES[..]).unwrap();
+        assert_eq!(f.sequence(), 0);
+        for version in [0, 3, 0xFF].iter() {
+            let mut bytes = FRAME_BYTES;
+            bytes[12] = *version;
+            assert_eq!(
+                Frame::new_checked(&bytes[..]).err(),
+                Some(Error::WireUnsupportedVersion)
+            );
+        }
+    }
+
+    #[test]
+    fn crc32_trailer() {
+        assert_eq!(Frame::<&[u8]>::trailer_len(0), 0);
+        assert_eq!(
+            Frame::<&[u8]>::frame_len(1, GETSET_FLAG_CRC32, 29),
+            17 + 29 + 4
+        );
+
+        let mut bytes = [0xFF; 50];
+        bytes[..46].copy_from_slice(&FRAME_BYTES[..]);
+        let mut f = Frame::new_unchecked(&mut bytes[..]);
+        f.set_flags(GETSET_FLAG_CRC32);
+        f.fill_checksum();
+        let crc = crc32(&bytes[..46]);
+        assert_eq!(&bytes[46..], &crc.to_le_bytes()[..]);
+
+        let f = Frame::new_checked(&bytes[..]).unwrap();
+        assert_eq!(f.checksum(), Some(crc));
+        assert_eq!(f.check_checksum(), Ok(()));
+        assert_eq!(
+            Frame::new_checked(&bytes[..49]).err(),
+            Some(Error::WireTruncated)
+        );
+
+        bytes[20] ^= 0x01;
+        assert_eq!(
+            Frame::new_checked(&bytes[..]).err(),
+            Some(Error::WireChecksum)
+        );
+
+        // No trailer without the flag
+        let f = Frame::new_checked(&FRAME_BYTES[..]).unwrap();
+        assert_eq!(f.checksum(), None);
+        assert_eq!(f.check_checksum(), Ok(()));
+    }
 }
@@ -1,7 +1,18 @@
+pub mod announce;
+pub mod capabilities;
+pub mod crc32;
+pub mod description;
+pub mod description_list;
+pub mod error;
 pub mod getset;
+pub mod page;
 pub mod parameter;
 pub mod parameter_id_list;
 pub mod parameter_list;
+pub mod ping;
+pub mod set_result_list;
+pub mod since;
+pub mod time;
 
 pub mod field {
     pub type Field = ::core::ops::Range<usize>;
@@ -0,0 +1,90 @@
+use crate::Error;
+use byteorder::{ByteOrder, LittleEndian};
+
+#[derive(Debug, Clone)]
+pub struct Packet<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+mod field {
+    use crate::wire::field::*;
+
+    /// Index of the first parameter of the requested page
+    pub const OFFSET: Field = 0..2;
+}
+
+impl<T: AsRef<[u8]>> Packet<T> {
+    pub fn new_unchecked(buffer: T) -> Packet<T> {
+        Packet { buffer }
+    }
+
+    pub fn new_checked(buffer: T) -> Result<Packet<T>, Error> {
+        let packet = Self::new_unchecked(buffer);
+        packet.check_len()?;
+        Ok(packet)
+    }
+
+    pub fn check_len(&self) -> Result<(), Error> {
+        let len = self.buffer.as_ref().len();
+        if len < Self::buffer_len() {
+            Err(Error::WireTruncated)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    /// The packet is fixed size
+    pub fn buffer_len() -> usize {
+        field::OFFSET.end
+    }
+
+    #[inline]
+    pub fn offset(&self) -> u16 {
+        let data = self.buffer.as_ref();
+        LittleEndian::read_u16(&data[field::OFFSET])
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> Packet<T> {
+    #[inline]
+    pub fn set_offset(&mut self, value: u16) {
+        let data = self.buffer.as_mut();
+        LittleEndian::write_u16(&mut data[field::OFFSET], value);
+    }
+}
+
+impl<T: AsRef<[u8]>> AsRef<[u8]> for Packet<T> {
+    fn as_ref(&self) -> &[u8] {
+        self.buffer.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    static BYTES: [u8; 2] = [0x40, 0x01];
+
+    #[test]
+    fn construct() {
+        let mut bytes = [0xFF; 2];
+        let mut p = Packet::new_checked(&mut bytes[..]).unwrap();
+        p.set_offset(0x0140);
+        assert_eq!(&p.into_inner()[..], &BYTES[..]);
+    }
+
+    #[test]
+    fn deconstruct() {
+        let p = Packet::new_checked(&BYTES[..]).unwrap();
+        assert_eq!(p.offset(), 0x0140);
+        assert_eq!(
+            Packet::new_checked(&BYTES[..1]).err(),
+            Some(Error::WireTruncated)
+        );
+    }
+}
@@ -1,6 +1,10 @@
 use crate::value::TypeId;
-use crate::{Error, ParameterFlags, ParameterId, ParameterValue};
+use crate::{
+    Error, ParameterFlags, ParameterId, ParameterValue, ParameterValueArray, ParameterValueBytes,
+    ParameterValueStr,
+};
 use byteorder::{ByteOrder, LittleEndian};
+use core::convert::TryFrom;
 use static_assertions::assert_eq_size;
 
 assert_eq_size!(u32, ParameterId);
@@ -73,26 +77,67 @@ impl<T: AsRef<[u8]>> Packet<T> {
     }
 
     #[inline]
-    pub(crate) fn value_type_id(&self) -> TypeId {
+    pub(crate) fn value_type_id(&self) -> Result<TypeId, Error> {
         let data = self.buffer.as_ref();
-        TypeId::from(data[field::VALUE_TYPE_ID])
+        TypeId::try_from(data[field::VALUE_TYPE_ID])
     }
 
+    /// Size of the value field, including the length prefix of variable
+    /// length types
     #[inline]
-    pub fn value(&self) -> ParameterValue {
+    pub fn value_wire_size(&self) -> Result<usize, Error> {
         let data = self.buffer.as_ref();
-        match TypeId::from(data[field::VALUE_TYPE_ID]) {
+        match self.value_type_id()? {
+            TypeId::Str | TypeId::Bytes => {
+                let len = data.get(field::VALUE.start).ok_or(Error::WireTruncated)?;
+                Ok(1 + usize::from(*len))
+            }
+            TypeId::Array => {
+                let elem = data.get(field::VALUE.start).ok_or(Error::WireTruncated)?;
+                let count = data
+                    .get(field::VALUE.start + 1)
+                    .ok_or(Error::WireTruncated)?;
+                Ok(2 + usize::from(*count) * TypeId::try_from(*elem)?.wire_size())
+            }
+            t => Ok(t.wire_size()),
+        }
+    }
+
+    /// Decode the value, the whole value field must be present in the buffer
+    #[inline]
+    pub fn value(&self) -> Result<ParameterValue, Error> {
+        let data = self.buffer.as_ref();
+        let type_id = self.value_type_id()?;
+        let end = field::VALUE.start + self.value_wire_size()?;
+        let value = data
+            .get(field::VALUE.start..end)
+            .ok_or(Error::WireTruncated)?;
+        Ok(match type_id {
             TypeId::None => ParameterValue::None,
             TypeId::Notification => ParameterValue::Notification,
-            TypeId::Bool => ParameterValue::Bool(data[field::VALUE.start] != 0),
-            TypeId::U8 => ParameterValue::U8(data[field::VALUE.start]),
-            TypeId::I8 => ParameterValue::I8(data[field::VALUE.start] as _),
-            TypeId::U32 => ParameterValue::U32(LittleEndian::read_u32(&data[field::VALUE])),
-            TypeId::I32 => ParameterValue::I32(LittleEndian::read_i32(&data[field::VALUE])),
-            TypeId::U64 => ParameterValue::U64(LittleEndian::read_u64(&data[field::VALUE])),
-            TypeId::I64 => ParameterValue::I64(LittleEndian::read_i64(&data[field::VALUE])),
-            TypeId::F32 => ParameterValue::F32(LittleEndian::read_f32(&data[field::VALUE])),
-        }
+            TypeId::Bool => ParameterValue::Bool(value[0] != 0),
+            TypeId::U8 => ParameterValue::U8(value[0]),
+            TypeId::I8 => ParameterValue::I8(value[0] as _),
+            TypeId::U16 => ParameterValue::U16(LittleEndian::read_u16(value)),
+            TypeId::I16 => ParameterValue::I16(LittleEndian::read_i16(value)),
+            TypeId::U32 => ParameterValue::U32(LittleEndian::read_u32(value)),
+            TypeId::I32 => ParameterValue::I32(LittleEndian::read_i32(value)),
+            TypeId::U64 => ParameterValue::U64(LittleEndian::read_u64(value)),
+            TypeId::I64 => ParameterValue::I64(LittleEndian::read_i64(value)),
+            TypeId::F32 => ParameterValue::F32(LittleEndian::read_f32(value)),
+            TypeId::F64 => ParameterValue::F64(LittleEndian::read_f64(value)),
+            TypeId::Str => ParameterValue::Str(ParameterValueStr::from_utf8(&value[1..])?),
+            TypeId::Bytes => ParameterValue::Bytes(ParameterValueBytes::from_slice(&value[1..])?),
+            TypeId::Array => {
+                let elem = TypeId::try_from(value[0])?;
+                let count = usize::from(value[1]);
+                ParameterValue::Array(ParameterValueArray::from_le_bytes(
+                    elem,
+                    count,
+                    &value[2..],
+                )?)
+            }
+        })
     }
 }
 
@@ -131,11 +176,33 @@ impl<T: AsRef<[u8]> + AsMut<[u8]>> Packet<T> {
             ParameterValue::I8(inner) => {
                 data[field::VALUE.start] = inner as _;
             }
+            ParameterValue::U16(inner) => LittleEndian::write_u16(&mut data[field::VALUE], inner),
+            ParameterValue::I16(inner) => LittleEndian::write_i16(&mut data[field::VALUE], inner),
             ParameterValue::U32(inner) => LittleEndian::write_u32(&mut data[field::VALUE], inner),
             ParameterValue::I32(inner) => LittleEndian::write_i32(&mut data[field::VALUE], inner),
             ParameterValue::U64(inner) => LittleEndian::write_u64(&mut data[field::VALUE], inner),
             ParameterValue::I64(inner) => LittleEndian::write_i64(&mut data[field::VALUE], inner),
             ParameterValue::F32(inner) => LittleEndian::write_f32(&mut data[field::VALUE], inner),
+            ParameterValue::F64(inner) => LittleEndian::write_f64(&mut data[field::VALUE], inner),
+            ParameterValue::Str(inner) => {
+                let bytes = inner.as_bytes();
+                data[field::VALUE.start] = bytes.len() as u8;
+                data[field::VALUE.start + 1..field::VALUE.start + 1 + bytes.len()]
+                    .copy_from_slice(bytes);
+            }
+            ParameterValue::Bytes(inner) => {
+                let bytes = inner.as_slice();
+                data[field::VALUE.start] = bytes.len() as u8;
+                data[field::VALUE.start + 1..field::VALUE.start + 1 + bytes.len()]
+                    .copy_from_slice(bytes);
+            }
+            ParameterValue::Array(inner) => {
+                let bytes = inner.as_le_bytes();
+                data[field::VALUE.start] = inner.element_type_id().as_u8();
+                data[field::VALUE.start + 1] = inner.len() as u8;
+                data[field::VALUE.start + 2..field::VALUE.start + 2 + bytes.len()]
+                    .copy_from_slice(bytes);
+            }
         }
     }
 }
@@ -202,6 +269,36 @@ mod tests {
         0x00, 0x09, 0xB6, 0xF3, 0x9D, 0xBF,
     ];
 
+    static U16_PARAM_BYTES: [u8; 19] = [
+        0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x0C, 0x00, 0xFF,
+    ];
+
+    static I16_PARAM_BYTES: [u8; 19] = [
+        0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x0D, 0x2E, 0xFB,
+    ];
+
+    static F64_PARAM_BYTES: [u8; 25] = [
+        0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x0E, 0x58, 0x39, 0xB4, 0xC8, 0x76, 0xBE, 0xF3, 0xBF,
+    ];
+
+    static STR_PARAM_BYTES: [u8; 22] = [
+        0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x0A, 0x04, 0x61, 0x62, 0x63, 0x64,
+    ];
+
+    static BYTES_PARAM_BYTES: [u8; 21] = [
+        0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x0B, 0x03, 0x0A, 0x0B, 0xFF,
+    ];
+
+    static ARRAY_PARAM_BYTES: [u8; 23] = [
+        0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x0F, 0x0C, 0x02, 0x01, 0x00, 0x00, 0xFF,
+    ];
+
     #[test]
     fn header_len() {
         assert_eq!(Packet::<&[u8]>::header_len(), 17);
@@ -226,7 +323,7 @@ mod tests {
         assert_eq!(f.local_time_ms(), 255);
         assert_eq!(f.id(), 0x0A_u32.into());
         assert_eq!(f.flags(), 0_u32.into());
-        assert_eq!(f.value(), ParameterValue::None);
+        assert_eq!(f.value(), Ok(ParameterValue::None));
     }
 
     #[test]
@@ -247,7 +344,7 @@ mod tests {
         assert_eq!(f.local_time_ms(), 255);
         assert_eq!(f.id(), 0x0A_u32.into());
         assert_eq!(f.flags(), 0_u32.into());
-        assert_eq!(f.value(), ParameterValue::Notification);
+        assert_eq!(f.value(), Ok(ParameterValue::Notification));
     }
 
     #[test]
@@ -268,7 +365,7 @@ mod tests {
         assert_eq!(f.local_time_ms(), 255);
         assert_eq!(f.id(), 0x0A_u32.into());
         assert_eq!(f.flags(), 0_u32.into());
-        assert_eq!(f.value(), ParameterValue::Bool(true));
+        assert_eq!(f.value(), Ok(ParameterValue::Bool(true)));
     }
 
     #[test]
@@ -289,7 +386,7 @@ mod tests {
         assert_eq!(f.local_time_ms(), 255);
         assert_eq!(f.id(), 0x0A_u32.into());
         assert_eq!(f.flags(), 0_u32.into());
-        assert_eq!(f.value(), ParameterValue::U8(0xBA));
+        assert_eq!(f.value(), Ok(ParameterValue::U8(0xBA)));
     }
 
     #[test]
@@ -310,7 +407,7 @@ mod tests {
         assert_eq!(f.local_time_ms(), 255);
         assert_eq!(f.id(), 0x0A_u32.into());
         assert_eq!(f.flags(), 0_u32.into());
-        assert_eq!(f.value(), ParameterValue::I8(0x0A));
+        assert_eq!(f.value(), Ok(ParameterValue::I8(0x0A)));
     }
 
     #[test]
@@ -331,7 +428,7 @@ mod tests {
         assert_eq!(f.local_time_ms(), 255);
         assert_eq!(f.id(), 0x0A_u32.into());
         assert_eq!(f.flags(), 0_u32.into());
-        assert_eq!(f.value(), ParameterValue::U32(0xFF_00_FF_00));
+        assert_eq!(f.value(), Ok(ParameterValue::U32(0xFF_00_FF_00)));
     }
 
     #[test]
@@ -352,7 +449,7 @@ mod tests {
         assert_eq!(f.local_time_ms(), 255);
         assert_eq!(f.id(), 0x0A_u32.into());
         assert_eq!(f.flags(), 0_u32.into());
-        assert_eq!(f.value(), ParameterValue::I32(-1234));
+        assert_eq!(f.value(), Ok(ParameterValue::I32(-1234)));
     }
 
     #[test]
@@ -373,7 +470,7 @@ mod tests {
         assert_eq!(f.local_time_ms(), 255);
         assert_eq!(f.id(), 0x0A_u32.into());
         assert_eq!(f.flags(), 0_u32.into());
-        assert_eq!(f.value(), ParameterValue::U64(0xFF_00_FF_00));
+        assert_eq!(f.value(), Ok(ParameterValue::U64(0xFF_00_FF_00)));
     }
 
     #[test]
@@ -394,7 +491,7 @@ mod tests {
         assert_eq!(f.local_time_ms(), 255);
         assert_eq!(f.id(), 0x0A_u32.into());
         assert_eq!(f.flags(), 0_u32.into());
-        assert_eq!(f.value(), ParameterValue::I64(0xFF_00_FF_00));
+        assert_eq!(f.value(), Ok(ParameterValue::I64(0xFF_00_FF_00)));
     }
 
     #[test]
@@ -415,9 +512,196 @@ mod tests {
         assert_eq!(f.local_time_ms(), 255);
         assert_eq!(f.id(), 0x0A_u32.into());
         assert_eq!(f.flags(), 0_u32.into());
-        match f.value() {
+        match f.value().unwrap() {
             ParameterValue::F32(val) => assert_relative_eq!(val, -1.234),
             _ => panic!("Unexpected value"),
         }
     }
+
+    #[test]
+    fn construct_str() {
+        let mut bytes = [0xFF; 22];
+        let mut f = Packet::new_unchecked(&mut bytes);
+        assert_eq!(f.check_len(), Ok(()));
+        f.set_local_time_ms(255);
+        f.set_id(0x0A_u32.into());
+        f.set_flags(0_u32.into());
+        f.set_value(ParameterValue::Str(
+            ParameterValueStr::from_utf8(b"abcd").unwrap(),
+        ));
+        assert_eq!(&f.into_inner()[..], &STR_PARAM_BYTES[..]);
+    }
+
+    #[test]
+    fn deconstruct_str() {
+        let f = Packet::new_checked(&STR_PARAM_BYTES[..]).unwrap();
+        assert_eq!(f.local_time_ms(), 255);
+        assert_eq!(f.id(), 0x0A_u32.into());
+        assert_eq!(f.flags(), 0_u32.into());
+        assert_eq!(f.value_wire_size(), Ok(5));
+        assert_eq!(
+            f.value(),
+            Ok(ParameterValue::Str(
+                ParameterValueStr::from_utf8(b"abcd").unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    fn deconstruct_str_truncated() {
+        let f = Packet::new_checked(&STR_PARAM_BYTES[..20]).unwrap();
+        assert_eq!(f.value(), Err(Error::WireTruncated));
+    }
+
+    #[test]
+    fn construct_bytes() {
+        let mut bytes = [0xFF; 21];
+        let mut f = Packet::new_unchecked(&mut bytes);
+        assert_eq!(f.check_len(), Ok(()));
+        f.set_local_time_ms(255);
+        f.set_id(0x0A_u32.into());
+        f.set_flags(0_u32.into());
+        f.set_value(ParameterValue::Bytes(
+            ParameterValueBytes::from_slice(&[0x0A, 0x0B, 0xFF]).unwrap(),
+        ));
+        assert_eq!(&f.into_inner()[..], &BYTES_PARAM_BYTES[..]);
+    }
+
+    #[test]
+    fn deconstruct_bytes() {
+        let f = Packet::new_checked(&BYTES_PARAM_BYTES[..]).unwrap();
+        assert_eq!(f.local_time_ms(), 255);
+        assert_eq!(f.id(), 0x0A_u32.into());
+        assert_eq!(f.flags(), 0_u32.into());
+        assert_eq!(f.value_wire_size(), Ok(4));
+        assert_eq!(
+            f.value(),
+            Ok(ParameterValue::Bytes(
+                ParameterValueBytes::from_slice(&[0x0A, 0x0B, 0xFF]).unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    fn construct_u16() {
+        let mut bytes = [0xFF; 19];
+        let mut f = Packet::new_unchecked(&mut bytes);
+        assert_eq!(f.check_len(), Ok(()));
+        f.set_local_time_ms(255);
+        f.set_id(0x0A_u32.into());
+        f.set_flags(0_u32.into());
+        f.set_value(ParameterValue::U16(0xFF_00));
+        assert_eq!(&f.into_inner()[..], &U16_PARAM_BYTES[..]);
+    }
+
+    #[test]
+    fn deconstruct_u16() {
+        let f = Packet::new_checked(&U16_PARAM_BYTES[..]).unwrap();
+        assert_eq!(f.local_time_ms(), 255);
+        assert_eq!(f.id(), 0x0A_u32.into());
+        assert_eq!(f.flags(), 0_u32.into());
+        assert_eq!(f.value(), Ok(ParameterValue::U16(0xFF_00)));
+    }
+
+    #[test]
+    fn construct_i16() {
+        let mut bytes = [0xFF; 19];
+        let mut f = Packet::new_unchecked(&mut bytes);
+        assert_eq!(f.check_len(), Ok(()));
+        f.set_local_time_ms(255);
+        f.set_id(0x0A_u32.into());
+        f.set_flags(0_u32.into());
+        f.set_value(ParameterValue::I16(-1234));
+        assert_eq!(&f.into_inner()[..], &I16_PARAM_BYTES[..]);
+    }
+
+    #[test]
+    fn deconstruct_i16() {
+        let f = Packet::new_checked(&I16_PARAM_BYTES[..]).unwrap();
+        assert_eq!(f.local_time_ms(), 255);
+        assert_eq!(f.id(), 0x0A_u32.into());
+        assert_eq!(f.flags(), 0_u32.into());
+        assert_eq!(f.value(), Ok(ParameterValue::I16(-1234)));
+    }
+
+    #[test]
+    fn construct_f64() {
+        let mut bytes = [0xFF; 25];
+        let mut f = Packet::new_unchecked(&mut bytes);
+        assert_eq!(f.check_len(), Ok(()));
+        f.set_local_time_ms(255);
+        f.set_id(0x0A_u32.into());
+        f.set_flags(0_u32.into());
+        f.set_value(ParameterValue::F64(-1.234));
+        assert_eq!(&f.into_inner()[..], &F64_PARAM_BYTES[..]);
+    }
+
+    #[test]
+    fn deconstruct_f64() {
+        let f = Packet::new_checked(&F64_PARAM_BYTES[..]).unwrap();
+        assert_eq!(f.local_time_ms(), 255);
+        assert_eq!(f.id(), 0x0A_u32.into());
+        assert_eq!(f.flags(), 0_u32.into());
+        match f.value().unwrap() {
+            ParameterValue::F64(val) => assert_relative_eq!(val, -1.234),
+            _ => panic!("Unexpected value"),
+        }
+    }
+
+    #[test]
+    fn construct_array() {
+        let mut bytes = [0xFF; 23];
+        let mut f = Packet::new_unchecked(&mut bytes);
+        assert_eq!(f.check_len(), Ok(()));
+        f.set_local_time_ms(255);
+        f.set_id(0x0A_u32.into());
+        f.set_flags(0_u32.into());
+        let mut a = ParameterValueArray::new(TypeId::U16).unwrap();
+        a.push(ParameterValue::U16(1)).unwrap();
+        a.push(ParameterValue::U16(0xFF_00)).unwrap();
+        f.set_value(ParameterValue::Array(a));
+        assert_eq!(&f.into_inner()[..], &ARRAY_PARAM_BYTES[..]);
+    }
+
+    #[test]
+    fn deconstruct_array() {
+        let f = Packet::new_checked(&ARRAY_PARAM_BYTES[..]).unwrap();
+        assert_eq!(f.local_time_ms(), 255);
+        assert_eq!(f.id(), 0x0A_u32.into());
+        assert_eq!(f.flags(), 0_u32.into());
+        assert_eq!(f.value_wire_size(), Ok(6));
+        let value = f.value().unwrap();
+        let a = value.as_array();
+        assert_eq!(a.element_type_id(), TypeId::U16);
+        assert_eq!(a.len(), 2);
+        assert_eq!(a.get(0), Some(ParameterValue::U16(1)));
+        assert_eq!(a.get(1), Some(ParameterValue::U16(0xFF_00)));
+    }
+
+    #[test]
+    fn deconstruct_array_truncated() {
+        let f = Packet::new_checked(&ARRAY_PARAM_BYTES[..22]).unwrap();
+        assert_eq!(f.value(), Err(Error::WireTruncated));
+    }
+
+    #[test]
+    fn deconstruct_invalid_type() {
+        let mut bytes = U8_PARAM_BYTES;
+        bytes[16] = 0xEE;
+        let f = Packet::new_checked(&bytes[..]).unwrap();
+        assert_eq!(f.value(), Err(Error::WireInvalidValueType));
+
+        let mut bytes = ARRAY_PARAM_BYTES;
+        bytes[17] = 0xEE;
+        let f = Packet::new_checked(&bytes[..]).unwrap();
+        assert_eq!(f.value(), Err(Error::WireInvalidValueType));
+    }
+
+    #[test]
+    fn deconstruct_scalar_truncated() {
+        let f = Packet::new_checked(&U32_PARAM_BYTES[..19]).unwrap();
+        assert_eq!(f.value(), Err(Error::WireTruncated));
+        let f = Packet::new_checked(&BOOL_PARAM_BYTES[..17]).unwrap();
+        assert_eq!(f.value(), Err(Error::WireTruncated));
+    }
 }
@@ -61,10 +61,59 @@ impl<T: AsRef<[u8]>> Packet<T> {
             return Err(Error::WireIndexOutOfBounds);
         }
         let data = self.buffer.as_ref();
-        let offset = index * mem::size_of::<ParameterId>();
-        Ok(ParameterId::from(LittleEndian::read_u32(
-            &data[field::IDS.start + offset..],
-        )))
+        let start = field::IDS.start + index * mem::size_of::<ParameterId>();
+        let bytes = data
+            .get(start..start + mem::size_of::<ParameterId>())
+            .ok_or(Error::WireTruncated)?;
+        Ok(ParameterId::from(LittleEndian::read_u32(bytes)))
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Packet<&'a T> {
+    /// Borrowing iterator over the parameter IDs
+    #[inline]
+    pub fn iter(&self) -> Iter<'a> {
+        let data = self.buffer.as_ref();
+        Iter {
+            data,
+            index: 0,
+            count: usize::from(self.count()),
+        }
+    }
+}
+
+/// Iterator over the IDs of a `ParameterIdListPacket`,
+/// stops after the first error
+#[derive(Debug, Clone, Default)]
+pub struct Iter<'a> {
+    data: &'a [u8],
+    index: usize,
+    count: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Result<ParameterId, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+        let start = field::IDS.start + self.index * mem::size_of::<ParameterId>();
+        let result = self
+            .data
+            .get(start..start + mem::size_of::<ParameterId>())
+            .map(|bytes| ParameterId::from(LittleEndian::read_u32(bytes)))
+            .ok_or(Error::WireTruncated);
+        self.index = if result.is_ok() {
+            self.index + 1
+        } else {
+            self.count
+        };
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.count - self.index))
     }
 }
 
@@ -130,8 +179,23 @@ mod tests {
     fn deconstruct() {
         let p = Packet::new_checked(&BYTES[..]).unwrap();
         assert_eq!(p.count(), PARAM_IDS.len().try_into().unwrap());
-        for index in 0..PARAM_IDS.len() {
-            assert_eq!(p.id_at(index), Ok(PARAM_IDS[index]));
+        for (index, id) in PARAM_IDS.iter().enumerate() {
+            assert_eq!(p.id_at(index), Ok(*id));
         }
     }
+
+    #[test]
+    fn iter() {
+        let p = Packet::new_checked(&BYTES[..]).unwrap();
+        let mut iter = p.iter();
+        for id in PARAM_IDS.iter() {
+            assert_eq!(iter.next(), Some(Ok(*id)));
+        }
+        assert_eq!(iter.next(), None);
+
+        let p = Packet::new_checked(&BYTES[..BYTES.len() - 1]).unwrap();
+        let mut iter = p.iter().skip(PARAM_IDS.len() - 1);
+        assert_eq!(iter.next(), Some(Err(Error::WireTruncated)));
+        assert_eq!(iter.next(), None);
+    }
 }
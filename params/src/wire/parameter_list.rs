@@ -56,14 +56,73 @@ impl<T: AsRef<[u8]>> Packet<T> {
             return Err(Error::WireIndexOutOfBounds);
         }
         let data = self.buffer.as_ref();
-        let mut offset = field::PARAMS.start;
+        let mut iter = Iter::new(data, self.count());
         for _ in 0..index {
-            let f = ParameterPacket::new_checked(&data[offset..])?;
-            offset += ParameterPacket::<&[u8]>::buffer_len(f.value_type_id().wire_size());
+            iter.next_packet()?;
         }
 
         // Cursor now at index
-        Parameter::parse(&ParameterPacket::new_unchecked(&data[offset..]))
+        iter.next().unwrap_or(Err(Error::WireIndexOutOfBounds))
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Packet<&'a T> {
+    /// Borrowing iterator over the parameters, walks the buffer once
+    #[inline]
+    pub fn iter(&self) -> Iter<'a> {
+        let data = self.buffer.as_ref();
+        Iter::new(data, self.count())
+    }
+}
+
+/// Iterator over the parameters of a `ParameterListPacket`,
+/// stops after the first error
+#[derive(Debug, Clone, Default)]
+pub struct Iter<'a> {
+    data: &'a [u8],
+    offset: usize,
+    remaining: usize,
+}
+
+impl<'a> Iter<'a> {
+    fn new(data: &'a [u8], count: u8) -> Self {
+        Iter {
+            data,
+            offset: field::PARAMS.start,
+            remaining: usize::from(count),
+        }
+    }
+
+    /// Advance past the next parameter, returning its packet without decoding the value
+    fn next_packet(&mut self) -> Result<ParameterPacket<&'a [u8]>, Error> {
+        let rest = self.data.get(self.offset..).ok_or(Error::WireTruncated)?;
+        let packet = ParameterPacket::new_checked(rest)?;
+        let size = ParameterPacket::<&[u8]>::buffer_len(packet.value_wire_size()?);
+        let bytes = rest.get(..size).ok_or(Error::WireTruncated)?;
+        self.offset += size;
+        self.remaining -= 1;
+        Ok(ParameterPacket::new_unchecked(bytes))
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Result<Parameter, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let result = self
+            .next_packet()
+            .and_then(|packet| Parameter::parse(&packet));
+        if result.is_err() {
+            self.remaining = 0;
+        }
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
     }
 }
 
@@ -83,7 +142,7 @@ impl<T: AsRef<[u8]> + AsMut<[u8]>> Packet<T> {
         let mut offset = field::PARAMS.start;
         for _ in 0..index {
             let f = ParameterPacket::new_checked(&data[offset..])?;
-            offset += ParameterPacket::<&[u8]>::buffer_len(f.value_type_id().wire_size());
+            offset += ParameterPacket::<&[u8]>::buffer_len(f.value_wire_size()?);
         }
 
         // Cursor now at index
@@ -102,7 +161,7 @@ impl<T: AsRef<[u8]>> AsRef<[u8]> for Packet<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Parameter, ParameterFlags, ParameterId, ParameterValue};
+    use crate::{Parameter, ParameterFlags, ParameterId, ParameterValue, ParameterValueStr};
     use core::convert::TryInto;
     use pretty_assertions::assert_eq;
 
@@ -168,8 +227,63 @@ mod tests {
     fn deconstruct() {
         let p = Packet::new_checked(&BYTES[..]).unwrap();
         assert_eq!(p.count(), PARAMS.len().try_into().unwrap());
-        for index in 0..PARAMS.len() {
-            assert_eq!(p.parameter_at(index), Ok(PARAMS[index]));
+        for (index, param) in PARAMS.iter().enumerate() {
+            assert_eq!(p.parameter_at(index), Ok(*param));
+        }
+    }
+
+    #[test]
+    fn variable_length_values() {
+        let params = [
+            Parameter::new_with_value(
+                ParameterId::new(0x0A),
+                ParameterFlags(0),
+                ParameterValue::Str(ParameterValueStr::from_utf8(b"abc").unwrap()),
+            ),
+            Parameter::new_with_value(
+                ParameterId::new(0x0B),
+                ParameterFlags(0),
+                ParameterValue::Str(ParameterValueStr::default()),
+            ),
+            Parameter::new_with_value(
+                ParameterId::new(0x0C),
+                ParameterFlags(0),
+                ParameterValue::U32(1234),
+            ),
+        ];
+        let size = Packet::<&[u8]>::buffer_len(params.iter().map(|p| p.wire_size()).sum());
+        let mut bytes = [0xFF; 64];
+        let mut p = Packet::new_unchecked(&mut bytes[..size]);
+        p.set_count(params.len().try_into().unwrap());
+        for (index, param) in params.iter().enumerate() {
+            assert_eq!(p.set_parameter_at(index, *param), Ok(()));
+        }
+
+        let p = Packet::new_checked(&bytes[..size]).unwrap();
+        for (index, param) in params.iter().enumerate() {
+            assert_eq!(p.parameter_at(index), Ok(*param));
+        }
+    }
+
+    #[test]
+    fn iter() {
+        let p = Packet::new_checked(&BYTES[..]).unwrap();
+        let mut count = 0;
+        for (param, expected) in p.iter().zip(PARAMS.iter()) {
+            assert_eq!(param, Ok(*expected));
+            count += 1;
+        }
+        assert_eq!(count, PARAMS.len());
+    }
+
+    #[test]
+    fn iter_truncated() {
+        let p = Packet::new_checked(&BYTES[..BYTES.len() - 1]).unwrap();
+        let mut iter = p.iter();
+        for expected in PARAMS.iter().take(PARAMS.len() - 1) {
+            assert_eq!(iter.next(), Some(Ok(*expected)));
         }
+        assert_eq!(iter.next(), Some(Err(Error::WireTruncated)));
+        assert_eq!(iter.next(), None);
     }
 }
@@ -0,0 +1,141 @@
+use crate::Error;
+use byteorder::{ByteOrder, LittleEndian};
+
+#[derive(Debug, Clone)]
+pub struct Packet<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+mod field {
+    use crate::wire::field::*;
+
+    /// Node uptime in seconds, zero in requests
+    pub const UPTIME: Field = 0..4;
+    /// Node local time in milliseconds, zero in requests
+    pub const TIME_MS: Field = 4..12;
+    pub const DATA_LEN: usize = 12;
+    /// Opaque data echoed by the node
+    pub const DATA: Rest = 13..;
+}
+
+impl<T: AsRef<[u8]>> Packet<T> {
+    pub fn new_unchecked(buffer: T) -> Packet<T> {
+        Packet { buffer }
+    }
+
+    pub fn new_checked(buffer: T) -> Result<Packet<T>, Error> {
+        let packet = Self::new_unchecked(buffer);
+        packet.check_len()?;
+        Ok(packet)
+    }
+
+    /// The header and the data it declares must be present
+    pub fn check_len(&self) -> Result<(), Error> {
+        let len = self.buffer.as_ref().len();
+        if len < field::DATA.start || len < Self::buffer_len(self.data_len()) {
+            Err(Error::WireTruncated)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    pub fn buffer_len(data_len: usize) -> usize {
+        field::DATA.start + data_len
+    }
+
+    #[inline]
+    pub fn uptime(&self) -> u32 {
+        let data = self.buffer.as_ref();
+        LittleEndian::read_u32(&data[field::UPTIME])
+    }
+
+    #[inline]
+    pub fn time_ms(&self) -> u64 {
+        let data = self.buffer.as_ref();
+        LittleEndian::read_u64(&data[field::TIME_MS])
+    }
+
+    #[inline]
+    pub fn data_len(&self) -> usize {
+        let data = self.buffer.as_ref();
+        usize::from(data[field::DATA_LEN])
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Packet<&'a T> {
+    #[inline]
+    pub fn data(&self) -> &'a [u8] {
+        let data = self.buffer.as_ref();
+        &data[field::DATA.start..field::DATA.start + self.data_len()]
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> Packet<T> {
+    #[inline]
+    pub fn set_uptime(&mut self, value: u32) {
+        let data = self.buffer.as_mut();
+        LittleEndian::write_u32(&mut data[field::UPTIME], value);
+    }
+
+    #[inline]
+    pub fn set_time_ms(&mut self, value: u64) {
+        let data = self.buffer.as_mut();
+        LittleEndian::write_u64(&mut data[field::TIME_MS], value);
+    }
+
+    /// Sets the length then the data, the buffer must hold
+    /// buffer_len(value.len()) bytes
+    #[inline]
+    pub fn set_data(&mut self, value: &[u8]) {
+        let data = self.buffer.as_mut();
+        data[field::DATA_LEN] = value.len() as u8;
+        data[field::DATA.start..field::DATA.start + value.len()].copy_from_slice(value);
+    }
+}
+
+impl<T: AsRef<[u8]>> AsRef<[u8]> for Packet<T> {
+    fn as_ref(&self) -> &[u8] {
+        self.buffer.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    static BYTES: [u8; 16] = [
+        0x2C, 0x01, 0x00, 0x00, 0xE0, 0x93, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x0A, 0x0B,
+        0x0C,
+    ];
+
+    #[test]
+    fn construct() {
+        let mut bytes = [0xFF; 16];
+        let mut p = Packet::new_unchecked(&mut bytes[..]);
+        p.set_uptime(300);
+        p.set_time_ms(300_000);
+        p.set_data(&[0x0A, 0x0B, 0x0C]);
+        assert_eq!(&p.into_inner()[..], &BYTES[..]);
+    }
+
+    #[test]
+    fn deconstruct() {
+        let p = Packet::new_checked(&BYTES[..]).unwrap();
+        assert_eq!(p.uptime(), 300);
+        assert_eq!(p.time_ms(), 300_000);
+        assert_eq!(p.data(), &[0x0A, 0x0B, 0x0C][..]);
+        assert_eq!(
+            Packet::new_checked(&BYTES[..15]).err(),
+            Some(Error::WireTruncated)
+        );
+        assert_eq!(
+            Packet::new_checked(&BYTES[..12]).err(),
+            Some(Error::WireTruncated)
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{Error, ParameterId, SetResult, SetStatus, MAX_PARAMS_PER_OP};
+use byteorder::{ByteOrder, LittleEndian};
+use core::convert::TryFrom;
+
+#[derive(Debug, Clone)]
+pub struct Packet<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+mod field {
+    use crate::wire::field::*;
+
+    pub const COUNT: usize = 0;
+    pub const RESULTS: Rest = 1..;
+
+    /// Each result is the parameter ID followed by the status
+    pub const RESULT_ID: Field = 0..4;
+    pub const RESULT_STATUS: usize = 4;
+    pub const RESULT_SIZE: usize = 5;
+}
+
+impl<T: AsRef<[u8]>> Packet<T> {
+    pub fn new_unchecked(buffer: T) -> Packet<T> {
+        Packet { buffer }
+    }
+
+    pub fn new_checked(buffer: T) -> Result<Packet<T>, Error> {
+        let packet = Self::new_unchecked(buffer);
+        packet.check_len()?;
+        Ok(packet)
+    }
+
+    pub fn check_len(&self) -> Result<(), Error> {
+        let len = self.buffer.as_ref().len();
+        if len < field::RESULTS.start {
+            Err(Error::WireTruncated)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    pub fn header_len() -> usize {
+        field::RESULTS.start
+    }
+
+    pub fn buffer_len(payload_len: usize) -> usize {
+        field::RESULTS.start + payload_len
+    }
+
+    #[inline]
+    pub fn count(&self) -> u8 {
+        let data = self.buffer.as_ref();
+        data[field::COUNT]
+    }
+
+    #[inline]
+    pub fn result_at(&self, index: usize) -> Result<SetResult, Error> {
+        if index >= usize::from(self.count()) {
+            return Err(Error::WireIndexOutOfBounds);
+        }
+        let data = self.buffer.as_ref();
+        read_result(data, index)
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> Packet<&'a T> {
+    /// Borrowing iterator over the results
+    #[inline]
+    pub fn iter(&self) -> Iter<'a> {
+        let data = self.buffer.as_ref();
+        Iter {
+            data,
+            index: 0,
+            count: usize::from(self.count()),
+        }
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> Packet<T> {
+    #[inline]
+    pub fn set_count(&mut self, value: u8) {
+        let data = self.buffer.as_mut();
+        data[field::COUNT] = value;
+    }
+
+    #[inline]
+    pub fn set_result_at(&mut self, index: usize, value: SetResult) -> Result<(), Error> {
+        if index >= MAX_PARAMS_PER_OP {
+            return Err(Error::WireIndexOutOfBounds);
+        }
+        let data = self.buffer.as_mut();
+        let start = field::RESULTS.start + index * field::RESULT_SIZE;
+        let bytes = data
+            .get_mut(start..start + field::RESULT_SIZE)
+            .ok_or(Error::WireTruncated)?;
+        LittleEndian::write_u32(&mut bytes[field::RESULT_ID], value.id().into());
+        bytes[field::RESULT_STATUS] = value.status().as_u8();
+        Ok(())
+    }
+}
+
+impl<T: AsRef<[u8]>> AsRef<[u8]> for Packet<T> {
+    fn as_ref(&self) -> &[u8] {
+        self.buffer.as_ref()
+    }
+}
+
+fn read_result(data: &[u8], index: usize) -> Result<SetResult, Error> {
+    let start = field::RESULTS.start + index * field::RESULT_SIZE;
+    let bytes = data
+        .get(start..start + field::RESULT_SIZE)
+        .ok_or(Error::WireTruncated)?;
+    let id = ParameterId::from(LittleEndian::read_u32(&bytes[field::RESULT_ID]));
+    let status = SetStatus::try_from(bytes[field::RESULT_STATUS])?;
+    Ok(SetResult::new(id, status))
+}
+
+/// Iterator over the results of a `SetResultListPacket`,
+/// stops after the first error
+#[derive(Debug, Clone, Default)]
+pub struct Iter<'a> {
+    data: &'a [u8],
+    index: usize,
+    count: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Result<SetResult, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+        let result = read_result(self.data, self.index);
+        self.index = if result.is_ok() {
+            self.index + 1
+        } else {
+            self.count
+        };
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.count - self.index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::TryInto;
+    use pretty_assertions::assert_eq;
+
+    static BYTES: [u8; 16] = [
+        0x03, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x0B, 0x00, 0x00, 0x00, 0x02, 0x0C, 0x00, 0x00, 0x00,
+        0x05,
+    ];
+
+    static RESULTS: [SetResult; 3] = [
+        SetResult::new(ParameterId::new(0x0A), SetStatus::Ok),
+        SetResult::new(ParameterId::new(0x0B), SetStatus::PermissionDenied),
+        SetResult::new(ParameterId::new(0x0C), SetStatus::Clamped),
+    ];
+
+    #[test]
+    fn construct() {
+        let mut bytes = [0xFF; 16];
+        let mut p = Packet::new_unchecked(&mut bytes[..]);
+        assert_eq!(p.check_len(), Ok(()));
+        p.set_count(RESULTS.len().try_into().unwrap());
+        for (index, r) in RESULTS.iter().enumerate() {
+            assert_eq!(p.set_result_at(index, *r), Ok(()));
+        }
+        assert_eq!(&p.into_inner()[..], &BYTES[..]);
+    }
+
+    #[test]
+    fn deconstruct() {
+        let p = Packet::new_checked(&BYTES[..]).unwrap();
+        assert_eq!(p.count(), RESULTS.len().try_into().unwrap());
+        for (index, r) in RESULTS.iter().enumerate() {
+            assert_eq!(p.result_at(index), Ok(*r));
+        }
+        assert_eq!(p.result_at(3), Err(Error::WireIndexOutOfBounds));
+        let mut iter = p.iter();
+        for r in RESULTS.iter() {
+            assert_eq!(iter.next(), Some(Ok(*r)));
+        }
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn deconstruct_invalid() {
+        let mut bytes = BYTES;
+        bytes[10] = 0xEE;
+        let p = Packet::new_checked(&bytes[..]).unwrap();
+        assert_eq!(p.result_at(1), Err(Error::WireInvalidSetStatus));
+        let p = Packet::new_checked(&BYTES[..15]).unwrap();
+        assert_eq!(p.result_at(2), Err(Error::WireTruncated));
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{Error, ParameterId};
+use byteorder::{ByteOrder, LittleEndian};
+
+#[derive(Debug, Clone)]
+pub struct Packet<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+mod field {
+    use crate::wire::field::*;
+
+    /// Node time in milliseconds, parameters changed at or after it are wanted
+    pub const SINCE_MS: Field = 0..8;
+    /// Lowest ID of the requested page, pages are in ID order
+    pub const START_ID: Field = 8..12;
+}
+
+impl<T: AsRef<[u8]>> Packet<T> {
+    pub fn new_unchecked(buffer: T) -> Packet<T> {
+        Packet { buffer }
+    }
+
+    pub fn new_checked(buffer: T) -> Result<Packet<T>, Error> {
+        let packet = Self::new_unchecked(buffer);
+        packet.check_len()?;
+        Ok(packet)
+    }
+
+    pub fn check_len(&self) -> Result<(), Error> {
+        let len = self.buffer.as_ref().len();
+        if len < Self::buffer_len() {
+            Err(Error::WireTruncated)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    /// The packet is fixed size
+    pub fn buffer_len() -> usize {
+        field::START_ID.end
+    }
+
+    #[inline]
+    pub fn since_ms(&self) -> u64 {
+        let data = self.buffer.as_ref();
+        LittleEndian::read_u64(&data[field::SINCE_MS])
+    }
+
+    #[inline]
+    pub fn start_id(&self) -> ParameterId {
+        let data = self.buffer.as_ref();
+        ParameterId::new(LittleEndian::read_u32(&data[field::START_ID]))
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> Packet<T> {
+    #[inline]
+    pub fn set_since_ms(&mut self, value: u64) {
+        let data = self.buffer.as_mut();
+        LittleEndian::write_u64(&mut data[field::SINCE_MS], value);
+    }
+
+    #[inline]
+    pub fn set_start_id(&mut self, value: ParameterId) {
+        let data = self.buffer.as_mut();
+        LittleEndian::write_u32(&mut data[field::START_ID], u32::from(value));
+    }
+}
+
+impl<T: AsRef<[u8]>> AsRef<[u8]> for Packet<T> {
+    fn as_ref(&self) -> &[u8] {
+        self.buffer.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    static BYTES: [u8; 12] = [
+        0x88, 0x13, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x01, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn construct() {
+        let mut bytes = [0xFF; 12];
+        let mut p = Packet::new_checked(&mut bytes[..]).unwrap();
+        p.set_since_ms(5000);
+        p.set_start_id(ParameterId::new(0x0102));
+        assert_eq!(&p.into_inner()[..], &BYTES[..]);
+    }
+
+    #[test]
+    fn deconstruct() {
+        let p = Packet::new_checked(&BYTES[..]).unwrap();
+        assert_eq!(p.since_ms(), 5000);
+        assert_eq!(p.start_id(), ParameterId::new(0x0102));
+        assert_eq!(
+            Packet::new_checked(&BYTES[..11]).err(),
+            Some(Error::WireTruncated)
+        );
+    }
+}
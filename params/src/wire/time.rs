@@ -0,0 +1,90 @@
+use crate::Error;
+use byteorder::{ByteOrder, LittleEndian};
+
+#[derive(Debug, Clone)]
+pub struct Packet<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+mod field {
+    use crate::wire::field::*;
+
+    /// Node time in milliseconds, same clock as the parameters' local time
+    pub const TIME_MS: Field = 0..8;
+}
+
+impl<T: AsRef<[u8]>> Packet<T> {
+    pub fn new_unchecked(buffer: T) -> Packet<T> {
+        Packet { buffer }
+    }
+
+    pub fn new_checked(buffer: T) -> Result<Packet<T>, Error> {
+        let packet = Self::new_unchecked(buffer);
+        packet.check_len()?;
+        Ok(packet)
+    }
+
+    pub fn check_len(&self) -> Result<(), Error> {
+        let len = self.buffer.as_ref().len();
+        if len < Self::buffer_len() {
+            Err(Error::WireTruncated)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    /// The packet is fixed size
+    pub fn buffer_len() -> usize {
+        field::TIME_MS.end
+    }
+
+    #[inline]
+    pub fn time_ms(&self) -> u64 {
+        let data = self.buffer.as_ref();
+        LittleEndian::read_u64(&data[field::TIME_MS])
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> Packet<T> {
+    #[inline]
+    pub fn set_time_ms(&mut self, value: u64) {
+        let data = self.buffer.as_mut();
+        LittleEndian::write_u64(&mut data[field::TIME_MS], value);
+    }
+}
+
+impl<T: AsRef<[u8]>> AsRef<[u8]> for Packet<T> {
+    fn as_ref(&self) -> &[u8] {
+        self.buffer.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    static BYTES: [u8; 8] = [0x0A, 0x0B, 0x0C, 0x00, 0x00, 0x00, 0x00, 0x01];
+
+    #[test]
+    fn construct() {
+        let mut bytes = [0xFF; 8];
+        let mut p = Packet::new_checked(&mut bytes[..]).unwrap();
+        p.set_time_ms(0x0100_0000_000C_0B0A);
+        assert_eq!(&p.into_inner()[..], &BYTES[..]);
+    }
+
+    #[test]
+    fn deconstruct() {
+        let p = Packet::new_checked(&BYTES[..]).unwrap();
+        assert_eq!(p.time_ms(), 0x0100_0000_000C_0B0A);
+        assert_eq!(
+            Packet::new_checked(&BYTES[..7]).err(),
+            Some(Error::WireTruncated)
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! Property tests, parsing arbitrary or corrupted bytes must never panic
+
+use params::*;
+use proptest::prelude::*;
+
+fn valid_response_bytes() -> Vec<u8> {
+    response_bytes(0)
+}
+
+fn response_bytes(flags: GetSetFlags) -> Vec<u8> {
+    let mut resp = Response::new(1, flags, GetSetOp::ListAll);
+    let values = [
+        ParameterValue::I32(-1234),
+        ParameterValue::Bool(true),
+        ParameterValue::Str(ParameterValueStr::from_utf8(b"abcd").unwrap()),
+        ParameterValue::Bytes(ParameterValueBytes::from_slice(&[1, 2, 3]).unwrap()),
+        ParameterValue::Array(
+            ParameterValueArray::parse(ParameterValueTypeId::U16, "1, 2, 3").unwrap(),
+        ),
+        ParameterValue::F64(1.5),
+    ];
+    for (index, value) in values.iter().enumerate() {
+        resp.push(Parameter::new_with_value(
+            ParameterId::new(index as u32),
+            ParameterFlags(0),
+            *value,
+        ))
+        .unwrap();
+    }
+    let mut bytes = vec![0; resp.wire_size()];
+    resp.emit(&mut GetSetFrame::new_unchecked(&mut bytes[..]))
+        .unwrap();
+    bytes
+}
+
+fn parse_all(bytes: &[u8]) {
+    let frame = GetSetFrame::new_unchecked(bytes);
+    let _ = Request::parse(&frame);
+    let _ = Response::parse(&frame);
+    if let Ok(req) = RefRequest::parse(&frame) {
+        assert!(req.ids().all(|id| id.is_ok()));
+        assert!(req.parameters().all(|p| p.is_ok()));
+        if req.op() == GetSetOp::CompareAndSet {
+            assert!(req.compare_and_set_pairs().all(|p| p.is_ok()));
+        }
+    }
+    if let Ok(resp) = RefDescribeResponse::parse(&frame) {
+        assert!(resp.descriptions().all(|d| d.is_ok()));
+    }
+
+    if let Ok(frame) = GetSetFrame::new_checked(bytes) {
+        let _ = frame.op();
+        let _ = frame.payload_type();
+        let _ = frame.cipher_nonce();
+        let _ = frame.cipher_tag();
+        let _ = frame.auth_counter();
+        let _ = frame.auth_tag();
+        let _ = frame.checksum();
+        let _ = format!("{}", frame);
+        if let Ok(payload) = frame.checked_payload() {
+            if let Ok(p) = ParameterIdListPacket::new_checked(payload) {
+                for index in 0..=usize::from(p.count()) {
+                    let _ = p.id_at(index);
+                }
+                let _ = p.iter().count();
+            }
+            if let Ok(p) = ParameterListPacket::new_checked(payload) {
+                for index in 0..=usize::from(p.count()) {
+                    let _ = p.parameter_at(index);
+                }
+                let _ = p.iter().count();
+            }
+        }
+    }
+
+    if let Ok(p) = ParameterPacket::new_checked(bytes) {
+        let _ = p.value_wire_size();
+        let _ = p.value();
+    }
+}
+
+proptest! {
+    #[test]
+    fn arbitrary_bytes(bytes in proptest::collection::vec(any::<u8>(), 0..256)) {
+        parse_all(&bytes);
+    }
+
+    #[test]
+    fn arbitrary_payload(
+        header in proptest::collection::vec(any::<u8>(), 17),
+        payload in proptest::collection::vec(any::<u8>(), 0..256),
+    ) {
+        // Keep a valid preamble so the payload parsers are reached
+        let mut bytes = header;
+        bytes[..4].copy_from_slice(&PREAMBLE_WORD.to_le_bytes());
+        bytes.extend_from_slice(&payload);
+        parse_all(&bytes);
+    }
+
+    #[test]
+    fn truncated(len in 0_usize..200) {
+        let bytes = valid_response_bytes();
+        let len = len.min(bytes.len());
+        parse_all(&bytes[..len]);
+        if len < bytes.len() {
+            let frame = GetSetFrame::new_unchecked(&bytes[..len]);
+            prop_assert!(Response::parse(&frame).is_err());
+        }
+    }
+
+    #[test]
+    fn corrupted(index in any::<prop::sample::Index>(), byte in any::<u8>()) {
+        let mut bytes = valid_response_bytes();
+        let i = index.index(bytes.len());
+        bytes[i] = byte;
+        parse_all(&bytes);
+    }
+
+    #[test]
+    fn corrupted_with_crc(index in any::<prop::sample::Index>(), bit in 0_u8..8) {
+        let mut bytes = response_bytes(GETSET_FLAG_CRC32);
+        let i = index.index(bytes.len());
+        // Clearing the flag itself drops the trailer
+        prop_assume!(i != 8 || bit != 0);
+        bytes[i] ^= 1 << bit;
+        parse_all(&bytes);
+        prop_assert!(GetSetFrame::new_checked(&bytes[..]).is_err());
+    }
+}
+
+#[test]
+fn valid_response_parses() {
+    let bytes = valid_response_bytes();
+    let frame = GetSetFrame::new_checked(&bytes[..]).unwrap();
+    let resp = Response::parse(&frame).unwrap();
+    assert_eq!(resp.parameters().len(), 6);
+
+    let bytes = response_bytes(GETSET_FLAG_CRC32);
+    assert_eq!(bytes.len(), valid_response_bytes().len() + 4);
+    let frame = GetSetFrame::new_checked(&bytes[..]).unwrap();
+    assert!(frame.checksum().is_some());
+    assert_eq!(Response::parse(&frame).unwrap().parameters().len(), 6);
+}
@@ -10,7 +10,7 @@ fn round_trip_list_all() {
     assert_eq!(tx_req.emit(&mut frame), Ok(()));
 
     let frame = GetSetFrame::new_checked(&bytes[..wire_size]).unwrap();
-    assert_eq!(frame.op(), GetSetOp::ListAll);
+    assert_eq!(frame.op(), Ok(GetSetOp::ListAll));
     let rx_req = Request::parse(&frame).unwrap();
     assert_eq!(rx_req, tx_req);
 
@@ -33,7 +33,7 @@ fn round_trip_list_all() {
     assert_eq!(tx_resp.emit(&mut frame), Ok(()));
 
     let frame = GetSetFrame::new_checked(&bytes[..wire_size]).unwrap();
-    assert_eq!(frame.op(), GetSetOp::ListAll);
+    assert_eq!(frame.op(), Ok(GetSetOp::ListAll));
     let rx_resp = Response::parse(&frame).unwrap();
     assert_eq!(rx_resp, tx_resp);
 }
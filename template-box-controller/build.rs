@@ -0,0 +1,66 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const KEY_SIZE: usize = 32;
+
+// The key files are provisioned per node and not checked in, they're copied
+// into OUT_DIR so a malformed one is reported here rather than as an
+// include_bytes! error in main.rs. Each key that's present enables a cfg of
+// the same name, only the cipher key is needed with require-encryption.
+fn main() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let require_encryption = env::var_os("CARGO_FEATURE_REQUIRE_ENCRYPTION").is_some();
+    copy_key(
+        "GETSET_AUTH_KEY_FILE",
+        "auth.key",
+        "auth_key",
+        false,
+        &out_dir,
+    );
+    copy_key(
+        "GETSET_CIPHER_KEY_FILE",
+        "cipher.key",
+        "cipher_key",
+        require_encryption,
+        &out_dir,
+    );
+}
+
+/// The key is read from the file the variable names, or the default file
+/// next to Cargo.toml
+fn copy_key(var: &str, default: &str, cfg: &str, required: bool, out_dir: &Path) {
+    println!("cargo:rerun-if-env-changed={}", var);
+    let path = env::var_os(var).map_or_else(|| PathBuf::from(default), PathBuf::from);
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    let key = match fs::read(&path) {
+        Ok(key) => key,
+        Err(e) => {
+            let msg = format!(
+                "Can't read key file {} ({}), create it with \
+                 'head -c {} /dev/urandom > {}' or set {} to its path",
+                path.display(),
+                e,
+                KEY_SIZE,
+                default,
+                var
+            );
+            if required {
+                panic!("{}", msg);
+            }
+            println!("cargo:warning={}, building without it", msg);
+            return;
+        }
+    };
+    if key.len() != KEY_SIZE {
+        panic!(
+            "Key file {} must hold {} bytes, got {}",
+            path.display(),
+            KEY_SIZE,
+            key.len()
+        );
+    }
+    fs::write(out_dir.join(default), key).unwrap();
+    println!("cargo:rustc-cfg={}", cfg);
+}
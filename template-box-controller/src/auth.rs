@@ -0,0 +1,44 @@
+use crate::error::Error;
+use heapless::consts::U8;
+use heapless::Vec;
+use params::{AuthKey, Authenticator, GetSetFrame, GetSetNodeId};
+
+pub type MaxPeers = U8;
+
+/// Shared keys of the nodes allowed to send signed requests,
+/// each with its own replay counter.
+///
+/// The counters don't survive a restart, so requests must be signed for the
+/// epoch picked at boot which is advertised in the node's Capabilities.
+pub struct KeyStore {
+    epoch: u64,
+    peers: Vec<(GetSetNodeId, Authenticator), MaxPeers>,
+}
+
+impl KeyStore {
+    pub fn new(keys: &[(GetSetNodeId, &AuthKey)], epoch: u64) -> Result<Self, Error> {
+        let mut peers = Vec::new();
+        for (node_id, key) in keys {
+            if peers.iter().any(|(id, _)| id == node_id) {
+                return Err(Error::Duplicate);
+            }
+            let mut auth = Authenticator::new(**key);
+            auth.set_epoch(epoch);
+            peers.push((*node_id, auth)).map_err(|_| Error::Capacity)?;
+        }
+        Ok(KeyStore { epoch, peers })
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Verify a request with the key of the node it claims to come from
+    pub fn verify<T: AsRef<[u8]>>(&mut self, frame: &GetSetFrame<T>) -> Result<(), Error> {
+        let node_id = frame.node_id();
+        match self.peers.iter_mut().find(|(id, _)| *id == node_id) {
+            Some((_, auth)) => Ok(auth.verify(frame)?),
+            None => Err(params::Error::AuthFailed.into()),
+        }
+    }
+}
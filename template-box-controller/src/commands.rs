@@ -0,0 +1,107 @@
+use crate::error::Error;
+use heapless::consts::U8;
+use heapless::Vec;
+use log::debug;
+use param_desc::param_command::param_command;
+use params::{ParameterId, ParameterValue};
+
+/// Runs a command, given its argument (Notification when it takes none)
+/// and returning its result (Notification when it returns none)
+pub type Handler = fn(ParameterValue) -> Result<ParameterValue, Error>;
+
+pub struct Commands {
+    handlers: Vec<(ParameterId, Handler), U8>,
+}
+
+impl Commands {
+    pub fn new() -> Self {
+        Commands {
+            handlers: Vec::new(),
+        }
+    }
+
+    /// Register the handler of a command parameter
+    pub fn register(&mut self, id: ParameterId, handler: Handler) -> Result<(), Error> {
+        debug!("Registering command ID {}", id);
+        if param_command(id).is_none() {
+            Err(Error::NotFound)
+        } else if self.handlers.iter().any(|(i, _)| *i == id) {
+            Err(Error::Duplicate)
+        } else {
+            self.handlers
+                .push((id, handler))
+                .map_err(|_| Error::Capacity)
+        }
+    }
+
+    /// Run a command, the argument and result are checked against the
+    /// command's types
+    pub fn invoke(&self, id: ParameterId, arg: ParameterValue) -> Result<ParameterValue, Error> {
+        let command = param_command(id).ok_or(Error::NotFound)?;
+        let handler = self
+            .handlers
+            .iter()
+            .find(|(i, _)| *i == id)
+            .map(|(_, h)| h)
+            .ok_or(Error::NotFound)?;
+        command.check_arg(arg)?;
+        let result = handler(arg)?;
+        command.check_result(result)?;
+        Ok(result)
+    }
+}
+
+impl Default for Commands {
+    fn default() -> Self {
+        Commands::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use param_desc::param::{BCAST_INTERVAL, REBOOT, SELF_TEST};
+    use pretty_assertions::assert_eq;
+
+    fn nop(_arg: ParameterValue) -> Result<ParameterValue, Error> {
+        Ok(ParameterValue::Notification)
+    }
+
+    fn echo(arg: ParameterValue) -> Result<ParameterValue, Error> {
+        Ok(arg)
+    }
+
+    #[test]
+    fn register() {
+        let mut commands = Commands::new();
+        assert_eq!(commands.register(REBOOT.id(), nop), Ok(()));
+        assert_eq!(commands.register(REBOOT.id(), nop), Err(Error::Duplicate));
+        assert_eq!(
+            commands.register(BCAST_INTERVAL.id(), nop),
+            Err(Error::NotFound)
+        );
+    }
+
+    #[test]
+    fn invoke() {
+        let mut commands = Commands::new();
+        assert_eq!(
+            commands.invoke(REBOOT.id(), ParameterValue::Notification),
+            Err(Error::NotFound)
+        );
+        assert_eq!(commands.register(REBOOT.id(), nop), Ok(()));
+        assert_eq!(commands.register(SELF_TEST.id(), echo), Ok(()));
+        assert_eq!(
+            commands.invoke(REBOOT.id(), ParameterValue::Notification),
+            Ok(ParameterValue::Notification)
+        );
+        assert_eq!(
+            commands.invoke(SELF_TEST.id(), ParameterValue::U32(3)),
+            Ok(ParameterValue::U32(3))
+        );
+        assert_eq!(
+            commands.invoke(SELF_TEST.id(), ParameterValue::U8(3)),
+            Err(Error::ParamsError(params::Error::ValueTypeMismatch))
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use params::{ErrorCode, SetStatus};
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum Error {
     ParamsError(params::Error),
@@ -7,6 +9,64 @@ pub enum Error {
     Duplicate,
     PermissionDenied,
     NotFound,
+    OutOfRange,
+    /// The current value doesn't match the expected one
+    Mismatch,
+}
+
+impl Error {
+    /// Code reported to the client in an Error op reply
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            Error::ParamsError(e) => ErrorCode::from(*e),
+            Error::SmoltcpError(_) => ErrorCode::Network,
+            Error::ProtocolMalformed(_) => ErrorCode::Malformed,
+            Error::Capacity => ErrorCode::Capacity,
+            Error::Duplicate => ErrorCode::Duplicate,
+            Error::PermissionDenied => ErrorCode::PermissionDenied,
+            Error::NotFound => ErrorCode::NotFound,
+            Error::OutOfRange => ErrorCode::OutOfRange,
+            Error::Mismatch => ErrorCode::InvalidValue,
+        }
+    }
+
+    /// Status reported to the client when a Set fails with this error
+    pub fn set_status(&self) -> SetStatus {
+        match self {
+            Error::ParamsError(e) => params_set_status(*e),
+            Error::NotFound => SetStatus::NotFound,
+            Error::PermissionDenied => SetStatus::PermissionDenied,
+            Error::OutOfRange => SetStatus::OutOfRange,
+            Error::Mismatch => SetStatus::Mismatch,
+            // Faults of the node rather than of the value
+            Error::SmoltcpError(_)
+            | Error::ProtocolMalformed(_)
+            | Error::Capacity
+            | Error::Duplicate => SetStatus::Internal,
+        }
+    }
+}
+
+fn params_set_status(e: params::Error) -> SetStatus {
+    use params::Error::*;
+    match e {
+        ValueTypeMismatch | ParseValue | WireInvalidValueType => SetStatus::TypeMismatch,
+        // Values too large for the parameter's bounded type
+        Capacity => SetStatus::OutOfRange,
+        AuthRequired | AuthFailed | AuthReplay | EncryptionRequired => SetStatus::PermissionDenied,
+        WireTruncated
+        | WireIndexOutOfBounds
+        | WirePreamble
+        | WireUnsupportedVersion
+        | WireChecksum
+        | WireInvalidPayloadType
+        | WireInvalidOp
+        | WireInvalidSetStatus
+        | WireInvalidErrorCode
+        | WireInvalidCompareAndSet
+        | DecryptFailed
+        | Remote(_) => SetStatus::Internal,
+    }
 }
 
 impl From<params::Error> for Error {
@@ -20,3 +80,32 @@ impl From<smoltcp::Error> for Error {
         Error::SmoltcpError(e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn set_status() {
+        assert_eq!(Error::NotFound.set_status(), SetStatus::NotFound);
+        assert_eq!(Error::OutOfRange.set_status(), SetStatus::OutOfRange);
+        assert_eq!(
+            Error::ParamsError(params::Error::ValueTypeMismatch).set_status(),
+            SetStatus::TypeMismatch
+        );
+        assert_eq!(
+            Error::ParamsError(params::Error::ParseValue).set_status(),
+            SetStatus::TypeMismatch
+        );
+        assert_eq!(
+            Error::ParamsError(params::Error::Capacity).set_status(),
+            SetStatus::OutOfRange
+        );
+        assert_eq!(Error::Capacity.set_status(), SetStatus::Internal);
+        assert_eq!(
+            Error::SmoltcpError(smoltcp::Error::Exhausted).set_status(),
+            SetStatus::Internal
+        );
+    }
+}
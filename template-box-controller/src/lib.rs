@@ -8,6 +8,8 @@ use heapless::mpmc::Q32;
 
 // TODO - use a prelude?
 
+pub mod auth;
+pub mod commands;
 pub mod error;
 pub mod logger;
 pub mod net;
@@ -8,7 +8,11 @@ use core::ops::DerefMut;
 use cortex_m::interrupt::Mutex;
 use cortex_m_rt::ExceptionFrame;
 use cortex_m_rt::{entry, exception};
+use heapless::Vec;
+use lib::auth::KeyStore;
+use lib::commands::Commands;
 use lib::error::Error;
+use lib::hal::gpio::{gpiob::PB14, Output, PushPull};
 use lib::hal::prelude::*;
 use lib::hal::serial::{config::Config, Serial};
 use lib::hal::stm32::{self, interrupt, TIM2, TIM3};
@@ -16,13 +20,20 @@ use lib::hal::timer::{Event as TimerEvent, Timer};
 use lib::logger::Logger;
 use lib::net::eth::{Eth, MTU, NEIGHBOR_CACHE_SIZE, SOCKET_BUFFER_SIZE};
 use lib::net::getset_protocol::GetSetProtocol;
+use lib::net::reply::{Emit, Framed};
 use lib::params::{dequeue_event, enqueue_event, Params};
 use lib::sys_clock;
-use log::{debug, info, LevelFilter};
+use log::{debug, info, warn, LevelFilter};
+use param_desc::param_description::param_description;
+use param_desc::paramdb::PARAMDB_HASH;
 use param_desc::{node_id::TEMPLATE_NODE1, param, param_id};
 use params::{
-    GetSetFlags, GetSetFrame, GetSetNodeId, GetSetOp, GetSetPayloadType, Parameter, RefResponse,
-    Request, Response, PREAMBLE_WORD,
+    Announce, AuthKey, Capabilities, Cipher, CipherKey, DescribeResponse, Description, GetSetFlags,
+    GetSetFrame, GetSetNodeId, GetSetOp, GetSetPayloadType, GetSetVersion, IdListResponse,
+    MaxParamsPerOp, Parameter, ParameterFlags, ParameterId, ParameterValue, RefRequest,
+    RefResponse, RemoteError, Response, SetResult, SetStatus, GETSET_FLAG_ATOMIC,
+    GETSET_FLAG_CRC32, GETSET_FLAG_ENCRYPTED, GETSET_FLAG_MORE, GETSET_MAX_VERSION,
+    GETSET_MIN_VERSION, GETSET_VERSION_1, NODE_ID_ANONYMOUS,
 };
 use smoltcp::iface::{EthernetInterfaceBuilder, NeighborCache, Routes};
 use smoltcp::phy::Device;
@@ -44,14 +55,64 @@ const TCP_SERVER_PORT: u16 = 9877;
 
 const NODE_ID: GetSetNodeId = TEMPLATE_NODE1;
 
-const PARAMETERS: [&'static Parameter; 5] = [
+/// Keys of the nodes allowed to send signed requests, the key files hold
+/// 32 random bytes (head -c 32 /dev/urandom > auth.key) and are not checked
+/// in, build.rs checks them. Without one no request can change parameters.
+#[cfg(auth_key)]
+const AUTH_KEYS: &[(GetSetNodeId, &AuthKey)] = &[(
+    NODE_ID_ANONYMOUS,
+    include_bytes!(concat!(env!("OUT_DIR"), "/auth.key")),
+)];
+#[cfg(not(auth_key))]
+const AUTH_KEYS: &[(GetSetNodeId, &AuthKey)] = &[];
+
+/// This node's encryption key, 32 random bytes provisioned per node
+/// (head -c 32 /dev/urandom > cipher.key) and not checked in.
+/// Without one encrypted requests are rejected.
+#[cfg(cipher_key)]
+const CIPHER_KEY: Option<&CipherKey> =
+    Some(include_bytes!(concat!(env!("OUT_DIR"), "/cipher.key")));
+#[cfg(not(cipher_key))]
+const CIPHER_KEY: Option<&CipherKey> = None;
+
+/// Flags requests must carry to read or change parameters, encrypted with
+/// the require-encryption feature so only hosts with CIPHER_KEY can.
+/// Otherwise hosts without the key read parameters and listen to
+/// broadcasts in the clear.
+#[cfg(feature = "require-encryption")]
+const REQUIRED_FLAGS: GetSetFlags = GETSET_FLAG_ENCRYPTED;
+#[cfg(not(feature = "require-encryption"))]
+const REQUIRED_FLAGS: GetSetFlags = 0;
+
+/// Broadcasts always carry a CRC32, UDP receivers can't ask again,
+/// and are encrypted when requests must be
+const BCAST_FLAGS: GetSetFlags = GETSET_FLAG_CRC32 | REQUIRED_FLAGS;
+
+/// Announcements are for finding nodes before their keys are
+/// known, they carry a CRC32 but aren't encrypted
+const ANNOUNCE_FLAGS: GetSetFlags = GETSET_FLAG_CRC32;
+
+type LedRed = PB14<Output<PushPull>>;
+
+/// Command parameters aren't values, they're only reachable with the
+/// Invoke op through the registered commands
+const PARAMETERS: [&'static Parameter; 10] = [
     &param::BCAST_INTERVAL,
     &param::UPTIME,
+    &param::FW_VERSION,
     &param::ETH_LINK_DOWN_COUNT,
     &param::LED_STATE,
     &param::TEMPERATURE,
+    &param::MAC_ADDRESS,
+    &param::ZONE_TEMPERATURES,
+    &param::HVAC_MODE,
+    &param::IO_STATE,
 ];
 
+/// Self test bits of the self_test command's argument and result
+const SELF_TEST_CLOCK: u32 = 1 << 0;
+const SELF_TEST_RNG: u32 = 1 << 1;
+
 static GLOBAL_LOGGER: Logger = Logger::new();
 
 static GLOBAL_ETH_PENDING: Mutex<Cell<bool>> = Mutex::new(Cell::new(false));
@@ -64,6 +125,9 @@ static GLOBAL_PARAM_BCAST_RELOAD: Mutex<Cell<u32>> = Mutex::new(Cell::new(1));
 
 static GLOBAL_ETH_POLL_TIM3: Mutex<RefCell<Option<Timer<TIM3>>>> = Mutex::new(RefCell::new(None));
 
+/// System time (ms) to reset at, set by the reboot command
+static GLOBAL_REBOOT_AT: Mutex<Cell<Option<u64>>> = Mutex::new(Cell::new(None));
+
 #[entry]
 fn main() -> ! {
     let dp = stm32::Peripherals::take().expect("Failed to take stm32::Peripherals");
@@ -72,6 +136,9 @@ fn main() -> ! {
 
     stm32_eth::setup(&dp.RCC, &dp.SYSCFG);
 
+    // The RNG runs off the PLL48 clock, it's up once the clocks are frozen
+    dp.RCC.ahb2enr.modify(|_, w| w.rngen().set_bit());
+
     let rcc = dp.RCC.constrain();
     let clocks = rcc.cfgr.sysclk(180.mhz()).freeze();
 
@@ -121,21 +188,51 @@ fn main() -> ! {
         params.add(**p).unwrap();
     }
 
+    debug!("Setup commands");
+    let mut commands = Commands::new();
+    commands.register(param_id::REBOOT, reboot).unwrap();
+    commands
+        .register(param_id::RESET_COUNTERS, reset_counters)
+        .unwrap();
+    commands.register(param_id::SELF_TEST, self_test).unwrap();
+
+    debug!("Setup {} auth keys", AUTH_KEYS.len());
+    // Random every boot so requests signed before a restart are rejected after it
+    let auth_epoch = u64::from(random_u32(&dp.RNG)) << 32 | u64::from(random_u32(&dp.RNG));
+    let mut key_store = KeyStore::new(AUTH_KEYS, auth_epoch).unwrap();
+
+    let announce = Announce::new(
+        GETSET_MIN_VERSION,
+        GETSET_MAX_VERSION,
+        PARAMDB_HASH,
+        SRC_IP,
+        TCP_SERVER_PORT,
+        param::FW_VERSION.value().as_str(),
+    )
+    .unwrap();
+
+    // Nonces restart on every boot, a random salt keeps them unique
+    let mut cipher = CIPHER_KEY.map(|key| Cipher::new(*key, random_u32(&dp.RNG)));
+
     // Handle initial setup from params
     for p in params.as_ref() {
         match p.id() {
-            param_id::LED_STATE => match p.value().as_bool() {
-                true => led_red.set_high().unwrap(),
-                false => led_red.set_low().unwrap(),
+            param_id::LED_STATE => match p.value().try_as_bool() {
+                Ok(true) => led_red.set_high().unwrap(),
+                Ok(false) => led_red.set_low().unwrap(),
+                Err(e) => warn!("LED state {:?}", e),
+            },
+            param_id::BCAST_INTERVAL => match p.value().try_as_u32() {
+                Ok(ival) => {
+                    let ival = cmp::max(1, ival);
+                    debug!("Bcast interval {} sec", ival);
+                    cortex_m::interrupt::free(|cs| {
+                        GLOBAL_PARAM_BCAST_COUNTER.borrow(cs).replace(ival);
+                        GLOBAL_PARAM_BCAST_RELOAD.borrow(cs).replace(ival);
+                    });
+                }
+                Err(e) => warn!("Bcast interval {:?}", e),
             },
-            param_id::BCAST_INTERVAL => {
-                let ival = cmp::max(1, p.value().as_u32());
-                debug!("Bcast interval {} sec", ival);
-                cortex_m::interrupt::free(|cs| {
-                    GLOBAL_PARAM_BCAST_COUNTER.borrow(cs).replace(ival);
-                    GLOBAL_PARAM_BCAST_RELOAD.borrow(cs).replace(ival);
-                });
-            }
             _ => (),
         }
     }
@@ -189,8 +286,10 @@ fn main() -> ! {
         )
     };
 
-    let mut rx_meta = [UdpPacketMetadata::EMPTY];
-    let mut tx_meta = [UdpPacketMetadata::EMPTY];
+    // Room for an announce queued alongside a parameter broadcast, and
+    // for other nodes' broadcasts received between polls
+    let mut rx_meta = [UdpPacketMetadata::EMPTY; 4];
+    let mut tx_meta = [UdpPacketMetadata::EMPTY; 2];
     let udp_socket = {
         static mut RX_BUFFER: [u8; SOCKET_BUFFER_SIZE] = [0; SOCKET_BUFFER_SIZE];
         static mut TX_BUFFER: [u8; SOCKET_BUFFER_SIZE] = [0; SOCKET_BUFFER_SIZE];
@@ -271,6 +370,13 @@ fn main() -> ! {
     // or have multiple bcast groups, each can have independent bcast interval
     led_blue.set_low().unwrap();
     let mut last_sec = 0;
+
+    // Flags and version of the connected client's Subscribe, changes
+    // of the subscribed parameters are pushed to it with them
+    let mut subscription: Option<(GetSetFlags, GetSetVersion)> = None;
+
+    // Announce at boot and whenever a client broadcasts a Discover
+    let mut announce_pending = true;
     loop {
         let time = sys_clock::system_time();
 
@@ -281,9 +387,9 @@ fn main() -> ! {
             let mut frame = GetSetFrame::new_unchecked(&mut eth_frame_buffer[..]);
             let bcast_params = params.get_all_broadcast();
             if bcast_params.len() != 0 {
-                let ref_resp =
-                    RefResponse::new(NODE_ID, GetSetFlags::default(), GetSetOp::Get, bcast_params);
+                let ref_resp = RefResponse::new(NODE_ID, BCAST_FLAGS, GetSetOp::Get, bcast_params);
                 ref_resp.emit(&mut frame).unwrap();
+                seal_reply(&mut cipher, &mut frame).unwrap();
                 let size = ref_resp.wire_size();
                 eth.send_udp_bcast(&frame.as_ref()[..size]).unwrap();
             }
@@ -296,6 +402,52 @@ fn main() -> ! {
             eth.poll(time);
         }
 
+        if announce_pending {
+            announce_pending = false;
+            let mut frame = GetSetFrame::new_unchecked(&mut eth_frame_buffer[..]);
+            announce
+                .emit(NODE_ID, ANNOUNCE_FLAGS, GETSET_VERSION_1, 0, &mut frame)
+                .unwrap();
+            debug!("Tx {}", frame);
+            let size = announce.wire_size(GETSET_VERSION_1, ANNOUNCE_FLAGS);
+            if let Err(e) = eth.send_udp_bcast(&frame.as_ref()[..size]) {
+                warn!("Failed to send announce {:?}", e);
+            }
+            cortex_m::interrupt::free(|cs| GLOBAL_ETH_PENDING.borrow(cs).replace(true));
+        }
+
+        // Other nodes' broadcasts share the port, only Discover
+        // and Ping requests are answered
+        if let Ok((bytes_recvd, src)) = eth.recv_udp_frame(&mut eth_frame_buffer[..]) {
+            let op = GetSetFrame::new_checked(&eth_frame_buffer[..bytes_recvd])
+                .ok()
+                .and_then(|frame| frame.op().ok());
+            match op {
+                Some(GetSetOp::Discover) => {
+                    let frame = GetSetFrame::new_unchecked(&eth_frame_buffer[..bytes_recvd]);
+                    if RefRequest::parse(&frame).is_ok() {
+                        debug!("Discover from {}", src);
+                        announce_pending = true;
+                    }
+                }
+                Some(GetSetOp::Ping) => {
+                    let result = udp_ping(&cipher, &params, &mut eth_frame_buffer[..bytes_recvd])
+                        .and_then(|resp| {
+                            let mut frame = GetSetFrame::new_unchecked(&mut eth_frame_buffer[..]);
+                            resp.emit(&mut frame)?;
+                            seal_reply(&mut cipher, &mut frame)?;
+                            debug!("Tx {}", frame);
+                            eth.send_udp(&frame.as_ref()[..resp.wire_size()], src)
+                        });
+                    if let Err(e) = result {
+                        warn!("UDP ping from {} failed {:?}", src, e);
+                    }
+                    cortex_m::interrupt::free(|cs| GLOBAL_ETH_PENDING.borrow(cs).replace(true));
+                }
+                _ => (),
+            }
+        }
+
         // TODO
         // merge GetSetProtocol and Params
         // list-all and get can be done internally
@@ -303,93 +455,370 @@ fn main() -> ! {
         // Service TCP get/set protocol
         if let Ok(bytes_recvd) = eth.recv_tcp_frame(&mut eth_frame_buffer[..]) {
             if bytes_recvd != 0 {
+                // Reported back if handling the request fails,
+                // replies carry a CRC32, are encrypted and atomic when
+                // the request was and echo its version and sequence number
+                let (req_op, reply_flags, req_version, req_seq) = {
+                    let frame = GetSetFrame::new_unchecked(&eth_frame_buffer[..bytes_recvd]);
+                    if frame.check_len().is_ok() {
+                        (
+                            frame.raw_op(),
+                            frame.flags()
+                                & (GETSET_FLAG_CRC32 | GETSET_FLAG_ENCRYPTED | GETSET_FLAG_ATOMIC),
+                            frame.version(),
+                            frame.sequence(),
+                        )
+                    } else {
+                        (
+                            GetSetOp::Error.as_u8(),
+                            GetSetFlags::default(),
+                            GETSET_VERSION_1,
+                            0,
+                        )
+                    }
+                };
                 let mut getset_proto = GetSetProtocol::new(&mut eth_frame_buffer[..]).unwrap();
-                let result = getset_proto.process_buffer(bytes_recvd, |op, buffer| {
+                let result = getset_proto.process_buffer(bytes_recvd, |op, version, buffer| {
                     cortex_m::interrupt::free(|cs| GLOBAL_ETH_PENDING.borrow(cs).replace(true));
+
+                    // Signed requests are always verified, changes and commands must be signed
+                    let signed = {
+                        let frame = GetSetFrame::new_unchecked(&buffer[..]);
+                        if frame.auth_counter().is_some() {
+                            key_store.verify(&frame)?;
+                            true
+                        } else {
+                            false
+                        }
+                    };
+                    let changes = op == GetSetOp::Set
+                        || op == GetSetOp::CompareAndSet
+                        || op == GetSetOp::Invoke;
+                    if changes && !signed {
+                        warn!("Rejecting unsigned {}", op);
+                        return Err(params::Error::AuthRequired.into());
+                    }
+                    let flags = GetSetFrame::new_unchecked(&buffer[..]).flags();
+                    if flags & REQUIRED_FLAGS != REQUIRED_FLAGS && !is_keyless(op) {
+                        warn!("Rejecting unencrypted {}", op);
+                        return Err(params::Error::EncryptionRequired.into());
+                    }
+
+                    // Encrypted requests are decrypted in place once verified
+                    open_request(&cipher, &mut GetSetFrame::new_unchecked(&mut buffer[..]))?;
+
                     match op {
                         GetSetOp::ListAll => {
-                            let mut frame = GetSetFrame::new_unchecked(buffer);
+                            // Pages start at the requested offset and hold
+                            // as many parameters as fit in a frame
+                            let offset = {
+                                let frame = GetSetFrame::new_checked(&buffer[..])?;
+                                usize::from(RefRequest::parse(&frame)?.offset())
+                            };
+                            let max_len = buffer.len();
                             let params = params.as_ref();
                             if params.len() != 0 {
-                                let ref_resp = RefResponse::new(
+                                let page = params.get(offset..).unwrap_or(&[]);
+                                let mut ref_resp =
+                                    RefResponse::new(NODE_ID, reply_flags, GetSetOp::ListAll, page);
+                                ref_resp.set_version(version);
+                                let page_len = ref_resp.page_len(max_len);
+                                let mut ref_resp = RefResponse::new(
                                     NODE_ID,
-                                    GetSetFlags::default(),
+                                    reply_flags,
                                     GetSetOp::ListAll,
-                                    params,
+                                    &page[..page_len],
                                 );
-                                ref_resp.emit(&mut frame)?;
-                                debug!("Tx {}", frame);
-                                let size = ref_resp.wire_size();
-                                eth.send_tcp(&frame.as_ref()[..size])?;
+                                if page_len < page.len() {
+                                    ref_resp.set_flags(reply_flags | GETSET_FLAG_MORE);
+                                }
+                                ref_resp.set_version(version);
+                                ref_resp.set_sequence(req_seq);
+                                send_reply(&mut eth, &mut cipher, buffer, &ref_resp)?;
                             }
                         }
+                        GetSetOp::Describe => {
+                            // Paged like ListAll, one description per
+                            // parameter, undescribed ones have no name
+                            let offset = {
+                                let frame = GetSetFrame::new_checked(&buffer[..])?;
+                                usize::from(RefRequest::parse(&frame)?.offset())
+                            };
+                            let descs: Vec<Description, MaxParamsPerOp> = params
+                                .as_ref()
+                                .iter()
+                                .skip(offset)
+                                .map(|p| {
+                                    param_description(p.id()).copied().unwrap_or_else(|| {
+                                        Description::new(
+                                            p.id(),
+                                            p.flags(),
+                                            p.value().type_id(),
+                                            "",
+                                            "",
+                                        )
+                                    })
+                                })
+                                .collect();
+                            let max_len = buffer.len();
+                            let resp = DescribeResponse::new(NODE_ID, reply_flags, &descs);
+                            let page_len = resp.page_len(max_len);
+                            let mut resp =
+                                DescribeResponse::new(NODE_ID, reply_flags, &descs[..page_len]);
+                            if offset + page_len < params.as_ref().len() {
+                                resp.set_flags(reply_flags | GETSET_FLAG_MORE);
+                            }
+                            resp.set_version(version);
+                            resp.set_sequence(req_seq);
+
+                            send_reply(&mut eth, &mut cipher, buffer, &resp)?;
+                        }
                         GetSetOp::Get => {
                             let frame = GetSetFrame::new_checked(&buffer[..])?;
-                            let req = Request::parse(&frame)?;
-                            let mut resp =
-                                Response::new(NODE_ID, GetSetFlags::default(), GetSetOp::Get);
+                            let req = RefRequest::parse(&frame)?;
+                            // Emitted straight from the parameter table, only
+                            // the requested IDs are collected
+                            let mut resp = IdListResponse::new(
+                                NODE_ID,
+                                reply_flags,
+                                GetSetOp::Get,
+                                params.as_ref(),
+                            );
+                            resp.set_version(version);
+                            resp.set_sequence(req_seq);
+                            // Parameters that don't fit are left for the next
+                            // request, it asks for the IDs after the last one
+                            let max_len = buffer.len();
                             for id in req.ids() {
-                                if let Some(p) = params.get(*id) {
-                                    resp.push(*p)?;
+                                if let Some(p) = params.get(id?) {
+                                    if resp.wire_size() + p.wire_size() > max_len {
+                                        resp.set_flags(reply_flags | GETSET_FLAG_MORE);
+                                        break;
+                                    }
+                                    resp.push(p.id())?;
                                 }
                             }
 
-                            let mut frame = GetSetFrame::new_unchecked(buffer);
-                            resp.emit(&mut frame)?;
-                            debug!("Tx {}", frame);
-                            let size = resp.wire_size();
-                            eth.send_tcp(&frame.as_ref()[..size])?;
+                            send_reply(&mut eth, &mut cipher, buffer, &resp)?;
                         }
                         GetSetOp::Set => {
                             let frame = GetSetFrame::new_checked(&buffer[..])?;
-                            let req = Request::parse(&frame)?;
-                            let mut resp =
-                                Response::new(NODE_ID, GetSetFlags::default(), GetSetOp::Set);
+                            let req = RefRequest::parse(&frame)?;
+                            let mut resp = Response::new_with_payload_type(
+                                NODE_ID,
+                                reply_flags,
+                                GetSetOp::Set,
+                                GetSetPayloadType::SetResultListPacket,
+                            );
+                            resp.set_version(version);
+                            resp.set_sequence(req_seq);
+                            let allow_read_only_change = false;
+
+                            // Atomic batches are validated as a whole first,
+                            // nothing is applied if any of them would fail
+                            let mut rolled_back = false;
+                            if frame.flags() & GETSET_FLAG_ATOMIC != 0 {
+                                for p in req.parameters() {
+                                    let p = p?;
+                                    let (value, _) = clamp_value(p.id(), p.value());
+                                    if params
+                                        .validate(p.id(), value, allow_read_only_change)
+                                        .is_err()
+                                    {
+                                        rolled_back = true;
+                                    }
+                                }
+                            }
+
                             for p in req.parameters() {
+                                let p = p?;
                                 // TODO - callback notification in here somewhere?
-                                let allow_read_only_change = false;
-                                if params
-                                    .set(p.id(), p.value(), allow_read_only_change)
-                                    .is_ok()
-                                {
-                                    // TODO - get_mut() above before set()
-                                    // , change/clamp values if needed
-                                    let p = params.get(p.id()).unwrap();
-                                    //resp.push(*params.get(p.id()).unwrap())?;
-
-                                    if p.flags().broadcast() && p.flags().broadcast_on_change() {
-                                        cortex_m::interrupt::free(|cs| {
-                                            GLOBAL_PARAM_BCAST_PENDING.borrow(cs).replace(true)
-                                        });
-                                    }
+                                let (value, clamped) = clamp_value(p.id(), p.value());
+                                let result = if rolled_back {
+                                    params.validate(p.id(), value, allow_read_only_change)
+                                } else {
+                                    params.set(p.id(), value, allow_read_only_change)
+                                };
+                                let status = match result {
+                                    Ok(()) if rolled_back => SetStatus::RolledBack,
+                                    Ok(()) if clamped => SetStatus::Clamped,
+                                    Ok(()) => SetStatus::Ok,
+                                    Err(e) => e.set_status(),
+                                };
+                                resp.push_result(SetResult::new(p.id(), status))?;
+
+                                if status.is_success() {
+                                    param_changed(params.get(p.id()).unwrap(), &mut led_red);
+                                }
+                            }
+
+                            send_reply(&mut eth, &mut cipher, buffer, &resp)?;
+                        }
+                        GetSetOp::CompareAndSet => {
+                            let frame = GetSetFrame::new_checked(&buffer[..])?;
+                            let req = RefRequest::parse(&frame)?;
+                            let mut resp = Response::new_with_payload_type(
+                                NODE_ID,
+                                reply_flags,
+                                GetSetOp::CompareAndSet,
+                                GetSetPayloadType::CompareAndSetResultPacket,
+                            );
+                            resp.set_version(version);
+                            resp.set_sequence(req_seq);
+                            let allow_read_only_change = false;
+                            for pair in req.compare_and_set_pairs() {
+                                let (expected, p) = pair?;
+                                let (value, clamped) = clamp_value(p.id(), p.value());
+                                let status = match params.compare_and_set(
+                                    p.id(),
+                                    expected.value(),
+                                    value,
+                                    allow_read_only_change,
+                                ) {
+                                    Ok(()) if clamped => SetStatus::Clamped,
+                                    Ok(()) => SetStatus::Ok,
+                                    Err(e) => e.set_status(),
+                                };
+                                resp.push_result(SetResult::new(p.id(), status))?;
+
+                                // Mismatched entries report the current value
+                                if status == SetStatus::Mismatch {
+                                    resp.push(*params.get(p.id()).unwrap())?;
+                                } else if status.is_success() {
+                                    param_changed(params.get(p.id()).unwrap(), &mut led_red);
+                                }
+                            }
 
-                                    // TODO
-                                    // need to sanitize values, might ignore user's
-                                    match p.id() {
-                                        param_id::LED_STATE => match p.value().as_bool() {
-                                            true => led_red.set_high().unwrap(),
-                                            false => led_red.set_low().unwrap(),
-                                        },
-                                        param_id::BCAST_INTERVAL => {
-                                            let ival = cmp::max(1, p.value().as_u32());
-                                            debug!("New bcast interval {} sec", ival);
-                                            cortex_m::interrupt::free(|cs| {
-                                                GLOBAL_PARAM_BCAST_COUNTER.borrow(cs).replace(ival);
-                                                GLOBAL_PARAM_BCAST_RELOAD.borrow(cs).replace(ival);
-                                            });
-                                        }
-                                        _ => (),
+                            send_reply(&mut eth, &mut cipher, buffer, &resp)?;
+                        }
+                        GetSetOp::GetChanged => {
+                            // The parameters changed at or after the requested
+                            // node time, paged in ID order from the start ID
+                            let frame = GetSetFrame::new_checked(&buffer[..])?;
+                            let req = RefRequest::parse(&frame)?;
+                            let since_ms = req.since_ms();
+                            let start_id = req.start_id();
+                            let mut resp = Response::new_with_payload_type(
+                                NODE_ID,
+                                reply_flags,
+                                GetSetOp::GetChanged,
+                                GetSetPayloadType::ChangedListPacket,
+                            );
+                            resp.set_version(version);
+                            resp.set_sequence(req_seq);
+                            // Sampled before the scan so no change made after
+                            // the reported time can be missed by the next request
+                            resp.set_node_time_ms(sys_clock::system_millis());
+                            let max_len = buffer.len();
+                            for p in params.changed_since(since_ms, start_id) {
+                                if resp.wire_size() + p.wire_size() > max_len {
+                                    resp.set_flags(reply_flags | GETSET_FLAG_MORE);
+                                    break;
+                                }
+                                resp.push(*p)?;
+                            }
+
+                            send_reply(&mut eth, &mut cipher, buffer, &resp)?;
+                        }
+                        GetSetOp::Subscribe => {
+                            let frame = GetSetFrame::new_checked(&buffer[..])?;
+                            let req = RefRequest::parse(&frame)?;
+                            let mut resp = Response::new(NODE_ID, reply_flags, GetSetOp::Subscribe);
+                            resp.set_version(version);
+                            resp.set_sequence(req_seq);
+                            // Unknown IDs are ignored like Get does, current
+                            // values that don't fit are left out
+                            let max_len = buffer.len();
+                            for id in req.ids() {
+                                let id = id?;
+                                if params.subscribe(id).is_ok() {
+                                    let p = params.get(id).unwrap();
+                                    if resp.wire_size() + p.wire_size() <= max_len {
+                                        resp.push(*p)?;
                                     }
+                                }
+                            }
+                            subscription = Some((reply_flags, version));
 
-                                    resp.push(*p)?;
+                            send_reply(&mut eth, &mut cipher, buffer, &resp)?;
+                        }
+                        GetSetOp::Unsubscribe => {
+                            let frame = GetSetFrame::new_checked(&buffer[..])?;
+                            let req = RefRequest::parse(&frame)?;
+                            if frame.payload_type()? == GetSetPayloadType::None {
+                                params.unsubscribe_all();
+                            } else {
+                                for id in req.ids() {
+                                    params.unsubscribe(id?);
                                 }
                             }
+                            let mut resp =
+                                Response::new(NODE_ID, reply_flags, GetSetOp::Unsubscribe);
+                            resp.set_version(version);
+                            resp.set_sequence(req_seq);
 
-                            let mut frame = GetSetFrame::new_unchecked(buffer);
-                            resp.emit(&mut frame)?;
-                            debug!("Tx {}", frame);
-                            let size = resp.wire_size();
-                            eth.send_tcp(&frame.as_ref()[..size])?;
+                            send_reply(&mut eth, &mut cipher, buffer, &resp)?;
+                        }
+                        GetSetOp::Invoke => {
+                            let frame = GetSetFrame::new_checked(&buffer[..])?;
+                            let req = RefRequest::parse(&frame)?;
+                            let mut parameters = req.parameters();
+                            let p = match (parameters.next(), parameters.next()) {
+                                (Some(p), None) => p?,
+                                _ => return Err(Error::ProtocolMalformed(op)),
+                            };
+                            debug!("Invoking command ID {}", p.id());
+                            let result = commands.invoke(p.id(), p.value())?;
+
+                            let mut resp = Response::new(NODE_ID, reply_flags, GetSetOp::Invoke);
+                            resp.set_version(version);
+                            resp.set_sequence(req_seq);
+                            resp.push(Parameter::new_with_value(
+                                p.id(),
+                                ParameterFlags::default(),
+                                result,
+                            ))?;
+
+                            send_reply(&mut eth, &mut cipher, buffer, &resp)?;
+                        }
+                        GetSetOp::Ping => {
+                            let frame = GetSetFrame::new_checked(&buffer[..])?;
+                            let req = RefRequest::parse(&frame)?;
+                            let resp = ping_response(&params, &req, reply_flags)?;
+
+                            send_reply(&mut eth, &mut cipher, buffer, &resp)?;
+                        }
+                        GetSetOp::Discover => {
+                            let mut reply = Framed::new(NODE_ID, reply_flags, &announce);
+                            reply.set_version(version);
+                            reply.set_sequence(req_seq);
+                            send_reply(&mut eth, &mut cipher, buffer, &reply)?;
+                        }
+                        GetSetOp::Capabilities => {
+                            let mut caps =
+                                Capabilities::new(GETSET_MIN_VERSION, GETSET_MAX_VERSION);
+                            caps.add_op(GetSetOp::ListAll);
+                            caps.add_op(GetSetOp::Get);
+                            caps.add_op(GetSetOp::Set);
+                            caps.add_op(GetSetOp::Capabilities);
+                            caps.add_op(GetSetOp::CompareAndSet);
+                            caps.add_op(GetSetOp::Describe);
+                            caps.add_op(GetSetOp::Subscribe);
+                            caps.add_op(GetSetOp::Unsubscribe);
+                            caps.add_op(GetSetOp::GetChanged);
+                            caps.add_op(GetSetOp::Invoke);
+                            caps.add_op(GetSetOp::Discover);
+                            caps.add_op(GetSetOp::Ping);
+                            caps.set_auth_epoch(key_store.epoch());
+
+                            let mut reply = Framed::new(NODE_ID, reply_flags, &caps);
+                            reply.set_version(version);
+                            reply.set_sequence(req_seq);
+                            send_reply(&mut eth, &mut cipher, buffer, &reply)?;
+                        }
+                        // Rejected as malformed by the protocol
+                        GetSetOp::Error | GetSetOp::Announce => {
+                            return Err(Error::ProtocolMalformed(op))
                         }
                     }
                     Ok(())
@@ -399,18 +828,19 @@ fn main() -> ! {
                     Ok(_) => (),
                     Err(e) => {
                         cortex_m::interrupt::free(|cs| GLOBAL_ETH_PENDING.borrow(cs).replace(true));
-                        if let Error::ProtocolMalformed(op) = e {
-                            let mut frame = GetSetFrame::new_unchecked(&mut eth_frame_buffer[..]);
-                            frame.set_preamble(PREAMBLE_WORD);
-                            frame.set_node_id(NODE_ID);
-                            frame.set_flags(GetSetFlags::default());
-                            frame.set_version(1);
-                            frame.set_op(op);
-                            frame.set_payload_type(GetSetPayloadType::None);
-                            frame.set_payload_size(0);
-                            debug!("Tx {}", frame);
-                            let size = GetSetFrame::<&[u8]>::header_len();
-                            eth.send_tcp(&frame.as_ref()[..size]).unwrap();
+                        warn!("GetSet request failed {:?}", e);
+                        let op = match e {
+                            Error::ProtocolMalformed(op) => op.as_u8(),
+                            _ => req_op,
+                        };
+                        let err = RemoteError::new(e.error_code(), op);
+                        let mut reply = Framed::new(NODE_ID, reply_flags, &err);
+                        reply.set_version(req_version);
+                        reply.set_sequence(req_seq);
+                        if let Err(e) =
+                            send_reply(&mut eth, &mut cipher, &mut eth_frame_buffer[..], &reply)
+                        {
+                            warn!("Failed to send error reply {:?}", e);
                         }
                     }
                 }
@@ -421,8 +851,8 @@ fn main() -> ! {
         let is_pressed = user_btn.is_low().unwrap();
         if !btn_was_pressed && is_pressed {
             debug!("Button press - toggle LED");
-            if let Some(v) = params.get_value(param_id::LED_STATE) {
-                let state = !v.as_bool();
+            if let Ok(v) = params.get_value_as::<bool>(param_id::LED_STATE) {
+                let state = !v;
                 match state {
                     true => led_red.set_high().unwrap(),
                     false => led_red.set_low().unwrap(),
@@ -435,7 +865,52 @@ fn main() -> ! {
         }
 
         // Drain parameter event queue
-        dequeue_event().map(|e| params.process_event(e).unwrap());
+        if let Some(e) = dequeue_event() {
+            if let Err(err) = params.process_event(e) {
+                warn!("Dropped event {:?} {:?}", e, err);
+            }
+        }
+
+        // Subscriptions end with the connection
+        if subscription.is_some() && !eth.tcp_connected() {
+            debug!("Dropping {} subscriptions", params.subscriptions().len());
+            subscription = None;
+            params.unsubscribe_all();
+        }
+
+        // Push changes of subscribed parameters a frame at a time as the
+        // send buffer makes room for them, they stay pending until then.
+        // Pushes are unsolicited so they carry sequence 0, which clients
+        // never use for requests.
+        if let Some((flags, version)) = subscription {
+            let max_len = eth_frame_buffer.len();
+            while !params.changed().is_empty() {
+                let mut resp = Response::new(NODE_ID, flags, GetSetOp::Subscribe);
+                resp.set_version(version);
+                let mut count = 0;
+                for id in params.changed() {
+                    if let Some(p) = params.get(*id) {
+                        if resp.wire_size() + p.wire_size() > max_len {
+                            break;
+                        }
+                        resp.push(*p).unwrap();
+                    }
+                    count += 1;
+                }
+                if eth.tcp_send_free() < resp.wire_size() {
+                    break;
+                }
+                push_update(&mut eth, &mut cipher, &mut eth_frame_buffer[..], &resp);
+                params.pushed(count);
+            }
+        }
+
+        // The reboot command's reply has been sent by now
+        let reboot_at = cortex_m::interrupt::free(|cs| GLOBAL_REBOOT_AT.borrow(cs).get());
+        if reboot_at.map_or(false, |t| sys_clock::system_millis() >= t) {
+            info!("Rebooting");
+            cortex_m::peripheral::SCB::sys_reset();
+        }
 
         let sec = time.as_secs();
         if sec != last_sec {
@@ -443,15 +918,207 @@ fn main() -> ! {
             led_green.toggle().unwrap();
 
             // TODO
-            let inner = params.get_value(param_id::UPTIME).unwrap().as_u32();
-            enqueue_event((param_id::UPTIME, inner.wrapping_add(1).into()).into()).unwrap();
+            if let Ok(inner) = params.get_value_as::<u32>(param_id::UPTIME) {
+                enqueue_event((param_id::UPTIME, inner.wrapping_add(1).into()).into()).unwrap();
+            }
 
-            let inner = params.get_value(param_id::TEMPERATURE).unwrap().as_f32();
-            enqueue_event((param_id::TEMPERATURE, (inner + 0.13).into()).into()).unwrap();
+            if let Ok(inner) = params.get_value_as::<f32>(param_id::TEMPERATURE) {
+                enqueue_event((param_id::TEMPERATURE, (inner + 0.13).into()).into()).unwrap();
+            }
         }
     }
 }
 
+/// Apply the side effects of a parameter changed by a request
+fn param_changed(p: &Parameter, led_red: &mut LedRed) {
+    if p.flags().broadcast() && p.flags().broadcast_on_change() {
+        cortex_m::interrupt::free(|cs| GLOBAL_PARAM_BCAST_PENDING.borrow(cs).replace(true));
+    }
+
+    // TODO
+    // need to sanitize values, might ignore user's
+    match p.id() {
+        param_id::LED_STATE => match p.value().try_as_bool() {
+            Ok(true) => led_red.set_high().unwrap(),
+            Ok(false) => led_red.set_low().unwrap(),
+            Err(e) => warn!("LED state {:?}", e),
+        },
+        param_id::BCAST_INTERVAL => match p.value().try_as_u32() {
+            Ok(ival) => {
+                let ival = cmp::max(1, ival);
+                debug!("New bcast interval {} sec", ival);
+                cortex_m::interrupt::free(|cs| {
+                    GLOBAL_PARAM_BCAST_COUNTER.borrow(cs).replace(ival);
+                    GLOBAL_PARAM_BCAST_RELOAD.borrow(cs).replace(ival);
+                });
+            }
+            Err(e) => warn!("Bcast interval {:?}", e),
+        },
+        _ => (),
+    }
+}
+
+/// Echo a Ping with the node's uptime and local time
+fn ping_response(
+    params: &Params,
+    req: &RefRequest<'_>,
+    flags: GetSetFlags,
+) -> Result<Response, Error> {
+    let mut resp = Response::new_with_payload_type(
+        NODE_ID,
+        flags,
+        GetSetOp::Ping,
+        GetSetPayloadType::PingPacket,
+    );
+    resp.set_version(req.version());
+    resp.set_sequence(req.sequence());
+    resp.set_uptime(params.get_value_as::<u32>(param_id::UPTIME)?);
+    resp.set_node_time_ms(sys_clock::system_millis());
+    resp.set_ping_data(req.ping_data())?;
+    Ok(resp)
+}
+
+/// Answer a Ping received over UDP, encrypted requests are decrypted
+/// in place and the reply is encrypted like over TCP
+fn udp_ping(
+    cipher: &Option<Cipher>,
+    params: &Params,
+    buffer: &mut [u8],
+) -> Result<Response, Error> {
+    open_request(cipher, &mut GetSetFrame::new_checked(&mut buffer[..])?)?;
+    let frame = GetSetFrame::new_checked(&buffer[..])?;
+    let req = RefRequest::parse(&frame)?;
+    if req.payload_type() != GetSetPayloadType::PingPacket {
+        return Err(Error::ProtocolMalformed(GetSetOp::Ping));
+    }
+    let flags = req.flags() & (GETSET_FLAG_CRC32 | GETSET_FLAG_ENCRYPTED);
+    ping_response(params, &req, flags)
+}
+
+/// Reboot command, the reset is deferred so the reply goes out first
+fn reboot(_arg: ParameterValue) -> Result<ParameterValue, Error> {
+    let at = sys_clock::system_millis() + 100;
+    cortex_m::interrupt::free(|cs| GLOBAL_REBOOT_AT.borrow(cs).set(Some(at)));
+    Ok(ParameterValue::Notification)
+}
+
+/// Reset counters command
+fn reset_counters(_arg: ParameterValue) -> Result<ParameterValue, Error> {
+    enqueue_event((param_id::ETH_LINK_DOWN_COUNT, 0_u32.into()).into())?;
+    Ok(ParameterValue::Notification)
+}
+
+/// Self test command, runs the tests selected by the argument's bits and
+/// returns the bits of those that failed
+fn self_test(arg: ParameterValue) -> Result<ParameterValue, Error> {
+    let tests = arg.try_as_u32()?;
+    let mut failed = 0;
+    if tests & SELF_TEST_CLOCK != 0 {
+        // SysTick ticks every millisecond
+        let start = sys_clock::system_millis();
+        let mut spins: u32 = 0;
+        while sys_clock::system_millis() == start && spins < 1_000_000 {
+            spins += 1;
+        }
+        if sys_clock::system_millis() == start {
+            failed |= SELF_TEST_CLOCK;
+        }
+    }
+    if tests & SELF_TEST_RNG != 0 {
+        // The RNG is only read here and during setup
+        let rng = unsafe { &*stm32::RNG::ptr() };
+        if random_u32(rng) == random_u32(rng) {
+            failed |= SELF_TEST_RNG;
+        }
+    }
+    Ok(ParameterValue::U32(failed))
+}
+
+/// Send a subscription update to the connected client, the eth frame
+/// buffer is used to emit it
+fn push_update(
+    eth: &mut Eth<'_, '_, '_, '_, '_, '_, '_, '_, '_>,
+    cipher: &mut Option<Cipher>,
+    buffer: &mut [u8],
+    resp: &Response,
+) {
+    if let Err(e) = send_reply(eth, cipher, buffer, resp) {
+        warn!("Failed to push subscription update {:?}", e);
+    }
+}
+
+/// Emit a reply into the buffer, encrypt it if its flags ask for it
+/// and send it to the connected client
+fn send_reply(
+    eth: &mut Eth<'_, '_, '_, '_, '_, '_, '_, '_, '_>,
+    cipher: &mut Option<Cipher>,
+    buffer: &mut [u8],
+    reply: &impl Emit,
+) -> Result<(), Error> {
+    let mut frame = GetSetFrame::new_unchecked(buffer);
+    let size = reply.emit_frame(&mut frame)?;
+    seal_reply(cipher, &mut frame)?;
+    debug!("Tx {}", frame);
+    eth.send_tcp(&frame.as_ref()[..size])
+}
+
+/// Ops that carry no parameter values, hosts can use them before
+/// they have the node's keys
+fn is_keyless(op: GetSetOp) -> bool {
+    match op {
+        GetSetOp::Capabilities | GetSetOp::Discover | GetSetOp::Ping => true,
+        _ => false,
+    }
+}
+
+/// Decrypt a received frame in place if its encrypted flag is set,
+/// it can't be without a cipher key
+fn open_request<T: AsRef<[u8]> + AsMut<[u8]>>(
+    cipher: &Option<Cipher>,
+    frame: &mut GetSetFrame<T>,
+) -> Result<(), params::Error> {
+    if frame.flags() & GETSET_FLAG_ENCRYPTED != 0 {
+        cipher
+            .as_ref()
+            .ok_or(params::Error::DecryptFailed)?
+            .open(frame)
+    } else {
+        Ok(())
+    }
+}
+
+/// Encrypt an emitted frame if its encrypted flag is set, only replies to
+/// requests that were decrypted and broadcasts with require-encryption are
+fn seal_reply<T: AsRef<[u8]> + AsMut<[u8]>>(
+    cipher: &mut Option<Cipher>,
+    frame: &mut GetSetFrame<T>,
+) -> Result<(), params::Error> {
+    if frame.flags() & GETSET_FLAG_ENCRYPTED != 0 {
+        cipher
+            .as_mut()
+            .ok_or(params::Error::EncryptionRequired)?
+            .seal(frame)
+    } else {
+        Ok(())
+    }
+}
+
+/// Read a word from the hardware RNG, its clock must be enabled
+fn random_u32(rng: &stm32::RNG) -> u32 {
+    rng.cr.modify(|_, w| w.rngen().set_bit());
+    while rng.sr.read().drdy().bit_is_clear() {}
+    rng.dr.read().bits()
+}
+
+/// Clamp a requested value into the parameter's valid range,
+/// returns true if the value was changed
+fn clamp_value(id: ParameterId, value: ParameterValue) -> (ParameterValue, bool) {
+    match (id, value) {
+        (param_id::BCAST_INTERVAL, ParameterValue::U32(0)) => (ParameterValue::U32(1), true),
+        _ => (value, false),
+    }
+}
+
 #[exception]
 fn SysTick() {
     cortex_m::interrupt::free(|cs| {
@@ -1,6 +1,7 @@
 use crate::error::Error;
+use crate::net::getset_protocol::frame_len;
 use crate::time::Instant;
-use log::debug;
+use log::{debug, warn};
 use params::GetSetFrame;
 use smoltcp::iface::EthernetInterface;
 use smoltcp::socket::{SocketHandle, SocketSet, TcpSocket, TcpState, UdpSocket};
@@ -15,9 +16,6 @@ const TCP_TIMEOUT_DURATION: Option<smoltcp::time::Duration> =
 const TCP_KEEP_ALIVE_INTERVAL: Option<smoltcp::time::Duration> =
     Some(smoltcp::time::Duration { millis: 2 * 1000 });
 
-// 49152..=65535
-const EPHEMERAL_PORT: u16 = 49152;
-
 pub struct Eth<'a, 'b, 'c, 'd, 'e, 'f, 'rx, 'tx, 'r> {
     iface: EthernetInterface<'a, 'b, 'c, &'r mut stm32_eth::Eth<'rx, 'tx>>,
     sockets: SocketSet<'d, 'e, 'f>,
@@ -47,10 +45,12 @@ impl<'a, 'b, 'c, 'd, 'e, 'f, 'rx, 'tx, 'r> Eth<'a, 'b, 'c, 'd, 'e, 'f, 'rx, 'tx,
             tcp_was_connected: false,
         };
 
+        // Bound to the broadcast port so Discover requests broadcast
+        // by clients are received
         debug!("UDP endpoint {}", eth.udp_endpoint);
         eth.sockets
             .get::<UdpSocket>(eth.udp_handle)
-            .bind(EPHEMERAL_PORT)?;
+            .bind(eth.udp_endpoint.port)?;
 
         eth.listen();
 
@@ -64,31 +64,96 @@ impl<'a, 'b, 'c, 'd, 'e, 'f, 'rx, 'tx, 'r> Eth<'a, 'b, 'c, 'd, 'e, 'f, 'rx, 'tx,
         Ok(())
     }
 
+    /// Send to a single endpoint, such as the sender of a request
+    pub fn send_udp(&mut self, data: &[u8], endpoint: IpEndpoint) -> Result<(), Error> {
+        self.sockets
+            .get::<UdpSocket>(self.udp_handle)
+            .send_slice(data, endpoint)?;
+        Ok(())
+    }
+
+    /// Returns the size and sender of a pending datagram, fails when none
+    /// are pending, datagrams that don't fit are truncated
+    pub fn recv_udp_frame(&mut self, data: &mut [u8]) -> Result<(usize, IpEndpoint), Error> {
+        let (bytes_recvd, endpoint) = self
+            .sockets
+            .get::<UdpSocket>(self.udp_handle)
+            .recv_slice(data)?;
+        Ok((bytes_recvd, endpoint))
+    }
+
+    /// Queue a whole frame for the connected client, nothing is queued
+    /// when the send buffer can't take all of it since the client couldn't
+    /// make sense of the rest of a partial frame
     pub fn send_tcp(&mut self, data: &[u8]) -> Result<(), Error> {
         let mut socket = self.sockets.get::<TcpSocket>(self.tcp_handle);
-        let _ = socket.send_slice(data)?;
+        if !socket.may_send() {
+            return Err(smoltcp::Error::Illegal.into());
+        }
+        let free = socket.send_capacity() - socket.send_queue();
+        if !socket.can_send() || free < data.len() {
+            warn!(
+                "Can't queue a {} byte frame, {} bytes free",
+                data.len(),
+                free
+            );
+            return Err(Error::Capacity);
+        }
+        let bytes_sent = socket.send_slice(data)?;
+        if bytes_sent != data.len() {
+            warn!("Queued {} of {} bytes", bytes_sent, data.len());
+            return Err(Error::Capacity);
+        }
         Ok(())
     }
 
+    /// Bytes the TCP send buffer can take before it's full
+    pub fn tcp_send_free(&mut self) -> usize {
+        let socket = self.sockets.get::<TcpSocket>(self.tcp_handle);
+        socket.send_capacity() - socket.send_queue()
+    }
+
+    /// A client is connected to the TCP server socket
+    pub fn tcp_connected(&self) -> bool {
+        self.tcp_was_connected
+    }
+
+    /// Receive the next whole GetSet frame, returns 0 until all of it has
+    /// arrived. The frame is sized from its header, which is only checked
+    /// for the preamble and version, the rest of it is checked by the caller.
+    ///
+    /// Frames of an unsupported version are returned as just their header
+    /// so they can be answered with an error, anything else that doesn't
+    /// start with a usable header is dropped along with the queued bytes.
     pub fn recv_tcp_frame(&mut self, data: &mut [u8]) -> Result<usize, Error> {
         let mut socket = self.sockets.get::<TcpSocket>(self.tcp_handle);
-        if socket.may_recv() {
-            let header_len = GetSetFrame::<&[u8]>::header_len();
-            if socket.recv_queue() >= header_len {
-                let bytes_recvd = socket.peek_slice(&mut data[..header_len])?;
-                if bytes_recvd == header_len {
-                    if let Ok(frame) = GetSetFrame::new_checked(&mut data[..header_len]) {
-                        let frame_size = header_len + usize::from(frame.payload_size());
-                        if socket.recv_queue() >= frame_size {
-                            let bytes_recvd = socket.recv_slice(&mut data[..frame_size])?;
-                            return Ok(bytes_recvd);
-                        }
-                    }
-                }
-            }
+        let header_len = GetSetFrame::<&[u8]>::header_len();
+        if !socket.may_recv() || socket.recv_queue() < header_len {
+            return Ok(0);
+        }
+        if socket.peek_slice(&mut data[..header_len])? != header_len {
+            return Ok(0);
         }
 
-        Ok(0)
+        match frame_len(&data[..header_len]) {
+            Ok(frame_size) if frame_size > data.len() => {
+                warn!("Dropping a {} byte frame, it doesn't fit", frame_size);
+                drain(&mut socket, data)?;
+                Err(Error::Capacity)
+            }
+            Ok(frame_size) if socket.recv_queue() >= frame_size => {
+                Ok(socket.recv_slice(&mut data[..frame_size])?)
+            }
+            Ok(_) => Ok(0),
+            Err(Error::ParamsError(params::Error::WireUnsupportedVersion)) => {
+                Ok(socket.recv_slice(&mut data[..header_len])?)
+            }
+            Err(e) => {
+                warn!("Dropping received bytes that aren't a frame {:?}", e);
+                drain(&mut socket, data)?;
+                Err(e)
+            }
+        }
     }
 
     pub fn poll(&mut self, time: Instant) {
@@ -140,3 +205,11 @@ impl<'a, 'b, 'c, 'd, 'e, 'f, 'rx, 'tx, 'r> Eth<'a, 'b, 'c, 'd, 'e, 'f, 'rx, 'tx,
         socket.set_keep_alive(TCP_KEEP_ALIVE_INTERVAL);
     }
 }
+
+/// Discard everything queued on the socket
+fn drain(socket: &mut TcpSocket, buffer: &mut [u8]) -> Result<(), Error> {
+    while socket.recv_queue() > 0 {
+        socket.recv_slice(buffer)?;
+    }
+    Ok(())
+}
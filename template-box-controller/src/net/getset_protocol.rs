@@ -4,7 +4,24 @@
 use crate::error::Error;
 use crate::net::eth::MTU;
 use log::{error, warn};
-use params::{GetSetFrame, GetSetOp, GetSetPayloadType};
+use params::{GetSetFrame, GetSetOp, GetSetPayloadType, GetSetVersion};
+
+/// Size of the frame whose header starts the buffer, from its version, flags
+/// and payload size. Only the preamble and version are checked, the rest is
+/// checked once the whole frame has been received.
+pub fn frame_len(header: &[u8]) -> Result<usize, Error> {
+    if header.len() < GetSetFrame::<&[u8]>::header_len() {
+        return Err(params::Error::WireTruncated.into());
+    }
+    let frame = GetSetFrame::new_unchecked(header);
+    frame.check_preamble()?;
+    frame.check_version()?;
+    Ok(GetSetFrame::<&[u8]>::frame_len(
+        frame.version(),
+        frame.flags(),
+        usize::from(frame.payload_size()),
+    ))
+}
 
 #[derive(Debug)]
 pub struct GetSetProtocol<T: AsRef<[u8]>> {
@@ -33,23 +50,51 @@ impl<T: AsRef<[u8]> + AsMut<[u8]>> GetSetProtocol<T> {
 
     pub fn process_buffer<F>(&mut self, size: usize, mut handler: F) -> Result<(), Error>
     where
-        F: FnMut(GetSetOp, &mut [u8]) -> Result<(), Error>,
+        F: FnMut(GetSetOp, GetSetVersion, &mut [u8]) -> Result<(), Error>,
     {
         let frame = GetSetFrame::new_checked(&self.buffer()[..size])?;
-        let op = frame.op();
+        let op = frame.op()?;
+        let version = frame.version();
 
         // Attempt to catch malformed requests
         let malformed = match op {
-            GetSetOp::ListAll => false,
-            GetSetOp::Get => {
-                if frame.payload_type() == GetSetPayloadType::ParameterIdListPacket {
+            GetSetOp::ListAll | GetSetOp::Describe => false,
+            // Only sent by nodes
+            GetSetOp::Error | GetSetOp::Announce => true,
+            GetSetOp::Get | GetSetOp::Subscribe => {
+                if frame.payload_type() == Ok(GetSetPayloadType::ParameterIdListPacket) {
+                    false
+                } else {
+                    true
+                }
+            }
+            GetSetOp::Set | GetSetOp::CompareAndSet | GetSetOp::Invoke => {
+                if frame.payload_type() == Ok(GetSetPayloadType::ParameterListPacket) {
+                    false
+                } else {
+                    true
+                }
+            }
+            GetSetOp::Unsubscribe => match frame.payload_type() {
+                Ok(GetSetPayloadType::ParameterIdListPacket) | Ok(GetSetPayloadType::None) => false,
+                _ => true,
+            },
+            GetSetOp::GetChanged => {
+                if frame.payload_type() == Ok(GetSetPayloadType::SincePacket) {
+                    false
+                } else {
+                    true
+                }
+            }
+            GetSetOp::Ping => {
+                if frame.payload_type() == Ok(GetSetPayloadType::PingPacket) {
                     false
                 } else {
                     true
                 }
             }
-            GetSetOp::Set => {
-                if frame.payload_type() == GetSetPayloadType::ParameterListPacket {
+            GetSetOp::Capabilities | GetSetOp::Discover => {
+                if frame.payload_type() == Ok(GetSetPayloadType::None) {
                     false
                 } else {
                     true
@@ -63,7 +108,7 @@ impl<T: AsRef<[u8]> + AsMut<[u8]>> GetSetProtocol<T> {
         }
 
         // User callback handling
-        (handler)(op, self.buffer.as_mut())?;
+        (handler)(op, version, self.buffer.as_mut())?;
 
         Ok(())
     }
@@ -72,10 +117,153 @@ impl<T: AsRef<[u8]> + AsMut<[u8]>> GetSetProtocol<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    //use pretty_assertions::assert_eq;
+    use params::{
+        Authenticator, Cipher, ParameterId, Request, GETSET_FLAG_AUTH, GETSET_FLAG_CRC32,
+        GETSET_FLAG_ENCRYPTED, GETSET_VERSION_1, GETSET_VERSION_2,
+    };
+    use pretty_assertions::assert_eq;
+
+    /// Emit the request into a buffer the size of a received frame
+    fn request_bytes(req: &Request, buffer: &mut [u8]) -> usize {
+        let size = req.wire_size();
+        let mut frame = GetSetFrame::new_unchecked(&mut buffer[..size]);
+        req.emit(&mut frame).unwrap();
+        frame.fill_checksum();
+        size
+    }
+
+    /// Size the frame from its header like the TCP receive path, then
+    /// process the whole of it
+    fn receive(buffer: &mut [u8], size: usize) -> Result<GetSetOp, Error> {
+        let header_len = GetSetFrame::<&[u8]>::header_len();
+        assert_eq!(frame_len(&buffer[..header_len]), Ok(size));
+        let mut received = None;
+        GetSetProtocol::new(buffer)?.process_buffer(size, |op, _, _| {
+            received = Some(op);
+            Ok(())
+        })?;
+        Ok(received.unwrap())
+    }
 
     #[test]
     fn todo() {
         //
     }
+
+    #[test]
+    fn receive_with_payload() {
+        let mut req = Request::new(
+            1,
+            0,
+            GetSetOp::Get,
+            GetSetPayloadType::ParameterIdListPacket,
+        );
+        req.set_version(GETSET_VERSION_1);
+        req.push_id(ParameterId::new(1)).unwrap();
+        let mut buffer = [0; MTU];
+        let size = request_bytes(&req, &mut buffer);
+        assert_eq!(size, 22);
+        assert_eq!(receive(&mut buffer, size), Ok(GetSetOp::Get));
+    }
+
+    #[test]
+    fn receive_v2() {
+        let mut req = Request::new(
+            1,
+            0,
+            GetSetOp::Get,
+            GetSetPayloadType::ParameterIdListPacket,
+        );
+        req.set_version(GETSET_VERSION_2);
+        req.set_sequence(0x1234_5678);
+        req.push_id(ParameterId::new(1)).unwrap();
+        let mut buffer = [0; MTU];
+        let size = request_bytes(&req, &mut buffer);
+        assert_eq!(size, 26);
+        assert_eq!(receive(&mut buffer, size), Ok(GetSetOp::Get));
+        let frame = GetSetFrame::new_checked(&buffer[..size]).unwrap();
+        assert_eq!(frame.sequence(), 0x1234_5678);
+    }
+
+    #[test]
+    fn receive_with_crc32() {
+        let mut req = Request::new(
+            1,
+            GETSET_FLAG_CRC32,
+            GetSetOp::Get,
+            GetSetPayloadType::ParameterIdListPacket,
+        );
+        req.push_id(ParameterId::new(1)).unwrap();
+        let mut buffer = [0; MTU];
+        let size = request_bytes(&req, &mut buffer);
+        assert_eq!(size, 22 + 4);
+        assert_eq!(receive(&mut buffer, size), Ok(GetSetOp::Get));
+    }
+
+    #[test]
+    fn receive_encrypted() {
+        let mut req = Request::new(
+            1,
+            GETSET_FLAG_ENCRYPTED | GETSET_FLAG_CRC32,
+            GetSetOp::Get,
+            GetSetPayloadType::ParameterIdListPacket,
+        );
+        req.push_id(ParameterId::new(1)).unwrap();
+        let mut buffer = [0; MTU];
+        let size = request_bytes(&req, &mut buffer);
+        assert_eq!(size, 22 + 28 + 4);
+        let plain = buffer;
+        let mut cipher = Cipher::new([7; 32], 1);
+        cipher
+            .seal(&mut GetSetFrame::new_unchecked(&mut buffer[..size]))
+            .unwrap();
+        assert_ne!(&buffer[..size], &plain[..size]);
+        assert_eq!(receive(&mut buffer, size), Ok(GetSetOp::Get));
+        cipher
+            .open(&mut GetSetFrame::new_unchecked(&mut buffer[..size]))
+            .unwrap();
+        assert_eq!(&buffer[..size - 28 - 4], &plain[..size - 28 - 4]);
+    }
+
+    #[test]
+    fn receive_signed() {
+        let mut req = Request::new(
+            1,
+            GETSET_FLAG_AUTH | GETSET_FLAG_ENCRYPTED | GETSET_FLAG_CRC32,
+            GetSetOp::Get,
+            GetSetPayloadType::ParameterIdListPacket,
+        );
+        req.set_version(GETSET_VERSION_2);
+        req.push_id(ParameterId::new(1)).unwrap();
+        let mut buffer = [0; MTU];
+        let size = request_bytes(&req, &mut buffer);
+        assert_eq!(size, 26 + 28 + 40 + 4);
+        let mut frame = GetSetFrame::new_unchecked(&mut buffer[..size]);
+        Cipher::new([7; 32], 1).seal(&mut frame).unwrap();
+        Authenticator::new([9; 32]).sign(&mut frame).unwrap();
+        assert_eq!(receive(&mut buffer, size), Ok(GetSetOp::Get));
+        let frame = GetSetFrame::new_checked(&buffer[..size]).unwrap();
+        assert_eq!(Authenticator::new([9; 32]).verify(&frame), Ok(()));
+    }
+
+    #[test]
+    fn frame_len_checks_header() {
+        let mut buffer = [0; MTU];
+        let req = Request::new(1, 0, GetSetOp::ListAll, GetSetPayloadType::None);
+        let size = request_bytes(&req, &mut buffer);
+        assert_eq!(
+            frame_len(&buffer[..size - 1]),
+            Err(params::Error::WireTruncated.into())
+        );
+        buffer[12] = 9;
+        assert_eq!(
+            frame_len(&buffer[..size]),
+            Err(params::Error::WireUnsupportedVersion.into())
+        );
+        buffer[0] = 0;
+        assert_eq!(
+            frame_len(&buffer[..size]),
+            Err(params::Error::WirePreamble.into())
+        );
+    }
 }
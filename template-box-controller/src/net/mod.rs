@@ -1,2 +1,3 @@
 pub mod eth;
 pub mod getset_protocol;
+pub mod reply;
@@ -0,0 +1,95 @@
+use params::{
+    Announce, Capabilities, DescribeResponse, Error, GetSetFlags, GetSetFrame, GetSetNodeId,
+    GetSetSequence, GetSetVersion, IdListResponse, Parameter, RefResponse, RemoteError, Response,
+    GETSET_VERSION_1,
+};
+
+/// Anything the node sends back as a whole GetSet frame
+pub trait Emit {
+    /// Emit the frame, returns its size on the wire
+    fn emit_frame(&self, frame: &mut GetSetFrame<&mut [u8]>) -> Result<usize, Error>;
+}
+
+impl Emit for Response {
+    fn emit_frame(&self, frame: &mut GetSetFrame<&mut [u8]>) -> Result<usize, Error> {
+        self.emit(frame)?;
+        Ok(self.wire_size())
+    }
+}
+
+impl<'a> Emit for IdListResponse<'a> {
+    fn emit_frame(&self, frame: &mut GetSetFrame<&mut [u8]>) -> Result<usize, Error> {
+        self.emit(frame)?;
+        Ok(self.wire_size())
+    }
+}
+
+impl<P: AsRef<[Parameter]>> Emit for RefResponse<P> {
+    fn emit_frame(&self, frame: &mut GetSetFrame<&mut [u8]>) -> Result<usize, Error> {
+        self.emit(frame)?;
+        Ok(self.wire_size())
+    }
+}
+
+impl<'a> Emit for DescribeResponse<'a> {
+    fn emit_frame(&self, frame: &mut GetSetFrame<&mut [u8]>) -> Result<usize, Error> {
+        self.emit(frame)?;
+        Ok(self.wire_size())
+    }
+}
+
+/// Payloads that are given the header's node ID and flags when emitted
+#[derive(Debug)]
+pub struct Framed<'a, T> {
+    node_id: GetSetNodeId,
+    flags: GetSetFlags,
+    version: GetSetVersion,
+    sequence: GetSetSequence,
+    payload: &'a T,
+}
+
+impl<'a, T> Framed<'a, T> {
+    pub fn new(node_id: GetSetNodeId, flags: GetSetFlags, payload: &'a T) -> Self {
+        Framed {
+            node_id,
+            flags,
+            version: GETSET_VERSION_1,
+            sequence: 0,
+            payload,
+        }
+    }
+
+    /// Replies answer in the version of the request
+    pub fn set_version(&mut self, version: GetSetVersion) {
+        self.version = version;
+    }
+
+    /// Replies echo the sequence number of the request
+    pub fn set_sequence(&mut self, sequence: GetSetSequence) {
+        self.sequence = sequence;
+    }
+}
+
+impl<'a> Emit for Framed<'a, Announce> {
+    fn emit_frame(&self, frame: &mut GetSetFrame<&mut [u8]>) -> Result<usize, Error> {
+        self.payload
+            .emit(self.node_id, self.flags, self.version, self.sequence, frame)?;
+        Ok(self.payload.wire_size(self.version, self.flags))
+    }
+}
+
+impl<'a> Emit for Framed<'a, Capabilities> {
+    fn emit_frame(&self, frame: &mut GetSetFrame<&mut [u8]>) -> Result<usize, Error> {
+        self.payload
+            .emit(self.node_id, self.flags, self.version, self.sequence, frame)?;
+        Ok(self.payload.wire_size(self.version, self.flags))
+    }
+}
+
+impl<'a> Emit for Framed<'a, RemoteError> {
+    fn emit_frame(&self, frame: &mut GetSetFrame<&mut [u8]>) -> Result<usize, Error> {
+        self.payload
+            .emit(self.node_id, self.flags, self.version, self.sequence, frame)?;
+        Ok(self.payload.wire_size(self.version, self.flags))
+    }
+}
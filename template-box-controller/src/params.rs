@@ -3,12 +3,18 @@
 use crate::error::Error;
 use crate::sys_clock;
 use crate::PARAM_EVENT_Q;
+use core::convert::TryFrom;
 use heapless::Vec;
 use log::debug;
+use param_desc::param_enum::{param_enum_name, param_enum_names};
 use params::{MaxParamsPerOp, Parameter, ParameterId, ParameterValue};
 
 pub struct Params {
     params: Vec<Parameter, MaxParamsPerOp>,
+    /// IDs a client subscribed to
+    subscriptions: Vec<ParameterId, MaxParamsPerOp>,
+    /// Subscribed IDs changed and not pushed yet
+    changed: Vec<ParameterId, MaxParamsPerOp>,
 }
 
 #[derive(Copy, Clone, PartialEq, PartialOrd, Debug)]
@@ -42,7 +48,11 @@ pub fn dequeue_event() -> Option<Event> {
 
 impl Params {
     pub fn new() -> Self {
-        Params { params: Vec::new() }
+        Params {
+            params: Vec::new(),
+            subscriptions: Vec::new(),
+            changed: Vec::new(),
+        }
     }
 
     pub fn add(&mut self, mut parameter: Parameter) -> Result<(), Error> {
@@ -68,26 +78,65 @@ impl Params {
         self.get(id).map(|p| p.value())
     }
 
+    pub fn get_value_as<T>(&self, id: ParameterId) -> Result<T, Error>
+    where
+        T: TryFrom<ParameterValue, Error = params::Error>,
+    {
+        let value = self.get_value(id).ok_or(Error::NotFound)?;
+        Ok(T::try_from(value)?)
+    }
+
     pub fn set(
         &mut self,
         id: ParameterId,
         value: ParameterValue,
         allow_ro: bool,
     ) -> Result<(), Error> {
-        self.params
+        let p = self
+            .params
             .iter_mut()
             .find(|p| p.id() == id)
-            .map_or(Err(Error::NotFound), |p| {
-                if !allow_ro && p.flags().read_only() {
-                    Err(Error::PermissionDenied)
-                } else if p.flags().constant() {
-                    Err(Error::PermissionDenied)
-                } else {
-                    p.set_value(value)?;
-                    p.set_local_time_ms(sys_clock::system_millis());
-                    Ok(())
-                }
-            })
+            .ok_or(Error::NotFound)?;
+        check_set(p, value, allow_ro)?;
+        let old = p.value();
+        p.set_value(value)?;
+        p.set_local_time_ms(sys_clock::system_millis());
+        if old != value && self.subscriptions.contains(&id) && !self.changed.contains(&id) {
+            // Holds at most the subscribed IDs, can't be full
+            let _ = self.changed.push(id);
+        }
+        Ok(())
+    }
+
+    /// Set the value only if the current value matches the expected one,
+    /// fails with Mismatch otherwise
+    pub fn compare_and_set(
+        &mut self,
+        id: ParameterId,
+        expected: ParameterValue,
+        value: ParameterValue,
+        allow_ro: bool,
+    ) -> Result<(), Error> {
+        self.validate(id, value, allow_ro)?;
+        if self.get_value(id) != Some(expected) {
+            return Err(Error::Mismatch);
+        }
+        self.set(id, value, allow_ro)
+    }
+
+    /// Check that set() would accept the value without applying it,
+    /// used to validate a whole batch before committing any of it
+    pub fn validate(
+        &self,
+        id: ParameterId,
+        value: ParameterValue,
+        allow_ro: bool,
+    ) -> Result<(), Error> {
+        let p = self.get(id).ok_or(Error::NotFound)?;
+        check_set(p, value, allow_ro)?;
+        let mut p = *p;
+        p.set_value(value)?;
+        Ok(())
     }
 
     pub fn process_event(&mut self, event: Event) -> Result<(), Error> {
@@ -96,6 +145,64 @@ impl Params {
         self.set(event.id, event.value, true)
     }
 
+    /// Track changes of the parameter, fails with NotFound for unknown IDs
+    pub fn subscribe(&mut self, id: ParameterId) -> Result<(), Error> {
+        if self.get(id).is_none() {
+            Err(Error::NotFound)
+        } else if self.subscriptions.contains(&id) {
+            Ok(())
+        } else {
+            self.subscriptions.push(id).map_err(|_| Error::Capacity)
+        }
+    }
+
+    pub fn unsubscribe(&mut self, id: ParameterId) {
+        remove_id(&mut self.subscriptions, id);
+        remove_id(&mut self.changed, id);
+    }
+
+    pub fn unsubscribe_all(&mut self) {
+        self.subscriptions.clear();
+        self.changed.clear();
+    }
+
+    pub fn subscriptions(&self) -> &[ParameterId] {
+        &self.subscriptions
+    }
+
+    /// IDs of the subscribed parameters changed and not pushed yet,
+    /// in the order they changed
+    pub fn changed(&self) -> &[ParameterId] {
+        &self.changed
+    }
+
+    /// The first `count` changes have been pushed
+    pub fn pushed(&mut self, count: usize) {
+        let count = count.min(self.changed.len());
+        let rest: Vec<ParameterId, MaxParamsPerOp> =
+            self.changed[count..].iter().cloned().collect();
+        self.changed = rest;
+    }
+
+    /// Parameters changed at or after the node time with IDs from `start_id`
+    /// on, in ID order so pages resuming after the last ID neither skip nor
+    /// repeat parameters that change in between. Changes made in the same
+    /// millisecond as `since_ms` are included, so a client passing the node
+    /// time of the last reply may get them twice.
+    pub fn changed_since(
+        &self,
+        since_ms: u64,
+        start_id: ParameterId,
+    ) -> Vec<&Parameter, MaxParamsPerOp> {
+        let mut changed: Vec<&Parameter, MaxParamsPerOp> = self
+            .params
+            .iter()
+            .filter(|p| p.local_time_ms() >= since_ms && p.id() >= start_id)
+            .collect();
+        changed.sort_unstable_by_key(|p| p.id());
+        changed
+    }
+
     pub fn get_all_broadcast(&self) -> &[Parameter] {
         // Expects to be sorted, broadcast flags up front
         let num_bcast = self
@@ -109,6 +216,35 @@ impl Params {
     }
 }
 
+/// Remove the ID, keeping the order of the others
+fn remove_id(ids: &mut Vec<ParameterId, MaxParamsPerOp>, id: ParameterId) {
+    if let Some(index) = ids.iter().position(|i| *i == id) {
+        ids[index..].rotate_left(1);
+        ids.pop();
+    }
+}
+
+/// Permission and range checks of a set, the value type is
+/// checked when the value is applied
+fn check_set(p: &Parameter, value: ParameterValue, allow_ro: bool) -> Result<(), Error> {
+    if !allow_ro && p.flags().read_only() {
+        Err(Error::PermissionDenied)
+    } else if p.flags().constant() {
+        Err(Error::PermissionDenied)
+    } else if enum_out_of_range(p, value) {
+        Err(Error::OutOfRange)
+    } else {
+        Ok(())
+    }
+}
+
+/// Enum parameters only accept indices of their variants
+fn enum_out_of_range(p: &Parameter, value: ParameterValue) -> bool {
+    param_enum_names(p.id()).is_some()
+        && p.value().type_id() == value.type_id()
+        && param_enum_name(p.id(), value).is_none()
+}
+
 impl AsRef<[Parameter]> for Params {
     fn as_ref(&self) -> &[Parameter] {
         self.params.as_ref()
@@ -153,6 +289,40 @@ mod tests {
         assert_eq!(params.get_all_broadcast().len(), 0);
     }
 
+    #[test]
+    fn changed_since() {
+        let mut params = Params::new();
+        for i in 1..=3 {
+            let p = Parameter::new_with_value(
+                ParameterId::new(i),
+                ParameterFlags::new_read_only(),
+                ParameterValue::U8(i as u8),
+            );
+            assert_eq!(params.add(p), Ok(()));
+        }
+        for p in params.params.iter_mut() {
+            p.set_local_time_ms(u64::from(u32::from(p.id())) * 10);
+        }
+        assert_eq!(params.changed_since(0, ParameterId::new(0)).len(), 3);
+        assert_eq!(params.changed_since(31, ParameterId::new(0)).len(), 0);
+
+        // A change at exactly the requested time is included, in ID order
+        let ids: Vec<ParameterId, MaxParamsPerOp> = params
+            .changed_since(20, ParameterId::new(0))
+            .iter()
+            .map(|p| p.id())
+            .collect();
+        assert_eq!(&ids[..], &[ParameterId::new(2), ParameterId::new(3)]);
+
+        // Pages resume after the last ID
+        let ids: Vec<ParameterId, MaxParamsPerOp> = params
+            .changed_since(0, ParameterId::new(2))
+            .iter()
+            .map(|p| p.id())
+            .collect();
+        assert_eq!(&ids[..], &[ParameterId::new(2), ParameterId::new(3)]);
+    }
+
     #[test]
     fn add_duplicate_error() {
         let p = Parameter::new_with_value(
@@ -226,6 +396,139 @@ mod tests {
         );
     }
 
+    #[test]
+    fn set_enum_out_of_range_error() {
+        let p = param_desc::param::HVAC_MODE;
+        let mut params = Params::new();
+        assert_eq!(params.add(p), Ok(()));
+        assert_eq!(
+            params.set(p.id(), ParameterValue::U8(200), false),
+            Err(Error::OutOfRange)
+        );
+        assert_eq!(params.set(p.id(), ParameterValue::U8(1), false), Ok(()));
+        assert_eq!(
+            Error::OutOfRange.set_status(),
+            params::SetStatus::OutOfRange
+        );
+    }
+
+    #[test]
+    fn validate_doesnt_apply() {
+        let p = Parameter::new_with_value(
+            ParameterId::new(1),
+            ParameterFlags::default(),
+            ParameterValue::U8(123),
+        );
+        let mut params = Params::new();
+        assert_eq!(params.add(p), Ok(()));
+        assert_eq!(
+            params.validate(p.id(), ParameterValue::U8(2), false),
+            Ok(())
+        );
+        assert_eq!(params.get_value(p.id()), Some(ParameterValue::U8(123)));
+        assert_eq!(
+            params.validate(p.id(), ParameterValue::Bool(false), false),
+            Err(Error::ParamsError(params::Error::ValueTypeMismatch))
+        );
+        assert_eq!(
+            params.validate(ParameterId::new(2), ParameterValue::U8(2), false),
+            Err(Error::NotFound)
+        );
+    }
+
+    #[test]
+    fn compare_and_set() {
+        let p = Parameter::new_with_value(
+            ParameterId::new(1),
+            ParameterFlags::default(),
+            ParameterValue::U8(123),
+        );
+        let mut params = Params::new();
+        assert_eq!(params.add(p), Ok(()));
+        assert_eq!(
+            params.compare_and_set(p.id(), ParameterValue::U8(1), ParameterValue::U8(2), false),
+            Err(Error::Mismatch)
+        );
+        assert_eq!(params.get_value(p.id()), Some(ParameterValue::U8(123)));
+        assert_eq!(
+            params.compare_and_set(
+                p.id(),
+                ParameterValue::U8(123),
+                ParameterValue::U8(2),
+                false
+            ),
+            Ok(())
+        );
+        assert_eq!(params.get_value(p.id()), Some(ParameterValue::U8(2)));
+        assert_eq!(
+            params.compare_and_set(
+                p.id(),
+                ParameterValue::U8(2),
+                ParameterValue::Bool(true),
+                false
+            ),
+            Err(Error::ParamsError(params::Error::ValueTypeMismatch))
+        );
+        assert_eq!(Error::Mismatch.set_status(), params::SetStatus::Mismatch);
+    }
+
+    #[test]
+    fn subscribe_tracks_changes() {
+        let p1 = Parameter::new_with_value(
+            ParameterId::new(1),
+            ParameterFlags::default(),
+            ParameterValue::U8(123),
+        );
+        let p2 = Parameter::new_with_value(
+            ParameterId::new(2),
+            ParameterFlags::new_read_only(),
+            ParameterValue::U32(0),
+        );
+        let mut params = Params::new();
+        assert_eq!(params.add(p1), Ok(()));
+        assert_eq!(params.add(p2), Ok(()));
+        assert_eq!(params.subscribe(ParameterId::new(3)), Err(Error::NotFound));
+        assert_eq!(params.subscribe(p1.id()), Ok(()));
+        assert_eq!(params.subscribe(p1.id()), Ok(()));
+        assert_eq!(params.subscribe(p2.id()), Ok(()));
+        assert_eq!(params.subscriptions(), &[p1.id(), p2.id()]);
+
+        // Unchanged values and failed sets aren't changes
+        assert_eq!(params.set(p1.id(), ParameterValue::U8(123), false), Ok(()));
+        assert_eq!(
+            params.set(p2.id(), ParameterValue::U32(1), false),
+            Err(Error::PermissionDenied)
+        );
+        assert!(params.changed().is_empty());
+
+        assert_eq!(
+            params.process_event((p2.id(), ParameterValue::U32(1)).into()),
+            Ok(())
+        );
+        assert_eq!(params.set(p1.id(), ParameterValue::U8(1), false), Ok(()));
+        assert_eq!(params.set(p1.id(), ParameterValue::U8(2), false), Ok(()));
+        assert_eq!(params.changed(), &[p2.id(), p1.id()]);
+
+        // Changes stay pending until pushed
+        params.pushed(1);
+        assert_eq!(params.changed(), &[p1.id()]);
+        params.pushed(1);
+        assert!(params.changed().is_empty());
+
+        params.unsubscribe(p1.id());
+        assert_eq!(params.set(p1.id(), ParameterValue::U8(3), false), Ok(()));
+        assert!(params.changed().is_empty());
+        assert_eq!(params.subscriptions(), &[p2.id()]);
+
+        assert_eq!(
+            params.process_event((p2.id(), ParameterValue::U32(2)).into()),
+            Ok(())
+        );
+        params.unsubscribe_all();
+        assert!(params.changed().is_empty());
+        assert!(params.subscriptions().is_empty());
+    }
+
     #[test]
     fn set_updates_value() {
         let p = Parameter::new_with_value(
@@ -241,6 +544,26 @@ mod tests {
         assert_eq!(params.get_value(p.id()), Some(ParameterValue::U8(2)));
     }
 
+    #[test]
+    fn get_value_as() {
+        let p = Parameter::new_with_value(
+            ParameterId::new(1),
+            ParameterFlags::default(),
+            ParameterValue::U8(123),
+        );
+        let mut params = Params::new();
+        assert_eq!(params.add(p), Ok(()));
+        assert_eq!(params.get_value_as::<u8>(p.id()), Ok(123));
+        assert_eq!(
+            params.get_value_as::<bool>(p.id()),
+            Err(Error::ParamsError(params::Error::ValueTypeMismatch))
+        );
+        assert_eq!(
+            params.get_value_as::<u8>(ParameterId::new(2)),
+            Err(Error::NotFound)
+        );
+    }
+
     #[test]
     fn sorted_by_bcast_flag() {
         let mut params = Params::new();